@@ -0,0 +1,81 @@
+// point lookups over 100k rows, scanning the rows against going through a B-tree and a hash index;
+// run with `cargo bench -p core --bench index_lookup`
+use std::hint::black_box;
+use std::time::Instant;
+use core::row::Row;
+use core::scheme::{ColumnDefinition, Scheme};
+use core::index::{format_memory_size, IndexKind};
+use core::table::Table;
+use core::types::{CellValue, ValueType, SUPPORTED_TYPES};
+
+const ROWS: i64 = 100_000;
+const LOOKUPS: i64 = 1_000;
+
+fn build_table() -> Table {
+    let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+    let scheme = Scheme::<dyn CellValue>::builder()
+        .with_column_definition(column("id", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+        .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+        .build();
+    let table = Table::new("people".to_string(), scheme);
+    for id in 0..ROWS {
+        let scheme = table.get_scheme();
+        table.add_row(Row::new(vec![
+            scheme.generate_value(0, id.to_string()).unwrap(),
+            scheme.generate_value(1, format!("person {}", id)).unwrap(),
+        ]));
+    }
+    table
+}
+
+// spreads the looked up ids over the whole table
+fn get_id(lookup: i64) -> i64 {
+    lookup * 7919 % ROWS
+}
+
+fn scan(table: &Table, id: i64) -> Vec<usize> {
+    table.get_rows()
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| matches!(row.get_values()[0].get_value(), ValueType::Int(value) if value.get_value() == id))
+        .map(|(position, _)| position)
+        .collect()
+}
+
+fn main() {
+    let table = build_table();
+    let cell = |id: i64| table.get_scheme().generate_value(0, id.to_string()).unwrap().get_value();
+
+    let start = Instant::now();
+    for lookup in 0..LOOKUPS {
+        black_box(scan(&table, get_id(lookup)));
+    }
+    let scanned = start.elapsed();
+
+    println!("{} point lookups over {} rows", LOOKUPS, ROWS);
+    println!("scan:  {:>10.3} ms, {:>8.2} us per lookup", scanned.as_secs_f64() * 1e3, scanned.as_secs_f64() * 1e6 / LOOKUPS as f64);
+
+    for kind in [IndexKind::BTree, IndexKind::Hash] {
+        let start = Instant::now();
+        table.add_index("by_id", vec!["id".to_string()], kind).unwrap();
+        let built = start.elapsed();
+
+        let start = Instant::now();
+        for lookup in 0..LOOKUPS {
+            black_box(table.find_rows("id", &cell(get_id(lookup))).unwrap());
+        }
+        let indexed = start.elapsed();
+        for lookup in 0..10 {
+            assert_eq!(table.find_rows("id", &cell(get_id(lookup))).unwrap(), scan(&table, get_id(lookup)));
+        }
+        println!(
+            "{:<6} {:>10.3} ms, {:>8.2} us per lookup (built in {:.3} ms, takes {})",
+            format!("{}:", kind.to_string().to_lowercase()),
+            indexed.as_secs_f64() * 1e3,
+            indexed.as_secs_f64() * 1e6 / LOOKUPS as f64,
+            built.as_secs_f64() * 1e3,
+            format_memory_size(table.get_indexes_memory_size())
+        );
+        table.drop_index("by_id").unwrap();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use crate::scheme::ColumnDefinition;
+use crate::types::{CellValue, ValueType};
+
+// what happens to the rows whose cell can't be converted into the new type of the column
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnConversionFailure {
+    #[default]
+    Abort,
+    SetNull,
+    DropRow,
+}
+
+impl OnConversionFailure {
+    pub fn parse(raw_value: &str) -> Result<Self, String> {
+        match raw_value.trim().to_lowercase().replace(['_', '-'], " ").as_str() {
+            "abort" => Ok(OnConversionFailure::Abort),
+            "null" | "set null" => Ok(OnConversionFailure::SetNull),
+            "drop" | "drop row" => Ok(OnConversionFailure::DropRow),
+            _ => Err(format!("unknown conversion failure action: {}, expected abort, null or drop", raw_value)),
+        }
+    }
+}
+
+impl Display for OnConversionFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnConversionFailure::Abort => write!(f, "ABORT"),
+            OnConversionFailure::SetNull => write!(f, "SET NULL"),
+            OnConversionFailure::DropRow => write!(f, "DROP ROW"),
+        }
+    }
+}
+
+// how converting a column went, or would go on a dry run
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub column: String,
+    pub rows: usize,
+    // index of the row and why its cell can't be converted
+    pub failures: Vec<(usize, String)>,
+    pub on_failure: OnConversionFailure,
+}
+
+impl ConversionReport {
+    pub fn get_failed_rows(&self) -> Vec<usize> {
+        self.failures.iter().map(|(index, _)| *index).collect()
+    }
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl Display for ConversionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.failures.is_empty() {
+            return write!(f, "all {} rows of column {} can be converted", self.rows, self.column);
+        }
+        write!(
+            f,
+            "{} of {} rows of column {} can't be converted ({}): ",
+            self.failures.len(),
+            self.rows,
+            self.column,
+            self.on_failure
+        )?;
+        let failures = self.failures.iter()
+            .map(|(index, reason)| format!("row {}: {}", index, reason))
+            .collect::<Vec<String>>();
+        write!(f, "{}", failures.join("; "))
+    }
+}
+
+// builds the cell of the column described by `definition` out of a cell of another type
+pub fn convert_value(value: &Rc<dyn CellValue>, definition: &ColumnDefinition) -> Result<Rc<dyn CellValue>, String> {
+    let value = value.get_value();
+    if let ValueType::Null(_) = value {
+        return definition.build_null_value();
+    }
+    match value.to_raw_value() {
+        Some(raw_value) => definition.build_value(raw_value),
+        None => Err(format!("the value can't be converted into {}", definition.ty)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use crate::conversion::{convert_value, ConversionReport, OnConversionFailure};
+    use crate::scheme::ColumnDefinition;
+    use crate::types::{CellValue, ValueType, SUPPORTED_TYPES};
+    use crate::types::null_value::NullValue;
+
+    fn value(ty: &str, raw_value: &str) -> Rc<dyn CellValue> {
+        SUPPORTED_TYPES.get(ty).unwrap()(raw_value.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_on_conversion_failure() {
+        assert_eq!(OnConversionFailure::parse("ABORT"), Ok(OnConversionFailure::Abort));
+        assert_eq!(OnConversionFailure::parse("null"), Ok(OnConversionFailure::SetNull));
+        assert_eq!(OnConversionFailure::parse("drop_row"), Ok(OnConversionFailure::DropRow));
+        assert!(OnConversionFailure::parse("ignore").is_err());
+    }
+
+    #[test]
+    fn test_convert_value() {
+        let int = ColumnDefinition::parse("age", "IntValue range(0, 120)").unwrap();
+        match convert_value(&value("StringValue", "42"), &int).unwrap().get_value() {
+            ValueType::Int(value) => assert_eq!(value.get_value(), 42),
+            _ => unreachable!(),
+        }
+        assert!(convert_value(&value("StringValue", "forty two"), &int).is_err());
+        assert!(convert_value(&value("StringValue", "200"), &int).is_err());
+        assert!(convert_value(&(Rc::new(NullValue::new()) as Rc<dyn CellValue>), &int).is_ok());
+
+        let string = ColumnDefinition::parse("name", "StringValue not_null").unwrap();
+        assert!(convert_value(&(Rc::new(NullValue::new()) as Rc<dyn CellValue>), &string).is_err());
+        let date = value("DateValue", "Dec  5, 2004 21:16:24.317453000 EET");
+        let date_string = convert_value(&date, &string).unwrap();
+        let date_definition = ColumnDefinition::parse("created", "DateValue").unwrap();
+        match (convert_value(&date_string, &date_definition).unwrap().get_value(), date.get_value()) {
+            (ValueType::Date(converted), ValueType::Date(original)) => assert_eq!(converted, original),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_display_report() {
+        let mut report = ConversionReport {
+            column: "age".to_string(),
+            rows: 3,
+            failures: Vec::default(),
+            on_failure: OnConversionFailure::SetNull,
+        };
+        assert_eq!(report.to_string(), "all 3 rows of column age can be converted");
+        report.failures.push((1, "validation has failed: old".to_string()));
+        assert_eq!(
+            report.to_string(),
+            "1 of 3 rows of column age can't be converted (SET NULL): row 1: validation has failed: old"
+        );
+    }
+}
\ No newline at end of file
@@ -1,21 +1,61 @@
-use std::cell::{Ref, RefCell, RefMut};
-use std::collections::HashMap;
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::fmt::Debug;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::conversion::{ConversionReport, OnConversionFailure};
+use crate::foreign_key::{ForeignKey, OnDelete};
+use crate::row::Row;
+use crate::scheme::{ColumnDefinition, Constraint};
 use crate::table::Table;
+use crate::types::{CellValue, ValueType};
+use crate::types::null_value::NullValue;
+use crate::view::{MaterializedView, View};
+
+// rows the loaded tables hold before the ones that are as stored are unloaded again
+pub const LOADED_ROWS_LIMIT: usize = 1_000_000;
+
+// reads the tables a database was opened without, see `Database::set_unloaded_tables`
+pub trait TableLoader: Debug {
+    fn load_table(&self, table_name: &str) -> Result<Table, String>;
+    // the version the table was stored with, without loading it
+    fn get_version(&self, table_name: &str) -> Option<u64>;
+}
 
 #[derive(Debug)]
 pub struct Database {
     pub name: String,
     pub location: String,
     pub tables: RefCell<HashMap<String, Table>>,
+    pub foreign_keys: RefCell<Vec<ForeignKey>>,
+    // in the order they were created
+    pub views: RefCell<Vec<View>>,
+    pub materialized_views: RefCell<Vec<MaterializedView>>,
+    // the tables that are still where `loader` reads them from, sorted by name
+    pub unloaded_tables: RefCell<Vec<String>>,
+    loader: RefCell<Option<Box<dyn TableLoader>>>,
+    // the tables `loader` read, least recently used first, and how many rows they may hold together
+    recent_tables: RefCell<Vec<String>>,
+    loaded_rows_limit: Cell<usize>,
 }
 
+// rows a deletion removes, by table, and the (table, row, columns) it sets to NULL
+type DeletionPlan = (HashMap<String, BTreeSet<usize>>, Vec<(String, usize, Vec<String>)>);
+
 impl Database {
     pub fn new(name: String, location: String) -> Self {
         Self {
             name,
             location,
             tables: RefCell::new(HashMap::default()),
+            foreign_keys: RefCell::new(Vec::default()),
+            views: RefCell::new(Vec::default()),
+            materialized_views: RefCell::new(Vec::default()),
+            unloaded_tables: RefCell::new(Vec::default()),
+            loader: RefCell::new(None),
+            recent_tables: RefCell::new(Vec::default()),
+            loaded_rows_limit: Cell::new(LOADED_ROWS_LIMIT),
         }
     }
     pub fn builder() -> DatabaseBuilder {
@@ -28,16 +68,717 @@ impl Database {
         self.location.as_str()
     }
 
-    pub fn get_tables(&self) -> Ref<HashMap<String, Table>> {
+    // every table, loading the ones that weren't yet; fails if one of them can't be read
+    pub fn get_tables(&self) -> Result<Ref<'_, HashMap<String, Table>>, String> {
+        self.load_tables()?;
+        Ok(self.tables.borrow())
+    }
+    pub fn get_tables_mut(&self) -> Result<RefMut<'_, HashMap<String, Table>>, String> {
+        self.load_tables()?;
+        Ok(self.tables.borrow_mut())
+    }
+    // the tables in memory, none is loaded for them
+    pub fn get_loaded_tables(&self) -> Ref<'_, HashMap<String, Table>> {
         self.tables.borrow()
     }
-    pub fn get_tables_mut(&self) -> RefMut<HashMap<String, Table>> {
-        self.tables.borrow_mut()
+    // only the tables asked for are loaded; fails if one of them doesn't exist or can't be read
+    pub fn get_tables_named(&self, table_names: &[&str]) -> Result<Ref<'_, HashMap<String, Table>>, String> {
+        self.load_tables_named(table_names)?;
+        Ok(self.tables.borrow())
+    }
+    // the table with the ones its foreign keys refer to, which checking a new row of it reads
+    pub fn get_table_with_referenced(&self, table_name: &str) -> Result<Ref<'_, HashMap<String, Table>>, String> {
+        let foreign_keys = self.get_foreign_keys();
+        let mut table_names = vec![table_name];
+        table_names.extend(foreign_keys.iter()
+            .filter(|foreign_key| foreign_key.get_table() == table_name)
+            .map(|foreign_key| foreign_key.get_referenced_table()));
+        self.get_tables_named(&table_names)
+    }
+    // as well as the ones whose foreign keys refer to it, which checking a changed row reads
+    pub fn get_table_with_references(&self, table_name: &str) -> Result<Ref<'_, HashMap<String, Table>>, String> {
+        let foreign_keys = self.get_foreign_keys();
+        let mut table_names = vec![table_name];
+        table_names.extend(foreign_keys.iter()
+            .filter_map(|foreign_key| match (foreign_key.get_table() == table_name, foreign_key.get_referenced_table() == table_name) {
+                (true, _) => Some(foreign_key.get_referenced_table()),
+                (_, true) => Some(foreign_key.get_table()),
+                _ => None,
+            }));
+        self.get_tables_named(&table_names)
+    }
+    // loads only the table asked for
+    pub fn get_table(&self, table_name: &str) -> Result<Ref<'_, Table>, String> {
+        self.load_table(table_name)?;
+        Ref::filter_map(self.tables.borrow(), |tables| tables.get(table_name))
+            .map_err(|_| format!("There is no table with name {}", table_name))
+    }
+    pub fn get_table_mut(&self, table_name: &str) -> Result<RefMut<'_, Table>, String> {
+        self.load_table(table_name)?;
+        RefMut::filter_map(self.tables.borrow_mut(), |tables| tables.get_mut(table_name))
+            .map_err(|_| format!("There is no table with name {}", table_name))
+    }
+    pub fn has_table(&self, table_name: &str) -> bool {
+        self.tables.borrow().contains_key(table_name) || self.unloaded_tables.borrow().binary_search(&table_name.to_string()).is_ok()
+    }
+    // the version of the table, see `Table::get_version`; an unloaded table isn't loaded for it
+    pub fn get_table_version(&self, table_name: &str) -> Option<u64> {
+        if let Some(table) = self.tables.borrow().get(table_name) {
+            return Some(table.get_version());
+        }
+        if self.unloaded_tables.borrow().binary_search(&table_name.to_string()).is_err() {
+            return None;
+        }
+        self.loader.borrow().as_ref().and_then(|loader| loader.get_version(table_name))
+    }
+    // the loaded tables and the ones still to be
+    pub fn get_table_names(&self) -> Vec<String> {
+        let mut names = self.tables.borrow().keys().cloned().collect::<Vec<String>>();
+        names.extend(self.unloaded_tables.borrow().iter().cloned());
+        names
     }
 
     pub fn set_tables(&self, tables: HashMap<String, Table>) {
         *self.tables.borrow_mut() = tables;
     }
+    // the tables are read by `loader` the first time they are needed
+    pub fn set_unloaded_tables(&self, mut table_names: Vec<String>, loader: Box<dyn TableLoader>) {
+        table_names.sort();
+        *self.unloaded_tables.borrow_mut() = table_names;
+        *self.loader.borrow_mut() = Some(loader);
+    }
+    pub fn set_loaded_rows_limit(&self, limit: usize) {
+        self.loaded_rows_limit.set(limit);
+    }
+    // loads the table if it wasn't, making room for it by unloading the ones used least recently
+    pub fn load_table(&self, table_name: &str) -> Result<(), String> {
+        let loaded = self.read_table(table_name)?;
+        if loaded || self.recent_tables.borrow().iter().any(|name| name == table_name) {
+            let mut recent_tables = self.recent_tables.borrow_mut();
+            recent_tables.retain(|name| name != table_name);
+            recent_tables.push(table_name.to_string());
+        }
+        self.unload_stored_tables(table_name);
+        Ok(())
+    }
+    // adds the table, or replaces the one with its name
+    pub fn add_table(&self, table: Table) {
+        if let Ok(position) = self.unloaded_tables.borrow().binary_search(&table.name) {
+            self.unloaded_tables.borrow_mut().remove(position);
+        }
+        self.tables.borrow_mut().insert(table.name.clone(), table);
+    }
+    // true if the table had to be read
+    fn read_table(&self, table_name: &str) -> Result<bool, String> {
+        let position = match self.unloaded_tables.borrow().binary_search(&table_name.to_string()) {
+            Ok(position) => position,
+            Err(_) => return Ok(false),
+        };
+        let table = match self.loader.borrow().as_ref() {
+            Some(loader) => loader.load_table(table_name)?,
+            None => return Err(format!("There is nothing to load table {} with", table_name)),
+        };
+        self.unloaded_tables.borrow_mut().remove(position);
+        self.tables.borrow_mut().insert(table_name.to_string(), table);
+        Ok(true)
+    }
+    // a table that can't be read fails the whole call, it stays where it is so saving the database
+    // keeps it as it was
+    fn load_tables(&self) -> Result<(), String> {
+        let table_names = self.unloaded_tables.borrow().clone();
+        for table_name in table_names {
+            self.read_table(&table_name)?;
+        }
+        Ok(())
+    }
+    // nothing is unloaded to make room for them, as the caller needs them all at once
+    fn load_tables_named(&self, table_names: &[&str]) -> Result<(), String> {
+        for table_name in table_names {
+            if !self.has_table(table_name) {
+                return Err(format!("There is no table with name {}", table_name));
+            }
+            if self.read_table(table_name)? {
+                self.recent_tables.borrow_mut().push(table_name.to_string());
+            }
+        }
+        Ok(())
+    }
+    // the tables and the ones whose foreign keys refer to them, directly or through other tables,
+    // which deleting rows of them may reach
+    fn get_referencing_table_names(&self, table_names: &[&str]) -> Vec<String> {
+        let mut referencing = table_names.iter().map(|table_name| table_name.to_string()).collect::<Vec<String>>();
+        let foreign_keys = self.get_foreign_keys();
+        let mut position = 0;
+        while position < referencing.len() {
+            let table_name = referencing[position].clone();
+            for foreign_key in foreign_keys.iter().filter(|foreign_key| foreign_key.get_referenced_table() == table_name) {
+                if !referencing.iter().any(|name| name == foreign_key.get_table()) {
+                    referencing.push(foreign_key.get_table().to_string());
+                }
+            }
+            position += 1;
+        }
+        referencing
+    }
+    // only a table that is as stored can be read again, the changed ones stay whatever they hold;
+    // nothing is unloaded while the tables are borrowed
+    fn unload_stored_tables(&self, kept_table: &str) {
+        let mut tables = match self.tables.try_borrow_mut() {
+            Ok(tables) => tables,
+            Err(_) => return,
+        };
+        let limit = self.loaded_rows_limit.get();
+        let mut rows = tables.values().map(|table| table.get_rows().len()).sum::<usize>();
+        if rows <= limit {
+            return;
+        }
+        let mut unloaded_tables = self.unloaded_tables.borrow_mut();
+        self.recent_tables.borrow_mut().retain(|table_name| {
+            let table = match tables.get(table_name) {
+                Some(table) => table,
+                None => return false,
+            };
+            if rows <= limit || table_name == kept_table || !table.is_stored() {
+                return true;
+            }
+            rows -= table.get_rows().len();
+            tables.remove(table_name);
+            if let Err(position) = unloaded_tables.binary_search(table_name) {
+                unloaded_tables.insert(position, table_name.clone());
+            }
+            false
+        });
+    }
+
+    pub fn get_foreign_keys(&self) -> Ref<'_, Vec<ForeignKey>> {
+        self.foreign_keys.borrow()
+    }
+    pub fn set_foreign_keys(&self, foreign_keys: Vec<ForeignKey>) {
+        *self.foreign_keys.borrow_mut() = foreign_keys;
+    }
+
+    pub fn get_views(&self) -> Ref<'_, Vec<View>> {
+        self.views.borrow()
+    }
+    pub fn set_views(&self, views: Vec<View>) {
+        *self.views.borrow_mut() = views;
+    }
+    pub fn get_view(&self, view_name: &str) -> Result<View, String> {
+        match self.get_views().iter().find(|view| view.get_name() == view_name) {
+            Some(view) => Ok(view.clone()),
+            None => Err(format!("There is no view with name {}", view_name)),
+        }
+    }
+    // the columns of the view are checked by whoever builds its source, see `View::check`
+    pub fn add_view(&self, view: View) -> Result<(), String> {
+        self.check_new_view(&view)?;
+        self.views.borrow_mut().push(view);
+        Ok(())
+    }
+    // true if there is a plain or a materialized view with the name
+    pub fn has_view(&self, view_name: &str) -> bool {
+        self.get_view(view_name).is_ok() || self.get_materialized_view(view_name).is_ok()
+    }
+    // drops the view whether it's materialized or not
+    pub fn drop_view(&self, view_name: &str) -> Result<(), String> {
+        if !self.has_view(view_name) {
+            return Err(format!("There is no view with name {}", view_name));
+        }
+        self.views.borrow_mut().retain(|view| view.get_name() != view_name);
+        self.materialized_views.borrow_mut().retain(|view| view.get_name() != view_name);
+        Ok(())
+    }
+
+    pub fn get_materialized_views(&self) -> Ref<'_, Vec<MaterializedView>> {
+        self.materialized_views.borrow()
+    }
+    pub fn get_materialized_views_mut(&self) -> RefMut<'_, Vec<MaterializedView>> {
+        self.materialized_views.borrow_mut()
+    }
+    pub fn set_materialized_views(&self, materialized_views: Vec<MaterializedView>) {
+        *self.materialized_views.borrow_mut() = materialized_views;
+    }
+    pub fn get_materialized_view(&self, view_name: &str) -> Result<MaterializedView, String> {
+        match self.get_materialized_views().iter().find(|view| view.get_name() == view_name) {
+            Some(view) => Ok(view.clone()),
+            None => Err(format!("There is no materialized view with name {}", view_name)),
+        }
+    }
+    // the rows of the view are taken by whoever evaluates it, as for `add_view`
+    pub fn add_materialized_view(&self, materialized_view: MaterializedView) -> Result<(), String> {
+        self.check_new_view(materialized_view.get_view())?;
+        self.materialized_views.borrow_mut().push(materialized_view);
+        Ok(())
+    }
+    // replaces the materialized view of the same name, e.g. after it's refreshed
+    pub fn update_materialized_view(&self, materialized_view: MaterializedView) -> Result<(), String> {
+        let mut materialized_views = self.materialized_views.borrow_mut();
+        match materialized_views.iter_mut().find(|view| view.get_name() == materialized_view.get_name()) {
+            Some(view) => {
+                *view = materialized_view;
+                Ok(())
+            },
+            None => Err(format!("There is no materialized view with name {}", materialized_view.get_name())),
+        }
+    }
+    // names of the materialized views whose rows may change together with the table: the ones reading
+    // it and the ones reading the tables referencing it, which its deletions reach through foreign keys
+    pub fn get_materialized_views_depending_on(&self, table_name: &str) -> Vec<String> {
+        let mut affected = vec![table_name.to_string()];
+        let mut queue = VecDeque::from([table_name.to_string()]);
+        while let Some(table) = queue.pop_front() {
+            for foreign_key in self.get_foreign_keys().iter().filter(|foreign_key| foreign_key.get_referenced_table() == table) {
+                if !affected.iter().any(|name| name == foreign_key.get_table()) {
+                    affected.push(foreign_key.get_table().to_string());
+                    queue.push_back(foreign_key.get_table().to_string());
+                }
+            }
+        }
+        self.get_materialized_views()
+            .iter()
+            .filter(|view| affected.iter().any(|table| view.get_view().uses_table(table)))
+            .map(|view| view.get_name().to_string())
+            .collect()
+    }
+    // flags the materialized views whose sources are gone, say from a file edited by hand, or have
+    // changed since the rows of the view were taken, which the versions of the tables tell
+    pub fn check_materialized_views(&self) {
+        for view in self.materialized_views.borrow_mut().iter_mut() {
+            let missing = view.get_view().get_source().get_tables().iter().any(|table| !self.has_table(table));
+            let changed = view.get_source_versions()
+                .iter()
+                .any(|(table, version)| self.get_table_version(table) != Some(*version));
+            if missing || changed {
+                view.stale = true;
+            }
+        }
+    }
+    fn check_new_view(&self, view: &View) -> Result<(), String> {
+        if view.get_name().is_empty() {
+            return Err("view name can't be empty".to_string());
+        }
+        if self.has_table(view.get_name()) {
+            return Err(format!("table {} already exists", view.get_name()));
+        }
+        if self.has_view(view.get_name()) {
+            return Err(format!("view {} already exists", view.get_name()));
+        }
+        if let Some(table) = view.get_source().get_tables().into_iter().find(|table| !self.has_table(table)) {
+            return Err(format!("There is no table with name {}", table));
+        }
+        Ok(())
+    }
+    // name of a view, materialized or not, reading the table, or the column of it if one is given
+    fn find_view_using(&self, table_name: &str, column: Option<&str>) -> Option<String> {
+        let materialized_views = self.get_materialized_views();
+        self.get_views()
+            .iter()
+            .chain(materialized_views.iter().map(|view| view.get_view()))
+            .find(|view| view.uses_table(table_name)
+                && column.is_none_or(|column| view.get_used_columns().iter().any(|used| used == column)))
+            .map(|view| view.get_name().to_string())
+    }
+
+    // registers the foreign key once the existing rows satisfy it
+    pub fn add_foreign_key(&self, foreign_key: ForeignKey) -> Result<(), String> {
+        let tables = self.get_tables_named(&[foreign_key.get_table(), foreign_key.get_referenced_table()])?;
+        {
+            let table = &tables[foreign_key.get_table()];
+            let referenced_table = &tables[foreign_key.get_referenced_table()];
+            if foreign_key.get_columns().is_empty() || foreign_key.get_columns().len() != foreign_key.get_referenced_columns().len() {
+                return Err(format!("{}: expected the same non zero number of columns on both sides", foreign_key));
+            }
+            let scheme = table.get_scheme();
+            let referenced_scheme = referenced_table.get_scheme();
+            let referenced_columns = foreign_key.get_referenced_columns();
+            if referenced_scheme.get_primary_key() != referenced_columns
+                && !referenced_scheme.get_unique().iter().any(|columns| columns == referenced_columns) {
+                return Err(format!("{}: referenced columns are neither the primary key nor unique", foreign_key));
+            }
+            for (column, referenced_column) in foreign_key.get_columns().iter().zip(referenced_columns) {
+                let index = match scheme.get_column_index(column) {
+                    Some(index) => index,
+                    None => return Err(format!("There is no column {} in table {}", column, foreign_key.get_table())),
+                };
+                let referenced_index = referenced_scheme.get_column_index(referenced_column).unwrap();
+                if scheme.get_types()[index] != referenced_scheme.get_types()[referenced_index] {
+                    return Err(format!("{}: {} and {} have different types", foreign_key, column, referenced_column));
+                }
+                let not_null = scheme.get_column_constraints(index).contains(&Constraint::NotNull)
+                    || scheme.get_primary_key().contains(column);
+                if foreign_key.get_on_delete() == OnDelete::SetNull && (not_null || scheme.is_computed_column(index)) {
+                    return Err(format!("{}: column {} can't be set to null", foreign_key, column));
+                }
+            }
+            if self.get_foreign_keys().iter().any(|existing| {
+                existing.get_table() == foreign_key.get_table() && existing.get_columns() == foreign_key.get_columns()
+            }) {
+                return Err(format!("columns ({}) of {} already reference a table", foreign_key.get_columns().join(", "), foreign_key.get_table()));
+            }
+        }
+        for row in tables[foreign_key.get_table()].get_rows().iter() {
+            self.check_reference(&foreign_key, row)?;
+        }
+        drop(tables);
+        self.foreign_keys.borrow_mut().push(foreign_key);
+        Ok(())
+    }
+
+    // fails if the row of `table_name` refers to a row that doesn't exist
+    pub fn check_references(&self, table_name: &str, row: &Row<dyn CellValue>) -> Result<(), String> {
+        for foreign_key in self.get_foreign_keys().iter().filter(|foreign_key| foreign_key.get_table() == table_name) {
+            self.check_reference(foreign_key, row)?;
+        }
+        Ok(())
+    }
+
+    // fails if replacing the row `index` of `table_name` with `new_row` would change a key other rows
+    // refer to, or make the new row refer to nothing
+    pub fn check_update_references(&self, table_name: &str, index: usize, new_row: &Row<dyn CellValue>) -> Result<(), String> {
+        self.check_references(table_name, new_row)?;
+        let tables = self.get_table_with_references(table_name)?;
+        let table = &tables[table_name];
+        let old_row = match table.get_rows().get(index) {
+            Some(row) => row.clone(),
+            None => return Ok(()),
+        };
+        for foreign_key in self.get_foreign_keys().iter().filter(|foreign_key| foreign_key.get_referenced_table() == table_name) {
+            let old_values = table.get_key_values(&old_row, foreign_key.get_referenced_columns());
+            if old_values == table.get_key_values(new_row, foreign_key.get_referenced_columns()) {
+                continue;
+            }
+            // the referencing columns are looked up in their key index if they make a key
+            let referencing_table = &tables[foreign_key.get_table()];
+            let referencing = referencing_table.find_by_key(foreign_key.get_columns(), &old_values)
+                .unwrap_or_else(|| referencing_table.get_rows()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, row)| referencing_table.get_key_values(row, foreign_key.get_columns()) == old_values)
+                    .map(|(referencing_index, _)| referencing_index)
+                    .collect());
+            let referenced = referencing.into_iter()
+                .any(|referencing_index| foreign_key.get_table() != table_name || referencing_index != index);
+            if referenced {
+                return Err(format!("row {} of {} is referenced by {}", index, table_name, foreign_key));
+            }
+        }
+        Ok(())
+    }
+
+    // removes the row together with whatever the foreign keys referencing it ask for
+    pub fn delete_row(&self, table_name: &str, index: usize) -> Result<(), String> {
+        {
+            let table = self.get_table(table_name)?;
+            if index >= table.get_rows().len() {
+                return Err(format!(
+                    "index is bigger that actual table size. Table - {}, size - {}, requested index - {}",
+                    table_name,
+                    table.get_rows().len(),
+                    index
+                ));
+            }
+        }
+        let plan = self.plan_deletion(vec![(table_name.to_string(), index)])?;
+        self.apply_deletion(plan)
+    }
+
+    // drops the table; rows of other tables referencing it are handled by their foreign keys,
+    // which are dropped afterwards as well as the table's own ones
+    pub fn delete_table(&self, table_name: &str) -> Result<(), String> {
+        if let Some(view) = self.find_view_using(table_name, None) {
+            return Err(format!("table {} is used by the view {}", table_name, view));
+        }
+        let seeds = (0..self.get_table(table_name)?.get_rows().len())
+            .map(|index| (table_name.to_string(), index))
+            .collect();
+        let plan = self.plan_deletion(seeds)?;
+        self.apply_deletion(plan)?;
+        self.tables.borrow_mut().remove(table_name);
+        self.foreign_keys.borrow_mut().retain(|foreign_key| {
+            foreign_key.get_table() != table_name && foreign_key.get_referenced_table() != table_name
+        });
+        Ok(())
+    }
+
+    // renames the table under its key and inside, together with the foreign keys and views mentioning it
+    pub fn rename_table(&self, table_name: &str, new_name: &str) -> Result<(), String> {
+        if new_name.is_empty() {
+            return Err("table name can't be empty".to_string());
+        }
+        if !self.has_table(table_name) {
+            return Err(format!("There is no table with name {}", table_name));
+        }
+        if self.has_table(new_name) {
+            return Err(format!("table {} already exists", new_name));
+        }
+        if self.has_view(new_name) {
+            return Err(format!("view {} already exists", new_name));
+        }
+        self.load_table(table_name)?;
+        let mut tables = self.tables.borrow_mut();
+        let mut table = tables.remove(table_name).unwrap();
+        table.name = new_name.to_string();
+        table.set_stored(false);
+        tables.insert(new_name.to_string(), table);
+        for foreign_key in self.foreign_keys.borrow_mut().iter_mut() {
+            if foreign_key.table == table_name {
+                foreign_key.table = new_name.to_string();
+            }
+            if foreign_key.referenced_table == table_name {
+                foreign_key.referenced_table = new_name.to_string();
+            }
+        }
+        for view in self.views.borrow_mut().iter_mut() {
+            view.rename_table(table_name, new_name);
+        }
+        for view in self.materialized_views.borrow_mut().iter_mut() {
+            view.rename_table(table_name, new_name);
+        }
+        Ok(())
+    }
+
+    // renames the columns of the table and the foreign keys and views mentioning them
+    pub fn rename_columns(&self, table_name: &str, columns: Vec<String>) -> Result<(), String> {
+        let old_columns = {
+            let mut table = self.get_table_mut(table_name)?;
+            if table.get_columns().len() != columns.len() {
+                return Err("wrong number of tables".to_string());
+            }
+            let old_columns = table.get_columns();
+            table.set_columns(columns.clone());
+            old_columns
+        };
+        let rename = |column: &mut String| {
+            if let Some(index) = old_columns.iter().position(|name| name == column) {
+                *column = columns[index].clone();
+            }
+        };
+        for foreign_key in self.foreign_keys.borrow_mut().iter_mut() {
+            if foreign_key.table == table_name {
+                foreign_key.columns.iter_mut().for_each(rename);
+            }
+            if foreign_key.referenced_table == table_name {
+                foreign_key.referenced_columns.iter_mut().for_each(rename);
+            }
+        }
+        let new_name = |column: &str| old_columns.iter().position(|name| name == column).map(|index| columns[index].clone());
+        for view in self.views.borrow_mut().iter_mut().filter(|view| view.uses_table(table_name)) {
+            view.rename_columns(&new_name);
+        }
+        for view in self.materialized_views.borrow_mut().iter_mut().filter(|view| view.get_view().uses_table(table_name)) {
+            view.view.rename_columns(&new_name);
+        }
+        Ok(())
+    }
+    pub fn rename_column(&self, table_name: &str, column: &str, new_name: &str) -> Result<(), String> {
+        let columns = self.get_table(table_name)?.get_columns();
+        if !columns.iter().any(|name| name == column) {
+            return Err(format!("There is no column {} in table {}", column, table_name));
+        }
+        if columns.iter().any(|name| name == new_name) {
+            return Err(format!("table {} already has column {}", table_name, new_name));
+        }
+        let columns = columns.into_iter()
+            .map(|name| if name == column { new_name.to_string() } else { name })
+            .collect();
+        self.rename_columns(table_name, columns)
+    }
+    #[allow(clippy::type_complexity)]
+    pub fn add_column(&self, table_name: &str, definition: ColumnDefinition, validator: Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>, position: Option<usize>) -> Result<(), String> {
+        self.get_table_mut(table_name)?.add_column(definition, validator, position)
+    }
+    // a column used by a foreign key or a view has to lose them first
+    pub fn drop_column(&self, table_name: &str, column: &str) -> Result<(), String> {
+        let column_name = column.to_string();
+        let foreign_key = self.foreign_keys.borrow()
+            .iter()
+            .find(|foreign_key| {
+                (foreign_key.table == table_name && foreign_key.columns.contains(&column_name))
+                    || (foreign_key.referenced_table == table_name && foreign_key.referenced_columns.contains(&column_name))
+            })
+            .cloned();
+        if let Some(foreign_key) = foreign_key {
+            return Err(format!("column {} is used by the foreign key {}", column, foreign_key));
+        }
+        if let Some(view) = self.find_view_using(table_name, Some(column)) {
+            return Err(format!("column {} is used by the view {}", column, view));
+        }
+        self.get_table_mut(table_name)?.drop_column(column)
+    }
+    pub fn reorder_columns(&self, table_name: &str, columns: &[String]) -> Result<(), String> {
+        self.get_table_mut(table_name)?.reorder_columns(columns)
+    }
+    // converts the column into the type of `definition` through its value generator. A dry run only
+    // reports the rows that would fail; otherwise they abort the change, get NULL or are deleted
+    // together with whatever their foreign keys ask for
+    #[allow(clippy::type_complexity)]
+    pub fn retype_column(
+        &self,
+        table_name: &str,
+        definition: ColumnDefinition,
+        validator: Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>,
+        on_failure: OnConversionFailure,
+        dry_run: bool,
+    ) -> Result<ConversionReport, String> {
+        let column = definition.name.clone();
+        let (index, results) = {
+            let table = self.get_table(table_name)?;
+            let index = match table.get_scheme().get_column_index(&column) {
+                Some(index) => index,
+                None => return Err(format!("There is no column {} in table {}", column, table_name)),
+            };
+            if table.get_scheme().get_types()[index] != definition.ty {
+                if let Some(foreign_key) = self.get_foreign_keys().iter().find(|foreign_key| {
+                    (foreign_key.table == table_name && foreign_key.columns.contains(&column))
+                        || (foreign_key.referenced_table == table_name && foreign_key.referenced_columns.contains(&column))
+                }) {
+                    return Err(format!("column {} is used by the foreign key {}", column, foreign_key));
+                }
+            }
+            (index, table.convert_column(index, &definition))
+        };
+        let report = ConversionReport {
+            column: column.clone(),
+            rows: results.len(),
+            failures: results.iter()
+                .enumerate()
+                .filter_map(|(row, result)| result.as_ref().err().map(|err| (row, err.clone())))
+                .collect(),
+            on_failure,
+        };
+        if dry_run {
+            return Ok(report);
+        }
+        let cells = match on_failure {
+            _ if report.is_clean() => results.into_iter().map(|result| result.unwrap()).collect(),
+            OnConversionFailure::Abort => return Err(report.to_string()),
+            OnConversionFailure::SetNull => results.into_iter()
+                .map(|result| result.or_else(|_| definition.build_null_value()))
+                .collect::<Result<Vec<Rc<dyn CellValue>>, String>>()?,
+            OnConversionFailure::DropRow => {
+                let seeds = report.get_failed_rows()
+                    .into_iter()
+                    .map(|row| (table_name.to_string(), row))
+                    .collect();
+                let plan = self.plan_deletion(seeds)?;
+                let deleted = plan.0.get(table_name).cloned().unwrap_or_default();
+                let remaining = results.into_iter()
+                    .enumerate()
+                    .map(|(row, result)| if deleted.contains(&row) { None } else { result.ok() })
+                    .collect::<Vec<Option<Rc<dyn CellValue>>>>();
+                self.get_table(table_name)?.convert_rows(index, &definition, validator.clone(), &remaining)?;
+                self.apply_deletion(plan)?;
+                remaining.into_iter().flatten().collect()
+            },
+        };
+        self.get_table_mut(table_name)?.retype_column(index, definition, validator, cells)?;
+        Ok(report)
+    }
+
+    fn check_reference(&self, foreign_key: &ForeignKey, row: &Row<dyn CellValue>) -> Result<(), String> {
+        let tables = self.get_tables_named(&[foreign_key.get_table(), foreign_key.get_referenced_table()])?;
+        let values = tables[foreign_key.get_table()].get_key_values(row, foreign_key.get_columns());
+        if values.iter().any(|value| matches!(value, ValueType::Null(_))) {
+            return Ok(());
+        }
+        // the referenced columns are the primary key or unique, so their key index has the row
+        let missing = tables[foreign_key.get_referenced_table()]
+            .find_by_key(foreign_key.get_referenced_columns(), &values)
+            .ok_or_else(|| format!("{}: referenced columns are neither the primary key nor unique", foreign_key))?
+            .is_empty();
+        if missing {
+            return Err(format!("{}: there is no referenced row", foreign_key));
+        }
+        Ok(())
+    }
+
+    // follows the foreign keys from the rows being deleted; nothing is changed until the whole
+    // plan is known, so a RESTRICT anywhere down the chain leaves the database intact
+    fn plan_deletion(&self, seeds: Vec<(String, usize)>) -> Result<DeletionPlan, String> {
+        let seed_tables = seeds.iter().map(|(table_name, _)| table_name.as_str()).collect::<Vec<&str>>();
+        let referencing = self.get_referencing_table_names(&seed_tables);
+        let tables = self.get_tables_named(&referencing.iter().map(String::as_str).collect::<Vec<&str>>())?;
+        let foreign_keys = self.get_foreign_keys();
+        let mut deleted: HashMap<String, BTreeSet<usize>> = HashMap::default();
+        let mut nulled = Vec::default();
+        for (table_name, index) in seeds.iter() {
+            deleted.entry(table_name.clone()).or_default().insert(*index);
+        }
+        let mut queue = VecDeque::from(seeds);
+        while let Some((table_name, index)) = queue.pop_front() {
+            let table = &tables[&table_name];
+            let row = table.get_rows()[index].clone();
+            for foreign_key in foreign_keys.iter().filter(|foreign_key| foreign_key.get_referenced_table() == table_name) {
+                let values = table.get_key_values(&row, foreign_key.get_referenced_columns());
+                if values.iter().any(|value| matches!(value, ValueType::Null(_))) {
+                    continue;
+                }
+                let referencing_table = &tables[foreign_key.get_table()];
+                for (referencing_index, referencing_row) in referencing_table.get_rows().iter().enumerate() {
+                    let already_deleted = deleted.get(foreign_key.get_table())
+                        .is_some_and(|indices| indices.contains(&referencing_index));
+                    if already_deleted || referencing_table.get_key_values(referencing_row, foreign_key.get_columns()) != values {
+                        continue;
+                    }
+                    match foreign_key.get_on_delete() {
+                        OnDelete::Restrict => return Err(format!(
+                            "row {} of {} is referenced by row {} of {}: {}",
+                            index,
+                            table_name,
+                            referencing_index,
+                            foreign_key.get_table(),
+                            foreign_key
+                        )),
+                        OnDelete::Cascade => {
+                            deleted.entry(foreign_key.get_table().to_string()).or_default().insert(referencing_index);
+                            queue.push_back((foreign_key.get_table().to_string(), referencing_index));
+                        },
+                        OnDelete::SetNull => {
+                            nulled.push((foreign_key.get_table().to_string(), referencing_index, foreign_key.get_columns().to_vec()));
+                        },
+                    }
+                }
+            }
+        }
+        Ok((deleted, nulled))
+    }
+
+    fn apply_deletion(&self, plan: DeletionPlan) -> Result<(), String> {
+        let (deleted, nulled) = plan;
+        let touched = deleted.keys()
+            .map(String::as_str)
+            .chain(nulled.iter().map(|(table_name, _, _)| table_name.as_str()))
+            .collect::<Vec<&str>>();
+        let tables = self.get_tables_named(&touched)?;
+        for (table_name, index, columns) in nulled {
+            if deleted.get(&table_name).is_some_and(|indices| indices.contains(&index)) {
+                continue;
+            }
+            let table = &tables[&table_name];
+            let row = table.get_rows()[index].clone();
+            let values = row.get_values()
+                .iter()
+                .enumerate()
+                .map(|(column_index, value)| {
+                    if columns.contains(&table.get_columns()[column_index]) {
+                        Rc::new(NullValue::new()) as Rc<dyn CellValue>
+                    } else {
+                        value.clone()
+                    }
+                })
+                .collect::<Vec<Rc<dyn CellValue>>>();
+            // the computed cells follow the nulled ones
+            let values = table.get_scheme().complete_values(table.get_scheme().get_stored_values(&values));
+            table.replace_row(index, Row::new(values));
+        }
+        for (table_name, indices) in deleted {
+            let table = &tables[&table_name];
+            for index in indices.into_iter().rev() {
+                table.erase(index as u64)?;
+            }
+        }
+        Ok(())
+    }
 }
 #[derive(Default)]
 pub struct DatabaseBuilder {
@@ -71,5 +812,247 @@ impl DatabaseBuilder {
 
 #[cfg(test)]
 mod tests {
-    // TODO: implement tests
+    use std::rc::Rc;
+    use crate::conversion::OnConversionFailure;
+    use crate::db::Database;
+    use crate::foreign_key::{ForeignKey, OnDelete};
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::table::Table;
+    use crate::types::{CellValue, ValueType, SUPPORTED_TYPES};
+    use crate::types::null_value::NullValue;
+
+    fn table(name: &str, columns: &[&str], primary_key: &str) -> Table {
+        let scheme = columns.iter()
+            .fold(Scheme::<dyn CellValue>::builder(), |builder, column| {
+                builder.with_column("IntValue".to_string(), column.to_string(), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            })
+            .build()
+            .with_primary_key(vec![primary_key.to_string()]);
+        Table::builder()
+            .with_name(name.to_string())
+            .with_scheme(scheme)
+            .build()
+            .unwrap()
+    }
+
+    fn row(raw_values: &[&str]) -> Row<dyn CellValue> {
+        let generator = SUPPORTED_TYPES.get("IntValue").unwrap();
+        let values = raw_values.iter()
+            .map(|raw_value| match *raw_value {
+                "NULL" => Rc::new(NullValue::new()) as Rc<dyn CellValue>,
+                raw_value => generator(raw_value.to_string()).unwrap(),
+            })
+            .collect();
+        Row::new(values)
+    }
+
+    fn insert(db: &Database, table_name: &str, raw_values: &[&str]) -> Result<(), String> {
+        let new_row = row(raw_values);
+        db.check_references(table_name, &new_row)?;
+        db.get_tables().unwrap()[table_name].insert_row(new_row)
+    }
+
+    // users <- orders <- items, with the given action on both foreign keys
+    fn shop(on_delete: OnDelete) -> Database {
+        let db = Database::builder().with_name("shop").with_location("/tmp").build().unwrap();
+        db.add_table(table("users", &["id"], "id"));
+        db.add_table(table("orders", &["id", "user_id"], "id"));
+        db.add_table(table("items", &["id", "order_id"], "id"));
+        insert(&db, "users", &["1"]).unwrap();
+        insert(&db, "users", &["2"]).unwrap();
+        insert(&db, "orders", &["10", "1"]).unwrap();
+        insert(&db, "orders", &["20", "2"]).unwrap();
+        insert(&db, "items", &["100", "10"]).unwrap();
+        insert(&db, "items", &["200", "20"]).unwrap();
+        let foreign_key = |table: &str, column: &str, referenced_table: &str| ForeignKey::new(
+            table.to_string(),
+            vec![column.to_string()],
+            referenced_table.to_string(),
+            vec!["id".to_string()],
+            on_delete,
+        );
+        db.add_foreign_key(foreign_key("orders", "user_id", "users")).unwrap();
+        db.add_foreign_key(foreign_key("items", "order_id", "orders")).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_add_foreign_key_checks_declaration() {
+        let db = shop(OnDelete::Restrict);
+        insert(&db, "items", &["300", "NULL"]).unwrap();
+        let foreign_key = |column: &str, referenced_column: &str, on_delete| ForeignKey::new(
+            "items".to_string(),
+            vec![column.to_string()],
+            "orders".to_string(),
+            vec![referenced_column.to_string()],
+            on_delete,
+        );
+        assert!(db.add_foreign_key(foreign_key("order_id", "user_id", OnDelete::Restrict)).is_err());
+        assert!(db.add_foreign_key(foreign_key("order_id", "id", OnDelete::Restrict)).is_err());
+        assert!(db.add_foreign_key(foreign_key("id", "id", OnDelete::SetNull)).is_err());
+        assert!(db.add_foreign_key(foreign_key("id", "id", OnDelete::Restrict)).is_err());
+        assert_eq!(db.get_foreign_keys().len(), 2);
+    }
+
+    #[test]
+    fn test_dangling_references_are_rejected() {
+        let db = shop(OnDelete::Restrict);
+        assert!(insert(&db, "orders", &["30", "3"]).is_err());
+        assert!(insert(&db, "orders", &["30", "NULL"]).is_ok());
+        assert!(db.check_update_references("orders", 0, &row(&["10", "3"])).is_err());
+        assert!(db.check_update_references("orders", 0, &row(&["10", "2"])).is_ok());
+        assert!(db.check_update_references("users", 0, &row(&["5"])).is_err());
+        assert!(db.check_update_references("orders", 2, &row(&["40", "NULL"])).is_ok());
+    }
+
+    #[test]
+    fn test_delete_row_restrict() {
+        let db = shop(OnDelete::Restrict);
+        assert!(db.delete_row("users", 0).is_err());
+        assert!(db.delete_table("orders").is_err());
+        assert_eq!(db.get_tables().unwrap()["users"].get_rows().len(), 2);
+        assert!(db.delete_row("items", 0).is_ok());
+        assert!(db.delete_row("orders", 0).is_ok());
+        assert!(db.delete_row("users", 0).is_ok());
+        assert_eq!(db.get_tables().unwrap()["users"].get_rows().len(), 1);
+    }
+
+    #[test]
+    fn test_delete_row_cascade() {
+        let db = shop(OnDelete::Cascade);
+        assert!(db.delete_row("users", 0).is_ok());
+        let tables = db.get_tables().unwrap();
+        assert_eq!(tables["users"].get_rows().len(), 1);
+        assert_eq!(tables["orders"].get_rows().len(), 1);
+        assert_eq!(tables["items"].get_rows().len(), 1);
+        match tables["items"].get_rows()[0].get_values()[0].get_value() {
+            ValueType::Int(id) => assert_eq!(id.get_value(), 200),
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn test_rename_columns_updates_foreign_keys() {
+        let db = shop(OnDelete::Restrict);
+        db.rename_columns("orders", vec!["order_id".to_string(), "buyer_id".to_string()]).unwrap();
+        assert!(db.rename_columns("orders", vec!["order_id".to_string()]).is_err());
+        let foreign_keys = db.get_foreign_keys().iter().map(|foreign_key| foreign_key.to_string()).collect::<Vec<String>>();
+        assert_eq!(foreign_keys, [
+            "orders(buyer_id) -> users(id) ON DELETE RESTRICT",
+            "items(order_id) -> orders(order_id) ON DELETE RESTRICT",
+        ]);
+        assert!(insert(&db, "orders", &["30", "3"]).is_err());
+        assert!(db.delete_row("orders", 0).is_err());
+    }
+
+    #[test]
+    fn test_rename_table_updates_foreign_keys() {
+        let db = shop(OnDelete::Cascade);
+        assert!(db.rename_table("orders", "users").is_err());
+        assert!(db.rename_table("carts", "baskets").is_err());
+        assert!(db.rename_table("orders", "").is_err());
+        db.rename_table("orders", "purchases").unwrap();
+        assert!(!db.get_tables().unwrap().contains_key("orders"));
+        assert_eq!(db.get_tables().unwrap()["purchases"].get_name(), "purchases");
+        let foreign_keys = db.get_foreign_keys().iter().map(|foreign_key| foreign_key.to_string()).collect::<Vec<String>>();
+        assert_eq!(foreign_keys, [
+            "purchases(user_id) -> users(id) ON DELETE CASCADE",
+            "items(order_id) -> purchases(id) ON DELETE CASCADE",
+        ]);
+        db.delete_row("users", 0).unwrap();
+        assert_eq!(db.get_tables().unwrap()["purchases"].get_rows().len(), 1);
+        assert_eq!(db.get_tables().unwrap()["items"].get_rows().len(), 1);
+    }
+
+    #[test]
+    fn test_delete_table_set_null() {
+        let db = shop(OnDelete::SetNull);
+        assert!(db.delete_table("orders").is_ok());
+        assert!(!db.get_tables().unwrap().contains_key("orders"));
+        assert!(db.get_foreign_keys().is_empty());
+        let tables = db.get_tables().unwrap();
+        assert_eq!(tables["items"].get_rows().len(), 2);
+        for row in tables["items"].get_rows().iter() {
+            assert!(matches!(row.get_values()[1].get_value(), ValueType::Null(_)));
+        }
+    }
+
+    #[test]
+    fn test_alter_columns_used_by_foreign_keys() {
+        let db = shop(OnDelete::Restrict);
+        assert!(db.drop_column("orders", "user_id").is_err());
+        assert!(db.drop_column("users", "id").is_err());
+        assert!(db.rename_column("orders", "user_id", "id").is_err());
+        db.rename_column("orders", "user_id", "customer_id").unwrap();
+        db.reorder_columns("orders", &["customer_id".to_string(), "id".to_string()]).unwrap();
+        assert_eq!(
+            db.get_foreign_keys()[0].to_string(),
+            "orders(customer_id) -> users(id) ON DELETE RESTRICT"
+        );
+        assert!(insert(&db, "orders", &["3", "30"]).is_err());
+        assert!(insert(&db, "orders", &["1", "30"]).is_ok());
+        assert!(db.delete_row("users", 0).is_err());
+    }
+
+    #[test]
+    fn test_retype_column() {
+        let db = shop(OnDelete::Cascade);
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column("IntValue".to_string(), "id".to_string(), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .with_column("StringValue".to_string(), "price".to_string(), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column("StringValue".to_string(), "code".to_string(), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .build()
+            .with_primary_key(vec!["id".to_string()])
+            .with_unique(vec![vec!["code".to_string()]]);
+        let products = Table::builder().with_name("products".to_string()).with_scheme(scheme.clone()).build().unwrap();
+        for raw_values in [["1", "10", "a"], ["2", "ten", "b"], ["3", "30", "03"], ["4", "40", "3"]] {
+            let values = raw_values.iter()
+                .enumerate()
+                .map(|(index, raw_value)| scheme.generate_value(index, raw_value.to_string()).unwrap())
+                .collect();
+            products.insert_row(Row::new(values)).unwrap();
+        }
+        db.add_table(products);
+        db.add_table(table("lines", &["id", "product_id"], "id"));
+        insert(&db, "lines", &["1", "2"]).unwrap();
+        db.add_foreign_key(ForeignKey::new(
+            "lines".to_string(),
+            vec!["product_id".to_string()],
+            "products".to_string(),
+            vec!["id".to_string()],
+            OnDelete::Cascade,
+        )).unwrap();
+        let int_column = |name: &str| ColumnDefinition::parse(name, "IntValue").unwrap();
+        let int_generator = || SUPPORTED_TYPES.get("IntValue").unwrap().clone();
+        let column_types = |db: &Database| db.get_tables().unwrap()["products"].get_scheme().get_types();
+
+        let report = db.retype_column("products", int_column("price"), int_generator(), OnConversionFailure::Abort, true).unwrap();
+        assert_eq!(report.get_failed_rows(), [1]);
+        assert!(db.retype_column("products", int_column("price"), int_generator(), OnConversionFailure::Abort, false).is_err());
+        assert_eq!(column_types(&db), ["IntValue", "StringValue", "StringValue"]);
+        assert!(db.retype_column("products", ColumnDefinition::parse("id", "StringValue").unwrap(), SUPPORTED_TYPES.get("StringValue").unwrap().clone(), OnConversionFailure::Abort, false).is_err());
+
+        db.retype_column("products", int_column("price"), int_generator(), OnConversionFailure::DropRow, false).unwrap();
+        assert_eq!(column_types(&db), ["IntValue", "IntValue", "StringValue"]);
+        assert_eq!(db.get_tables().unwrap()["products"].get_rows().len(), 3);
+        assert_eq!(db.get_tables().unwrap()["lines"].get_rows().len(), 0);
+
+        assert!(db.retype_column("products", int_column("code"), int_generator(), OnConversionFailure::SetNull, false).is_err());
+        assert_eq!(column_types(&db), ["IntValue", "IntValue", "StringValue"]);
+        let not_null = ColumnDefinition::parse("price", "IntValue range(0, 35) not_null").unwrap();
+        assert!(db.retype_column("products", not_null, int_generator(), OnConversionFailure::SetNull, false).is_err());
+        let bounded = ColumnDefinition::parse("price", "IntValue range(0, 35)").unwrap();
+        let report = db.retype_column("products", bounded, int_generator(), OnConversionFailure::SetNull, false).unwrap();
+        assert_eq!(report.get_failed_rows(), [2]);
+        let prices = db.get_tables().unwrap()["products"].get_rows()
+            .iter()
+            .map(|row| match row.get_values()[1].get_value() {
+                ValueType::Int(price) => Some(price.get_value()),
+                ValueType::Null(_) => None,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<Option<i64>>>();
+        assert_eq!(prices, [Some(10), Some(30), None]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,700 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use crate::types::date_interval_value::DateIntervalValue;
+use crate::types::date_value::DateValue;
+use crate::types::int_interval_value::IntIntervalValue;
+use crate::types::real_interval_value::RealIntervalValue;
+use crate::types::{ValueBuilder, ValueType};
+
+// Expressions over the cells of a row, e.g. `price * qty` or `lower(email_domain(email)) = 'ukr.net'`.
+// They know literals, column names, arithmetic (`+` also joins strings), comparisons, `and`, `or`,
+// `not`, brackets and the functions listed in `call`. NULL in anything but `coalesce` gives NULL
+
+// the value an expression works with; cells are brought to it by `from_value`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Datum {
+    Null,
+    Int(i64),
+    Real(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl Datum {
+    pub fn from_value(value: &ValueType) -> Self {
+        match value {
+            ValueType::Null(_) => Datum::Null,
+            ValueType::Int(value) => Datum::Int(value.get_value()),
+            ValueType::Real(value) => Datum::Real(value.get_value()),
+            ValueType::Bool(value) => Datum::Bool(value.get_value()),
+            ValueType::Decimal(value) => value.to_string().parse::<f64>().map(Datum::Real).unwrap_or(Datum::Null),
+            value => value.to_raw_value().map(Datum::Str).unwrap_or(Datum::Null),
+        }
+    }
+    // text a value generator builds the cell from, `None` for NULL
+    pub fn to_raw_value(&self) -> Option<String> {
+        match self {
+            Datum::Null => None,
+            Datum::Int(value) => Some(value.to_string()),
+            Datum::Real(value) => Some(value.to_string()),
+            Datum::Str(value) => Some(value.clone()),
+            Datum::Bool(value) => Some(value.to_string()),
+        }
+    }
+    // NULL counts as false, as in a `where` clause
+    pub fn is_true(&self) -> bool {
+        matches!(self, Datum::Bool(true))
+    }
+    fn get_type_name(&self) -> &'static str {
+        match self {
+            Datum::Null => "null",
+            Datum::Int(_) => "integer",
+            Datum::Real(_) => "real",
+            Datum::Str(_) => "string",
+            Datum::Bool(_) => "boolean",
+        }
+    }
+    fn as_real(&self) -> Option<f64> {
+        match self {
+            Datum::Int(value) => Some(*value as f64),
+            Datum::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+    fn compare(&self, other: &Self) -> Result<Ordering, String> {
+        let ordering = match (self, other) {
+            (Datum::Int(lhs), Datum::Int(rhs)) => Some(lhs.cmp(rhs)),
+            (Datum::Str(lhs), Datum::Str(rhs)) => Some(lhs.cmp(rhs)),
+            (Datum::Bool(lhs), Datum::Bool(rhs)) => Some(lhs.cmp(rhs)),
+            (lhs, rhs) => match (lhs.as_real(), rhs.as_real()) {
+                (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs),
+                _ => None,
+            },
+        };
+        ordering.ok_or(format!("can't compare {} with {}", self.get_type_name(), other.get_type_name()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOperator {
+    Negate,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    // the comparison with the operands swapped, `a < b` being `b > a`
+    pub fn flip(&self) -> Option<Self> {
+        match self {
+            BinaryOperator::Equal => Some(BinaryOperator::Equal),
+            BinaryOperator::NotEqual => Some(BinaryOperator::NotEqual),
+            BinaryOperator::Less => Some(BinaryOperator::Greater),
+            BinaryOperator::LessOrEqual => Some(BinaryOperator::GreaterOrEqual),
+            BinaryOperator::Greater => Some(BinaryOperator::Less),
+            BinaryOperator::GreaterOrEqual => Some(BinaryOperator::LessOrEqual),
+            _ => None,
+        }
+    }
+    fn get_precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::Less
+            | BinaryOperator::LessOrEqual | BinaryOperator::Greater | BinaryOperator::GreaterOrEqual => 4,
+            BinaryOperator::Add | BinaryOperator::Subtract => 5,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Remainder => 6,
+        }
+    }
+    fn get_symbol(&self) -> &'static str {
+        match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Remainder => "%",
+            BinaryOperator::Equal => "=",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessOrEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterOrEqual => ">=",
+            BinaryOperator::And => "and",
+            BinaryOperator::Or => "or",
+        }
+    }
+}
+
+// precedence of `not`, between `and` and the comparisons
+const NOT_PRECEDENCE: u8 = 3;
+// precedence of the unary minus, above every binary operator
+const NEGATE_PRECEDENCE: u8 = 7;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Literal(Datum),
+    Column(String),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, position: 0 };
+        let expression = parser.parse_expression(0)?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected {} in the expression: {}", token, source)),
+        }
+    }
+    // names of the columns the expression reads, each of them once
+    pub fn get_columns(&self) -> Vec<String> {
+        let mut columns = Vec::default();
+        self.collect_columns(&mut columns);
+        columns
+    }
+    fn collect_columns(&self, columns: &mut Vec<String>) {
+        match self {
+            Expression::Literal(_) => (),
+            Expression::Column(column) => {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            },
+            Expression::Unary(_, operand) => operand.collect_columns(columns),
+            Expression::Binary(_, lhs, rhs) => {
+                lhs.collect_columns(columns);
+                rhs.collect_columns(columns);
+            },
+            Expression::Call(_, arguments) => arguments.iter().for_each(|argument| argument.collect_columns(columns)),
+        }
+    }
+    // `new_name` gives the new name of a column, `None` keeps the old one
+    pub fn rename_columns(&mut self, new_name: &dyn Fn(&str) -> Option<String>) {
+        match self {
+            Expression::Literal(_) => (),
+            Expression::Column(name) => {
+                if let Some(new_name) = new_name(name) {
+                    *name = new_name;
+                }
+            },
+            Expression::Unary(_, operand) => operand.rename_columns(new_name),
+            Expression::Binary(_, lhs, rhs) => {
+                lhs.rename_columns(new_name);
+                rhs.rename_columns(new_name);
+            },
+            Expression::Call(_, arguments) => arguments.iter_mut().for_each(|argument| argument.rename_columns(new_name)),
+        }
+    }
+    // `row` gives the value of a column by name, `None` if there is no such column
+    pub fn evaluate(&self, row: &dyn Fn(&str) -> Option<Datum>) -> Result<Datum, String> {
+        match self {
+            Expression::Literal(value) => Ok(value.clone()),
+            Expression::Column(column) => row(column).ok_or(format!("unknown column {}", column)),
+            Expression::Unary(operator, operand) => {
+                match (operator, operand.evaluate(row)?) {
+                    (_, Datum::Null) => Ok(Datum::Null),
+                    (UnaryOperator::Negate, Datum::Int(value)) => value.checked_neg().map(Datum::Int).ok_or("integer overflow".to_string()),
+                    (UnaryOperator::Negate, Datum::Real(value)) => Ok(Datum::Real(-value)),
+                    (UnaryOperator::Not, Datum::Bool(value)) => Ok(Datum::Bool(!value)),
+                    (_, value) => Err(format!("{} can't be applied to {}", operator_name(*operator), value.get_type_name())),
+                }
+            },
+            Expression::Binary(BinaryOperator::And, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(row)?, rhs.evaluate(row)?);
+                match (&lhs, &rhs) {
+                    (Datum::Bool(false), _) | (_, Datum::Bool(false)) => Ok(Datum::Bool(false)),
+                    (Datum::Bool(true), Datum::Bool(true)) => Ok(Datum::Bool(true)),
+                    (Datum::Null | Datum::Bool(_), Datum::Null | Datum::Bool(_)) => Ok(Datum::Null),
+                    _ => Err(format!("and can't be applied to {} and {}", lhs.get_type_name(), rhs.get_type_name())),
+                }
+            },
+            Expression::Binary(BinaryOperator::Or, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(row)?, rhs.evaluate(row)?);
+                match (&lhs, &rhs) {
+                    (Datum::Bool(true), _) | (_, Datum::Bool(true)) => Ok(Datum::Bool(true)),
+                    (Datum::Bool(false), Datum::Bool(false)) => Ok(Datum::Bool(false)),
+                    (Datum::Null | Datum::Bool(_), Datum::Null | Datum::Bool(_)) => Ok(Datum::Null),
+                    _ => Err(format!("or can't be applied to {} and {}", lhs.get_type_name(), rhs.get_type_name())),
+                }
+            },
+            Expression::Binary(operator, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(row)?, rhs.evaluate(row)?);
+                if lhs == Datum::Null || rhs == Datum::Null {
+                    return Ok(Datum::Null);
+                }
+                apply_binary(*operator, lhs, rhs)
+            },
+            Expression::Call(function, arguments) => {
+                let arguments = arguments.iter()
+                    .map(|argument| argument.evaluate(row))
+                    .collect::<Result<Vec<Datum>, String>>()?;
+                call(function, arguments)
+            },
+        }
+    }
+    fn get_precedence(&self) -> u8 {
+        match self {
+            Expression::Binary(operator, _, _) => operator.get_precedence(),
+            Expression::Unary(UnaryOperator::Not, _) => NOT_PRECEDENCE,
+            Expression::Unary(UnaryOperator::Negate, _) => NEGATE_PRECEDENCE,
+            _ => u8::MAX,
+        }
+    }
+}
+
+// prints the expression back in a form `parse` reads, with brackets only where they're needed
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operand = |expression: &Expression, min_precedence: u8| {
+            if expression.get_precedence() < min_precedence {
+                format!("({})", expression)
+            } else {
+                expression.to_string()
+            }
+        };
+        match self {
+            Expression::Literal(Datum::Null) => write!(f, "NULL"),
+            Expression::Literal(Datum::Int(value)) => write!(f, "{}", value),
+            // written out in full, with no exponent, and with a fraction so it reads back as a real
+            Expression::Literal(Datum::Real(value)) if value.fract() == 0.0 && value.is_finite() => write!(f, "{}.0", value),
+            Expression::Literal(Datum::Real(value)) => write!(f, "{}", value),
+            Expression::Literal(Datum::Str(value)) => write!(f, "'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+            Expression::Literal(Datum::Bool(value)) => write!(f, "{}", value),
+            Expression::Column(column) => write!(f, "{}", column),
+            Expression::Unary(UnaryOperator::Negate, value) => write!(f, "-{}", operand(value, NEGATE_PRECEDENCE)),
+            Expression::Unary(UnaryOperator::Not, value) => write!(f, "not {}", operand(value, NOT_PRECEDENCE)),
+            Expression::Binary(operator, lhs, rhs) => {
+                let precedence = operator.get_precedence();
+                write!(f, "{} {} {}", operand(lhs, precedence), operator.get_symbol(), operand(rhs, precedence + 1))
+            },
+            Expression::Call(function, arguments) => {
+                let arguments = arguments.iter().map(|argument| argument.to_string()).collect::<Vec<String>>();
+                write!(f, "{}({})", function, arguments.join(", "))
+            },
+        }
+    }
+}
+
+fn operator_name(operator: UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Negate => "-",
+        UnaryOperator::Not => "not",
+    }
+}
+
+fn apply_binary(operator: BinaryOperator, lhs: Datum, rhs: Datum) -> Result<Datum, String> {
+    let mismatch = || format!("{} can't be applied to {} and {}", operator.get_symbol(), lhs.get_type_name(), rhs.get_type_name());
+    match operator {
+        BinaryOperator::Equal => Ok(Datum::Bool(lhs.compare(&rhs)? == Ordering::Equal)),
+        BinaryOperator::NotEqual => Ok(Datum::Bool(lhs.compare(&rhs)? != Ordering::Equal)),
+        BinaryOperator::Less => Ok(Datum::Bool(lhs.compare(&rhs)?.is_lt())),
+        BinaryOperator::LessOrEqual => Ok(Datum::Bool(lhs.compare(&rhs)?.is_le())),
+        BinaryOperator::Greater => Ok(Datum::Bool(lhs.compare(&rhs)?.is_gt())),
+        BinaryOperator::GreaterOrEqual => Ok(Datum::Bool(lhs.compare(&rhs)?.is_ge())),
+        BinaryOperator::Add => match (&lhs, &rhs) {
+            (Datum::Str(lhs), Datum::Str(rhs)) => Ok(Datum::Str(format!("{}{}", lhs, rhs))),
+            (Datum::Int(lhs), Datum::Int(rhs)) => lhs.checked_add(*rhs).map(Datum::Int).ok_or("integer overflow".to_string()),
+            _ => lhs.as_real().zip(rhs.as_real()).map(|(lhs, rhs)| Datum::Real(lhs + rhs)).ok_or_else(mismatch),
+        },
+        BinaryOperator::Subtract => match (&lhs, &rhs) {
+            (Datum::Int(lhs), Datum::Int(rhs)) => lhs.checked_sub(*rhs).map(Datum::Int).ok_or("integer overflow".to_string()),
+            _ => lhs.as_real().zip(rhs.as_real()).map(|(lhs, rhs)| Datum::Real(lhs - rhs)).ok_or_else(mismatch),
+        },
+        BinaryOperator::Multiply => match (&lhs, &rhs) {
+            (Datum::Int(lhs), Datum::Int(rhs)) => lhs.checked_mul(*rhs).map(Datum::Int).ok_or("integer overflow".to_string()),
+            _ => lhs.as_real().zip(rhs.as_real()).map(|(lhs, rhs)| Datum::Real(lhs * rhs)).ok_or_else(mismatch),
+        },
+        // integers are divided without the remainder, as in SQL
+        BinaryOperator::Divide | BinaryOperator::Remainder => match (&lhs, &rhs) {
+            (Datum::Int(_), Datum::Int(0)) => Err("division by zero".to_string()),
+            (Datum::Int(lhs), Datum::Int(rhs)) if operator == BinaryOperator::Divide => Ok(Datum::Int(lhs.wrapping_div(*rhs))),
+            (Datum::Int(lhs), Datum::Int(rhs)) => Ok(Datum::Int(lhs.wrapping_rem(*rhs))),
+            _ => match lhs.as_real().zip(rhs.as_real()) {
+                Some((_, 0.0)) => Err("division by zero".to_string()),
+                Some((lhs, rhs)) if operator == BinaryOperator::Divide => Ok(Datum::Real(lhs / rhs)),
+                Some((lhs, rhs)) => Ok(Datum::Real(lhs % rhs)),
+                None => Err(mismatch()),
+            },
+        },
+        BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+    }
+}
+
+fn call(function: &str, arguments: Vec<Datum>) -> Result<Datum, String> {
+    let expect = |count: usize| -> Result<(), String> {
+        if arguments.len() != count {
+            return Err(format!("{} expects {} arguments, got {}", function, count, arguments.len()));
+        }
+        Ok(())
+    };
+    if function == "coalesce" {
+        return Ok(arguments.into_iter().find(|argument| *argument != Datum::Null).unwrap_or(Datum::Null));
+    }
+    if arguments.contains(&Datum::Null) {
+        return Ok(Datum::Null);
+    }
+    let text = |datum: &Datum| datum.to_raw_value().unwrap_or_default();
+    match function {
+        "concat" => Ok(Datum::Str(arguments.iter().map(text).collect())),
+        "lower" => expect(1).map(|_| Datum::Str(text(&arguments[0]).to_lowercase())),
+        "upper" => expect(1).map(|_| Datum::Str(text(&arguments[0]).to_uppercase())),
+        "trim" => expect(1).map(|_| Datum::Str(text(&arguments[0]).trim().to_string())),
+        "length" => expect(1).map(|_| Datum::Int(text(&arguments[0]).chars().count() as i64)),
+        // the part of an email after `@`, NULL for anything that isn't one
+        "email_domain" => {
+            expect(1)?;
+            Ok(text(&arguments[0]).rsplit_once('@')
+                .map(|(_, domain)| Datum::Str(domain.to_lowercase()))
+                .unwrap_or(Datum::Null))
+        },
+        "abs" => {
+            expect(1)?;
+            match arguments[0] {
+                Datum::Int(value) => value.checked_abs().map(Datum::Int).ok_or("integer overflow".to_string()),
+                Datum::Real(value) => Ok(Datum::Real(value.abs())),
+                ref value => Err(format!("abs can't be applied to {}", value.get_type_name())),
+            }
+        },
+        // round(value) or round(value, digits)
+        "round" => {
+            if arguments.is_empty() || arguments.len() > 2 {
+                return Err(format!("round expects 1 or 2 arguments, got {}", arguments.len()));
+            }
+            let digits = match arguments.get(1) {
+                None => 0,
+                Some(Datum::Int(digits)) => *digits as i32,
+                Some(value) => return Err(format!("round digits can't be {}", value.get_type_name())),
+            };
+            match arguments[0] {
+                Datum::Int(value) => Ok(Datum::Int(value)),
+                Datum::Real(value) => {
+                    let scale = 10f64.powi(digits);
+                    Ok(Datum::Real((value * scale).round() / scale))
+                },
+                ref value => Err(format!("round can't be applied to {}", value.get_type_name())),
+            }
+        },
+        // contains(interval, value), contains(interval, interval) and overlaps(interval, interval)
+        "contains" | "overlaps" => {
+            expect(2)?;
+            compare_intervals(function, &arguments[0], &arguments[1])
+        },
+        _ => Err(format!("unknown function {}", function)),
+    }
+}
+
+// the intervals are cells of the interval types or their text, e.g. '[1, 5]', tried as integer,
+// real and date ones in turn until both arguments fit one of them
+fn compare_intervals(function: &str, interval: &Datum, other: &Datum) -> Result<Datum, String> {
+    let text = |datum: &Datum| datum.to_raw_value().unwrap_or_default();
+    let contains = function == "contains";
+    if let Ok(interval) = IntIntervalValue::builder().with_raw_value(text(interval)).build() {
+        match (other, IntIntervalValue::builder().with_raw_value(text(other)).build()) {
+            (Datum::Int(value), _) if contains => return Ok(Datum::Bool(interval.contains(*value))),
+            (_, Ok(other)) if contains => return Ok(Datum::Bool(interval.contains_interval(&other))),
+            (_, Ok(other)) => return Ok(Datum::Bool(interval.overlaps(&other))),
+            _ => (),
+        }
+    }
+    if let Ok(interval) = RealIntervalValue::builder().with_raw_value(text(interval)).build() {
+        match (other.as_real(), RealIntervalValue::builder().with_raw_value(text(other)).build()) {
+            (Some(value), _) if contains => return Ok(Datum::Bool(interval.contains(value))),
+            (_, Ok(other)) if contains => return Ok(Datum::Bool(interval.contains_interval(&other))),
+            (_, Ok(other)) => return Ok(Datum::Bool(interval.overlaps(&other))),
+            _ => (),
+        }
+    }
+    if let Ok(interval) = DateIntervalValue::builder().with_raw_value(text(interval)).build() {
+        match (DateValue::builder().with_raw_value(text(other)).build(), DateIntervalValue::builder().with_raw_value(text(other)).build()) {
+            (Ok(value), _) if contains => return Ok(Datum::Bool(interval.contains(value.get_value()))),
+            (_, Ok(other)) if contains => return Ok(Datum::Bool(interval.contains_interval(&other))),
+            (_, Ok(other)) => return Ok(Datum::Bool(interval.overlaps(&other))),
+            _ => (),
+        }
+    }
+    Err(format!("{} can't be applied to {} and {}", function, text(interval), text(other)))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Int(i64),
+    Real(f64),
+    Str(String),
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Int(value) => write!(f, "{}", value),
+            Token::Real(value) => write!(f, "{}", value),
+            Token::Str(value) => write!(f, "'{}'", value),
+            Token::Identifier(value) => write!(f, "{}", value),
+            Token::Symbol(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 15] = ["<=", ">=", "!=", "<>", "==", "+", "-", "*", "/", "%", "(", ")", ",", "<", ">"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::default();
+    let mut position = 0;
+    while position < chars.len() {
+        let c = chars[position];
+        if c.is_whitespace() {
+            position += 1;
+        } else if c.is_ascii_digit() {
+            let start = position;
+            while position < chars.len() && (chars[position].is_ascii_digit() || chars[position] == '.') {
+                position += 1;
+            }
+            let number = chars[start..position].iter().collect::<String>();
+            let token = if number.contains('.') {
+                number.parse::<f64>().map(Token::Real).map_err(|_| format!("invalid number {}", number))?
+            } else {
+                number.parse::<i64>().map(Token::Int).map_err(|_| format!("invalid number {}", number))?
+            };
+            tokens.push(token);
+        } else if c.is_alphabetic() || c == '_' {
+            let start = position;
+            while position < chars.len() && (chars[position].is_alphanumeric() || chars[position] == '_') {
+                position += 1;
+            }
+            tokens.push(Token::Identifier(chars[start..position].iter().collect()));
+        } else if c == '\'' || c == '"' {
+            let mut value = String::new();
+            position += 1;
+            loop {
+                match chars.get(position) {
+                    Some(&quote) if quote == c => break,
+                    Some('\\') => {
+                        position += 1;
+                        match chars.get(position) {
+                            Some(&escaped) => value.push(escaped),
+                            None => return Err(format!("unfinished escape in the expression: {}", source)),
+                        }
+                    },
+                    Some(&other) => value.push(other),
+                    None => return Err(format!("missing closing {} in the expression: {}", c, source)),
+                }
+                position += 1;
+            }
+            position += 1;
+            tokens.push(Token::Str(value));
+        } else {
+            let rest = chars[position..].iter().take(2).collect::<String>();
+            let symbol = SYMBOLS.iter()
+                .chain(["="].iter())
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or(format!("unexpected {} in the expression: {}", c, source))?;
+            position += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(found)) if found == symbol => Ok(()),
+            Some(token) => Err(format!("expected {}, found {}", symbol, token)),
+            None => Err(format!("expected {} at the end of the expression", symbol)),
+        }
+    }
+    fn peek_operator(&self) -> Option<BinaryOperator> {
+        match self.peek()? {
+            Token::Symbol("+") => Some(BinaryOperator::Add),
+            Token::Symbol("-") => Some(BinaryOperator::Subtract),
+            Token::Symbol("*") => Some(BinaryOperator::Multiply),
+            Token::Symbol("/") => Some(BinaryOperator::Divide),
+            Token::Symbol("%") => Some(BinaryOperator::Remainder),
+            Token::Symbol("=") | Token::Symbol("==") => Some(BinaryOperator::Equal),
+            Token::Symbol("!=") | Token::Symbol("<>") => Some(BinaryOperator::NotEqual),
+            Token::Symbol("<") => Some(BinaryOperator::Less),
+            Token::Symbol("<=") => Some(BinaryOperator::LessOrEqual),
+            Token::Symbol(">") => Some(BinaryOperator::Greater),
+            Token::Symbol(">=") => Some(BinaryOperator::GreaterOrEqual),
+            Token::Identifier(word) if word.eq_ignore_ascii_case("and") => Some(BinaryOperator::And),
+            Token::Identifier(word) if word.eq_ignore_ascii_case("or") => Some(BinaryOperator::Or),
+            _ => None,
+        }
+    }
+    // binary operators bind to the left; comparisons don't chain
+    fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, String> {
+        let mut lhs = self.parse_prefix()?;
+        while let Some(operator) = self.peek_operator() {
+            let precedence = operator.get_precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            self.next();
+            let rhs = self.parse_expression(precedence + 1)?;
+            lhs = Expression::Binary(operator, Box::new(lhs), Box::new(rhs));
+            if precedence == 4 && self.peek_operator().is_some_and(|next| next.get_precedence() == 4) {
+                return Err("comparisons can't be chained, use and".to_string());
+            }
+        }
+        Ok(lhs)
+    }
+    fn parse_prefix(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Expression::Literal(Datum::Int(value))),
+            Some(Token::Real(value)) => Ok(Expression::Literal(Datum::Real(value))),
+            Some(Token::Str(value)) => Ok(Expression::Literal(Datum::Str(value))),
+            Some(Token::Symbol("-")) => {
+                let operand = self.parse_expression(NEGATE_PRECEDENCE)?;
+                Ok(Expression::Unary(UnaryOperator::Negate, Box::new(operand)))
+            },
+            Some(Token::Symbol("(")) => {
+                let expression = self.parse_expression(0)?;
+                self.expect(")")?;
+                Ok(expression)
+            },
+            Some(Token::Identifier(word)) => match word.to_lowercase().as_str() {
+                "null" => Ok(Expression::Literal(Datum::Null)),
+                "true" => Ok(Expression::Literal(Datum::Bool(true))),
+                "false" => Ok(Expression::Literal(Datum::Bool(false))),
+                "not" => {
+                    let operand = self.parse_expression(NOT_PRECEDENCE)?;
+                    Ok(Expression::Unary(UnaryOperator::Not, Box::new(operand)))
+                },
+                "and" | "or" => Err(format!("unexpected {}", word)),
+                _ if self.peek() == Some(&Token::Symbol("(")) => {
+                    self.next();
+                    let mut arguments = Vec::default();
+                    if self.peek() != Some(&Token::Symbol(")")) {
+                        loop {
+                            arguments.push(self.parse_expression(0)?);
+                            if self.peek() != Some(&Token::Symbol(",")) {
+                                break;
+                            }
+                            self.next();
+                        }
+                    }
+                    self.expect(")")?;
+                    Ok(Expression::Call(word.to_lowercase(), arguments))
+                },
+                _ => Ok(Expression::Column(word)),
+            },
+            Some(token) => Err(format!("unexpected {}", token)),
+            None => Err("the expression ends unexpectedly".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expression::{Datum, Expression};
+
+    fn evaluate(source: &str) -> Result<Datum, String> {
+        let row = |column: &str| match column {
+            "price" => Some(Datum::Real(2.5)),
+            "qty" => Some(Datum::Int(4)),
+            "email" => Some(Datum::Str("Taras@UKR.net".to_string())),
+            "note" => Some(Datum::Null),
+            _ => None,
+        };
+        Expression::parse(source)?.evaluate(&row)
+    }
+
+    #[test]
+    fn test_evaluate_arithmetic() {
+        assert_eq!(evaluate("price * qty"), Ok(Datum::Real(10.0)));
+        assert_eq!(evaluate("1 + 2 * 3 - -4"), Ok(Datum::Int(11)));
+        assert_eq!(evaluate("(1 + 2) * 3"), Ok(Datum::Int(9)));
+        assert_eq!(evaluate("7 / 2 + 7 % 2"), Ok(Datum::Int(4)));
+        assert_eq!(evaluate("qty + note"), Ok(Datum::Null));
+        assert_eq!(evaluate("'a' + 'b'"), Ok(Datum::Str("ab".to_string())));
+        assert!(evaluate("qty / 0").is_err());
+        assert!(evaluate("qty + 'a'").is_err());
+        assert!(evaluate("weight * 2").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_functions_and_logic() {
+        assert_eq!(evaluate("email_domain(email)"), Ok(Datum::Str("ukr.net".to_string())));
+        assert_eq!(evaluate("upper(concat(qty, '-', email_domain(email)))"), Ok(Datum::Str("4-UKR.NET".to_string())));
+        assert_eq!(evaluate("coalesce(note, 'none')"), Ok(Datum::Str("none".to_string())));
+        assert_eq!(evaluate("round(price * 1.111, 2)"), Ok(Datum::Real(2.78)));
+        assert_eq!(evaluate("qty >= 4 and not price > 3"), Ok(Datum::Bool(true)));
+        assert_eq!(evaluate("note = 1 or qty = 4"), Ok(Datum::Bool(true)));
+        assert_eq!(evaluate("note = 1 and qty = 4"), Ok(Datum::Null));
+        assert!(evaluate("length(email, 1)").is_err());
+        assert!(evaluate("frobnicate(qty)").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_interval_functions() {
+        assert_eq!(evaluate("contains('[1, 5]', qty)"), Ok(Datum::Bool(true)));
+        assert_eq!(evaluate("contains('[1, 5]', '[2, 6]')"), Ok(Datum::Bool(false)));
+        assert_eq!(evaluate("overlaps('[1, 5]', '[5, 9]')"), Ok(Datum::Bool(true)));
+        assert_eq!(evaluate("contains('[0.5, 2.5]', price)"), Ok(Datum::Bool(true)));
+        assert_eq!(evaluate("overlaps('[0.5, 1.5]', '[1.6, 2]')"), Ok(Datum::Bool(false)));
+        assert_eq!(evaluate("contains(note, qty)"), Ok(Datum::Null));
+        assert!(evaluate("contains('[1, 5]', email)").is_err());
+        assert!(evaluate("overlaps('[1, 5]', qty)").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Expression::parse("price *").is_err());
+        assert!(Expression::parse("(price").is_err());
+        assert!(Expression::parse("price qty").is_err());
+        assert!(Expression::parse("1 < qty < 3").is_err());
+        assert!(Expression::parse("'open").is_err());
+        assert!(Expression::parse("price # 2").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for source in ["price * qty", "(price + 1) * -qty", "a - (b - c)", "not (a or b) and c = 'it\\'s'", "lower(email) != 'x' or 2.0 > round(a, 1)"] {
+            let expression = Expression::parse(source).unwrap();
+            assert_eq!(Expression::parse(&expression.to_string()).unwrap(), expression);
+        }
+        assert_eq!(Expression::parse("(a*b)+(c)").unwrap().to_string(), "a * b + c");
+        for value in [0.00001, 1e-300, 0.1, 2.0, 1e15, 1e16, 123456789.125, 1e300, f64::MAX, f64::MIN_POSITIVE] {
+            let expression = Expression::Literal(Datum::Real(value));
+            assert_eq!(Expression::parse(&expression.to_string()), Ok(expression));
+        }
+        assert_eq!(Expression::parse("price * 0.00001").unwrap().to_string(), "price * 0.00001");
+        let mut expression = Expression::parse("price * qty + price").unwrap();
+        assert_eq!(expression.get_columns(), ["price", "qty"]);
+        expression.rename_columns(&|column| (column == "price").then(|| "cost".to_string()));
+        assert_eq!(expression.to_string(), "cost * qty + cost");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+
+// what happens to the referencing rows when the referenced row or table goes away
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnDelete {
+    #[default]
+    Restrict,
+    Cascade,
+    SetNull,
+}
+
+impl OnDelete {
+    pub fn parse(raw_value: &str) -> Result<Self, String> {
+        match raw_value.trim().to_lowercase().replace(['_', '-'], " ").as_str() {
+            "restrict" => Ok(OnDelete::Restrict),
+            "cascade" => Ok(OnDelete::Cascade),
+            "set null" | "setnull" => Ok(OnDelete::SetNull),
+            _ => Err(format!("unknown on delete action: {}, expected restrict, cascade or set_null", raw_value)),
+        }
+    }
+}
+
+impl Display for OnDelete {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnDelete::Restrict => write!(f, "RESTRICT"),
+            OnDelete::Cascade => write!(f, "CASCADE"),
+            OnDelete::SetNull => write!(f, "SET NULL"),
+        }
+    }
+}
+
+// `table(columns)` references the primary key or a unique column set `referenced_table(referenced_columns)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignKey {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_delete: OnDelete,
+}
+
+impl ForeignKey {
+    pub fn new(table: String, columns: Vec<String>, referenced_table: String, referenced_columns: Vec<String>, on_delete: OnDelete) -> Self {
+        Self {
+            table,
+            columns,
+            referenced_table,
+            referenced_columns,
+            on_delete,
+        }
+    }
+    pub fn get_table(&self) -> &str {
+        self.table.as_str()
+    }
+    pub fn get_columns(&self) -> &[String] {
+        self.columns.as_slice()
+    }
+    pub fn get_referenced_table(&self) -> &str {
+        self.referenced_table.as_str()
+    }
+    pub fn get_referenced_columns(&self) -> &[String] {
+        self.referenced_columns.as_slice()
+    }
+    pub fn get_on_delete(&self) -> OnDelete {
+        self.on_delete
+    }
+}
+
+impl Display for ForeignKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({}) -> {}({}) ON DELETE {}",
+            self.table,
+            self.columns.join(", "),
+            self.referenced_table,
+            self.referenced_columns.join(", "),
+            self.on_delete
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::foreign_key::{ForeignKey, OnDelete};
+
+    #[test]
+    fn test_parse_on_delete() {
+        assert_eq!(OnDelete::parse("CASCADE"), Ok(OnDelete::Cascade));
+        assert_eq!(OnDelete::parse(" restrict "), Ok(OnDelete::Restrict));
+        assert_eq!(OnDelete::parse("set_null"), Ok(OnDelete::SetNull));
+        assert_eq!(OnDelete::parse("SET NULL"), Ok(OnDelete::SetNull));
+        assert!(OnDelete::parse("ignore").is_err());
+    }
+
+    #[test]
+    fn test_display_foreign_key() {
+        let foreign_key = ForeignKey::new(
+            "orders".to_string(),
+            vec!["user_id".to_string()],
+            "users".to_string(),
+            vec!["id".to_string()],
+            OnDelete::SetNull,
+        );
+        assert_eq!(foreign_key.to_string(), "orders(user_id) -> users(id) ON DELETE SET NULL");
+    }
+}
\ No newline at end of file
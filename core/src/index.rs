@@ -0,0 +1,554 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::ops::Bound;
+use std::rc::Rc;
+use crate::row::Row;
+use crate::scheme::Scheme;
+use crate::types::{CellValue, ValueType};
+
+// types whose cells an index orders the same way the cells compare, so it can serve sorts and ranges
+const ORDERED_TYPES: [&str; 4] = ["IntValue", "RealValue", "StringValue", "CharValue"];
+// what a B-tree spends on a key besides the key itself: its share of a node and the links to it
+const BTREE_NODE_OVERHEAD: usize = 16;
+
+// a real number ordered totally, with -0 and 0 being the same key
+#[derive(Clone, Copy, Debug)]
+pub struct RealKey(f64);
+
+impl RealKey {
+    pub fn new(value: f64) -> Self {
+        Self(if value == 0.0 { 0.0 } else { value })
+    }
+}
+
+impl PartialEq for RealKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RealKey {}
+
+impl PartialOrd for RealKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RealKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for RealKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+// a cell as an index keeps it; NULL goes after everything, as in the sorts of views, a decimal is
+// kept exactly by its units and scale with the trailing zeros dropped, and its currency, and the
+// cells of the other types without an order are kept by their text
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IndexKey {
+    Bool(bool),
+    Int(i64),
+    Real(RealKey),
+    Decimal(i128, u32, Option<String>),
+    Date(i64),
+    Str(String),
+    Null,
+}
+
+impl IndexKey {
+    pub fn from_value(value: &ValueType) -> Self {
+        match value {
+            ValueType::Null(_) => IndexKey::Null,
+            ValueType::Bool(value) => IndexKey::Bool(value.get_value()),
+            ValueType::Int(value) => IndexKey::Int(value.get_value()),
+            ValueType::Real(value) => IndexKey::Real(RealKey::new(value.get_value())),
+            ValueType::Decimal(value) => {
+                let value = value.normalize();
+                IndexKey::Decimal(value.get_units(), value.get_scale(), value.get_currency().map(str::to_string))
+            },
+            ValueType::Date(value) => IndexKey::Date(value.get_value().timestamp_millis()),
+            value => value.to_raw_value().map(IndexKey::Str).unwrap_or(IndexKey::Null),
+        }
+    }
+}
+
+// how an index keeps its keys: ordered ones serve lookups, ranges and sorts, hashed ones only
+// lookups of the whole key, but in constant time, and full-text ones keep the words of string
+// cells for `Index::search`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexKind {
+    #[default]
+    BTree,
+    Hash,
+    FullText,
+}
+
+impl IndexKind {
+    pub fn parse(raw_value: &str) -> Result<Self, String> {
+        match raw_value.trim().to_lowercase().as_str() {
+            "btree" | "b-tree" | "ordered" => Ok(IndexKind::BTree),
+            "hash" => Ok(IndexKind::Hash),
+            "fulltext" | "full-text" | "text" => Ok(IndexKind::FullText),
+            _ => Err(format!("unknown index kind: {}, expected btree, hash or fulltext", raw_value)),
+        }
+    }
+}
+
+impl Display for IndexKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexKind::BTree => write!(f, "BTREE"),
+            IndexKind::Hash => write!(f, "HASH"),
+            IndexKind::FullText => write!(f, "FULLTEXT"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Entries {
+    Ordered(BTreeMap<Vec<IndexKey>, Vec<usize>>),
+    Hashed(HashMap<Vec<IndexKey>, Vec<usize>>),
+    // a word to the positions of the rows holding it and how many times each of them does
+    Text(BTreeMap<String, Vec<(usize, usize)>>),
+}
+
+// the words of a text: runs of letters and digits, case folded
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|char: char| !char.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// a map from the cells of `columns` to the positions of the rows holding them
+#[derive(Clone, Debug)]
+pub struct Index {
+    name: String,
+    columns: Vec<String>,
+    entries: Entries,
+}
+
+impl Index {
+    pub fn new(name: String, columns: Vec<String>) -> Self {
+        Self::with_kind(name, columns, IndexKind::BTree)
+    }
+    pub fn with_kind(name: String, columns: Vec<String>, kind: IndexKind) -> Self {
+        let entries = match kind {
+            IndexKind::BTree => Entries::Ordered(BTreeMap::default()),
+            IndexKind::Hash => Entries::Hashed(HashMap::default()),
+            IndexKind::FullText => Entries::Text(BTreeMap::default()),
+        };
+        Self {
+            name,
+            columns,
+            entries,
+        }
+    }
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+    pub fn get_kind(&self) -> IndexKind {
+        match self.entries {
+            Entries::Ordered(_) => IndexKind::BTree,
+            Entries::Hashed(_) => IndexKind::Hash,
+            Entries::Text(_) => IndexKind::FullText,
+        }
+    }
+    pub fn get_columns(&self) -> &[String] {
+        self.columns.as_slice()
+    }
+    pub fn set_columns(&mut self, columns: Vec<String>) {
+        self.columns = columns;
+    }
+    // number of distinct keys, words for a full-text index
+    pub fn len(&self) -> usize {
+        match &self.entries {
+            Entries::Ordered(entries) => entries.len(),
+            Entries::Hashed(entries) => entries.len(),
+            Entries::Text(entries) => entries.len(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    // the columns have to exist and hold cells an index can keep
+    pub fn check(&self, scheme: &Scheme<dyn CellValue>) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("index name can't be empty".to_string());
+        }
+        if self.columns.is_empty() {
+            return Err(format!("index {} has no columns", self.name));
+        }
+        for (position, column) in self.columns.iter().enumerate() {
+            let index = match scheme.get_column_index(column) {
+                Some(index) => index,
+                None => return Err(format!("There is no column {} to index", column)),
+            };
+            if self.columns[..position].contains(column) {
+                return Err(format!("column {} is listed twice in index {}", column, self.name));
+            }
+            if scheme.get_types()[index] == "PictureValue" {
+                return Err(format!("column {} holds pictures, which can't be indexed", column));
+            }
+            if self.get_kind() == IndexKind::FullText && scheme.get_types()[index] != "StringValue" {
+                return Err(format!("column {} doesn't hold strings, which a full-text index needs", column));
+            }
+        }
+        Ok(())
+    }
+    pub fn get_key(&self, scheme: &Scheme<dyn CellValue>, row: &Row<dyn CellValue>) -> Vec<IndexKey> {
+        self.columns.iter()
+            .map(|column| match scheme.get_column_index(column).and_then(|index| row.get_values().get(index)) {
+                Some(value) => IndexKey::from_value(&value.get_value()),
+                None => IndexKey::Null,
+            })
+            .collect()
+    }
+    pub fn build(&mut self, scheme: &Scheme<dyn CellValue>, rows: &[Rc<Row<dyn CellValue>>]) {
+        match &mut self.entries {
+            Entries::Ordered(entries) => entries.clear(),
+            Entries::Hashed(entries) => entries.clear(),
+            Entries::Text(entries) => entries.clear(),
+        }
+        for (position, row) in rows.iter().enumerate() {
+            self.insert(self.get_key(scheme, row), position);
+        }
+    }
+    // the words of the string cells of a key with how many times each of them occurs
+    fn count_words(key: &[IndexKey]) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::<String, usize>::default();
+        for value in key {
+            if let IndexKey::Str(text) = value {
+                for word in tokenize(text) {
+                    *counts.entry(word).or_default() += 1;
+                }
+            }
+        }
+        counts
+    }
+    pub fn insert(&mut self, key: Vec<IndexKey>, position: usize) {
+        let positions = match &mut self.entries {
+            Entries::Ordered(entries) => entries.entry(key).or_default(),
+            Entries::Hashed(entries) => entries.entry(key).or_default(),
+            Entries::Text(entries) => {
+                for (word, count) in Self::count_words(&key) {
+                    let postings = entries.entry(word).or_default();
+                    let at = postings.partition_point(|&(other, _)| other < position);
+                    postings.insert(at, (position, count));
+                }
+                return;
+            },
+        };
+        let at = positions.partition_point(|&other| other < position);
+        positions.insert(at, position);
+    }
+    pub fn remove(&mut self, key: &[IndexKey], position: usize) {
+        let positions = match &mut self.entries {
+            Entries::Ordered(entries) => entries.get_mut(key),
+            Entries::Hashed(entries) => entries.get_mut(key),
+            Entries::Text(entries) => {
+                for word in Self::count_words(key).keys() {
+                    if let Some(postings) = entries.get_mut(word) {
+                        postings.retain(|&(other, _)| other != position);
+                        if postings.is_empty() {
+                            entries.remove(word);
+                        }
+                    }
+                }
+                return;
+            },
+        };
+        if let Some(positions) = positions {
+            positions.retain(|&other| other != position);
+            if positions.is_empty() {
+                match &mut self.entries {
+                    Entries::Ordered(entries) => { entries.remove(key); },
+                    Entries::Hashed(entries) => { entries.remove(key); },
+                    Entries::Text(_) => {},
+                };
+            }
+        }
+    }
+    // the row at `position` is gone and the ones after it move up
+    pub fn shift_after(&mut self, position: usize) {
+        let shift = |positions: &mut Vec<usize>| {
+            for other in positions.iter_mut().filter(|other| **other > position) {
+                *other -= 1;
+            }
+        };
+        match &mut self.entries {
+            Entries::Ordered(entries) => entries.values_mut().for_each(shift),
+            Entries::Hashed(entries) => entries.values_mut().for_each(shift),
+            Entries::Text(entries) => entries.values_mut()
+                .flat_map(|postings| postings.iter_mut())
+                .filter(|(other, _)| *other > position)
+                .for_each(|(other, _)| *other -= 1),
+        }
+    }
+    // positions of the rows whose first indexed cell is within the bounds, in the order of the rows;
+    // `None` for a hash or full-text index, which know nothing about the order
+    pub fn find_range(&self, lower: Bound<&IndexKey>, upper: Bound<&IndexKey>) -> Option<Vec<usize>> {
+        let entries = match &self.entries {
+            Entries::Ordered(entries) => entries,
+            Entries::Hashed(_) | Entries::Text(_) => return None,
+        };
+        let start = match lower {
+            Bound::Included(key) | Bound::Excluded(key) => Bound::Included(vec![key.clone()]),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let mut positions = entries.range((start, Bound::Unbounded))
+            .skip_while(|(key, _)| matches!(lower, Bound::Excluded(lower) if &key[0] == lower))
+            .take_while(|(key, _)| match upper {
+                Bound::Included(upper) => &key[0] <= upper,
+                Bound::Excluded(upper) => &key[0] < upper,
+                Bound::Unbounded => true,
+            })
+            .flat_map(|(_, positions)| positions.iter().copied())
+            .collect::<Vec<usize>>();
+        positions.sort_unstable();
+        Some(positions)
+    }
+    // positions of the rows whose indexed cells start with `prefix`, in the order of the rows;
+    // `None` if it's a hash index and the prefix isn't the whole key, or a full-text index
+    pub fn find(&self, prefix: &[IndexKey]) -> Option<Vec<usize>> {
+        match &self.entries {
+            Entries::Ordered(entries) => {
+                let mut positions = entries.range(prefix.to_vec()..)
+                    .take_while(|(key, _)| key.starts_with(prefix))
+                    .flat_map(|(_, positions)| positions.iter().copied())
+                    .collect::<Vec<usize>>();
+                positions.sort_unstable();
+                Some(positions)
+            },
+            Entries::Hashed(entries) if prefix.len() == self.columns.len() => {
+                Some(entries.get(prefix).cloned().unwrap_or_default())
+            },
+            Entries::Hashed(_) | Entries::Text(_) => None,
+        }
+    }
+    // positions of every row sorted by the indexed cells, the rows with equal cells keep their order;
+    // `None` for a hash or full-text index
+    pub fn get_sorted(&self, descending: bool) -> Option<Vec<usize>> {
+        let entries = match &self.entries {
+            Entries::Ordered(entries) => entries,
+            Entries::Hashed(_) | Entries::Text(_) => return None,
+        };
+        let flatten = |(_, positions): (&Vec<IndexKey>, &Vec<usize>)| positions.clone();
+        match descending {
+            true => Some(entries.iter().rev().flat_map(flatten).collect()),
+            false => Some(entries.iter().flat_map(flatten).collect()),
+        }
+    }
+    // an estimate of the bytes the index takes: the keys with the text they hold, the positions and
+    // the bookkeeping of the map, counted by its capacity for a hash map and a node per key otherwise
+    pub fn get_memory_size(&self) -> usize {
+        let key_size = |key: &Vec<IndexKey>| size_of::<Vec<IndexKey>>()
+            + key.capacity() * size_of::<IndexKey>()
+            + key.iter().map(|value| match value {
+                IndexKey::Str(value) => value.capacity(),
+                IndexKey::Decimal(_, _, Some(currency)) => currency.capacity(),
+                _ => 0,
+            }).sum::<usize>();
+        let positions_size = |positions: &Vec<usize>| size_of::<Vec<usize>>() + positions.capacity() * size_of::<usize>();
+        let entry_size = |(key, positions): (&Vec<IndexKey>, &Vec<usize>)| key_size(key) + positions_size(positions);
+        size_of::<Self>() + match &self.entries {
+            Entries::Ordered(entries) => entries.iter().map(entry_size).sum::<usize>() + entries.len() * BTREE_NODE_OVERHEAD,
+            Entries::Text(entries) => entries.iter()
+                .map(|(word, postings)| size_of::<String>() + word.capacity()
+                    + size_of::<Vec<(usize, usize)>>() + postings.capacity() * size_of::<(usize, usize)>())
+                .sum::<usize>() + entries.len() * BTREE_NODE_OVERHEAD,
+            Entries::Hashed(entries) => {
+                let empty_slots = entries.capacity() - entries.len();
+                entries.iter().map(entry_size).sum::<usize>()
+                    + empty_slots * (size_of::<Vec<IndexKey>>() + size_of::<Vec<usize>>())
+                    + entries.capacity()
+            },
+        }
+    }
+    // positions of the rows holding every word of the query, as a whole word or as the start of one,
+    // with their scores, best first; a word scores by how often the row has it and how rare it is
+    // among the `rows_count` rows, and a word that only starts with a query word scores half;
+    // `None` unless it's a full-text index
+    pub fn search(&self, query: &str, rows_count: usize) -> Option<Vec<(usize, f64)>> {
+        let entries = match &self.entries {
+            Entries::Text(entries) => entries,
+            _ => return None,
+        };
+        let words = tokenize(query);
+        let mut found: Option<BTreeMap<usize, f64>> = None;
+        for word in &words {
+            let mut scores = BTreeMap::<usize, f64>::default();
+            for (indexed, postings) in entries.range(word.clone()..)
+                .take_while(|(indexed, _)| indexed.starts_with(word.as_str()))
+            {
+                let weight = if indexed == word { 1.0 } else { 0.5 };
+                let rarity = (1.0 + rows_count as f64 / postings.len() as f64).ln();
+                for &(position, count) in postings {
+                    *scores.entry(position).or_default() += weight * count as f64 * rarity;
+                }
+            }
+            found = Some(match found {
+                None => scores,
+                Some(found) => found.into_iter()
+                    .filter_map(|(position, score)| scores.get(&position).map(|other| (position, score + other)))
+                    .collect(),
+            });
+        }
+        let mut found = found.unwrap_or_default().into_iter().collect::<Vec<(usize, f64)>>();
+        found.sort_by(|(_, lhs), (_, rhs)| rhs.total_cmp(lhs));
+        Some(found)
+    }
+    pub fn get_summary(&self) -> IndexSummary {
+        IndexSummary {
+            name: self.name.clone(),
+            kind: self.get_kind(),
+            columns: self.columns.clone(),
+            keys: self.len(),
+            memory_size: self.get_memory_size(),
+        }
+    }
+}
+
+// what there is to know about an index without its entries
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexSummary {
+    pub name: String,
+    pub kind: IndexKind,
+    pub columns: Vec<String>,
+    pub keys: usize,
+    pub memory_size: usize,
+}
+
+impl Display for IndexSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({}): {} keys, {}",
+            self.name,
+            self.kind,
+            self.columns.join(", "),
+            self.keys,
+            format_memory_size(self.memory_size)
+        )
+    }
+}
+
+// `1536` as `1.5 KiB`
+pub fn format_memory_size(bytes: usize) -> String {
+    match bytes {
+        bytes if bytes < 1024 => format!("{} B", bytes),
+        bytes if bytes < 1024 * 1024 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        bytes => format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+// true if an index on a column of the type orders the cells as they compare, see `ORDERED_TYPES`
+pub fn is_ordered_type(ty: &str) -> bool {
+    ORDERED_TYPES.contains(&ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+    use std::rc::Rc;
+    use crate::index::{format_memory_size, tokenize, Index, IndexKey, IndexKind, RealKey};
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::types::{CellValue, SUPPORTED_TYPES};
+
+    #[test]
+    fn test_index_lookups() {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("age", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .build();
+        let rows = [("Illia", "21"), ("Taras", "NULL"), ("Ivan", "40"), ("Lesya", "21")]
+            .iter()
+            .map(|(name, age)| {
+                let age = match *age {
+                    "NULL" => scheme.generate_null_value(1).unwrap(),
+                    age => scheme.generate_value(1, age.to_string()).unwrap(),
+                };
+                Rc::new(Row::new(vec![scheme.generate_value(0, name.to_string()).unwrap(), age]))
+            })
+            .collect::<Vec<Rc<Row<dyn CellValue>>>>();
+        let mut index = Index::new("by_age".to_string(), vec!["age".to_string(), "name".to_string()]);
+        assert!(index.check(&scheme).is_ok());
+        index.build(&scheme, &rows);
+
+        assert_eq!(index.find(&[IndexKey::Int(21)]).unwrap(), [0, 3]);
+        assert_eq!(index.find(&[IndexKey::Int(21), IndexKey::Str("Lesya".to_string())]).unwrap(), [3]);
+        assert_eq!(index.find_range(Bound::Excluded(&IndexKey::Int(21)), Bound::Unbounded).unwrap(), [1, 2]);
+        assert_eq!(index.find_range(Bound::Unbounded, Bound::Included(&IndexKey::Int(40))).unwrap(), [0, 2, 3]);
+        assert_eq!(index.get_sorted(false).unwrap(), [0, 3, 2, 1]);
+        assert_eq!(index.get_sorted(true).unwrap(), [1, 2, 3, 0]);
+
+        index.remove(&index.get_key(&scheme, &rows[0]), 0);
+        index.shift_after(0);
+        assert_eq!(index.find(&[IndexKey::Int(21)]).unwrap(), [2]);
+        index.insert(vec![IndexKey::Int(21), IndexKey::Str("Anna".to_string())], 3);
+        assert_eq!(index.find(&[IndexKey::Int(21)]).unwrap(), [2, 3]);
+
+        let mut hash_index = Index::with_kind("by_age".to_string(), vec!["age".to_string()], IndexKind::Hash);
+        hash_index.build(&scheme, &rows);
+        assert_eq!(hash_index.find(&[IndexKey::Int(21)]).unwrap(), [0, 3]);
+        assert_eq!(hash_index.find(&[IndexKey::Int(22)]).unwrap(), Vec::<usize>::new());
+        assert!(hash_index.find_range(Bound::Unbounded, Bound::Unbounded).is_none());
+        assert!(hash_index.get_sorted(false).is_none());
+        assert_eq!(hash_index.len(), 3);
+        assert!(hash_index.get_memory_size() > 3 * std::mem::size_of::<IndexKey>());
+        assert_eq!(IndexKind::parse("Hash").unwrap(), IndexKind::Hash);
+        assert_eq!(format_memory_size(1536), "1.5 KiB");
+
+        assert_eq!(RealKey::new(-0.0), RealKey::new(0.0));
+        assert!(Index::new("broken".to_string(), vec!["email".to_string()]).check(&scheme).is_err());
+        assert!(Index::new("broken".to_string(), vec!["age".to_string(), "age".to_string()]).check(&scheme).is_err());
+    }
+
+    #[test]
+    fn test_full_text_search() {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("id", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .with_column_definition(column("notes", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .build();
+        let rows = ["Feed the cat", "Buy cat food, then feed the CAT", "Catalog the books", "Walk the dog"]
+            .iter()
+            .enumerate()
+            .map(|(id, notes)| Rc::new(Row::new(vec![
+                scheme.generate_value(0, id.to_string()).unwrap(),
+                scheme.generate_value(1, notes.to_string()).unwrap(),
+            ])))
+            .collect::<Vec<Rc<Row<dyn CellValue>>>>();
+        assert_eq!(tokenize("Buy cat-food, NOW!"), ["buy", "cat", "food", "now"]);
+        assert!(Index::with_kind("by_id".to_string(), vec!["id".to_string()], IndexKind::FullText).check(&scheme).is_err());
+        let mut index = Index::with_kind("by_notes".to_string(), vec!["notes".to_string()], IndexKind::FullText);
+        assert!(index.check(&scheme).is_ok());
+        index.build(&scheme, &rows);
+
+        let positions = |query: &str, index: &Index| index.search(query, 4).unwrap()
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect::<Vec<usize>>();
+        assert_eq!(positions("cat", &index), [1, 0, 2]);
+        assert_eq!(positions("FEED cat", &index), [1, 0]);
+        assert_eq!(positions("fee", &index), [0, 1]);
+        assert_eq!(positions("bird", &index), Vec::<usize>::new());
+        assert!(index.find(&[IndexKey::Str("cat".to_string())]).is_none());
+
+        index.remove(&index.get_key(&scheme, &rows[0]), 0);
+        index.shift_after(0);
+        assert_eq!(positions("feed", &index), [0]);
+        assert_eq!(positions("dog", &index), [2]);
+    }
+}
\ No newline at end of file
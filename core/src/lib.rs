@@ -1,8 +1,16 @@
 pub mod row;
+pub mod row_literal;
 pub mod types;
 pub mod scheme;
 pub mod table;
 pub mod db;
+pub mod foreign_key;
+pub mod conversion;
+pub mod expression;
+pub mod view;
+pub mod index;
+pub mod planner;
+pub mod stats;
 
 #[macro_export]
 macro_rules! test_resources {
@@ -0,0 +1,581 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::Bound;
+use crate::expression::{BinaryOperator, Expression};
+use crate::index::{is_ordered_type, IndexKey, IndexKind};
+use crate::table::Table;
+use crate::types::ValueType;
+use crate::view::View;
+
+// share of the rows a comparison is taken to keep when the statistics tell nothing better
+const EQUALITY_SELECTIVITY: f64 = 0.1;
+const RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+const OTHER_SELECTIVITY: f64 = 0.5;
+
+// what the planner knows of a table: the number of rows and the number of distinct cells of the columns
+// an index keeps alone, which the table has at hand, and what the last profile of the table tells of
+// the distinct cells and the bounds of the numbers of the other columns
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableStats {
+    pub rows: usize,
+    pub distinct: HashMap<String, usize>,
+    // (min, max) of the numeric columns
+    pub bounds: HashMap<String, (f64, f64)>,
+}
+
+impl TableStats {
+    pub fn collect(table: &Table) -> Self {
+        let mut stats = Self {
+            rows: table.get_rows().len(),
+            ..Self::default()
+        };
+        let columns = table.get_columns();
+        let table_profile = table.get_profile();
+        let profiled = table_profile.iter()
+            .flat_map(|profile| profile.columns.iter())
+            .filter(|profile| columns.contains(&profile.name));
+        for profile in profiled {
+            let number = |value: &Option<String>| value.as_ref().and_then(|value| value.parse::<f64>().ok());
+            if let (Some(min), Some(max), Some(_)) = (number(&profile.min), number(&profile.max), profile.mean) {
+                stats.bounds.insert(profile.name.clone(), (min, max));
+            }
+            stats.distinct.insert(profile.name.clone(), profile.distinct);
+        }
+        let indexed = table.get_indexes()
+            .iter()
+            .filter(|index| index.get_kind() != IndexKind::FullText && index.get_columns().len() == 1)
+            .map(|index| (index.get_columns()[0].clone(), index.len()))
+            .collect::<Vec<(String, usize)>>();
+        stats.distinct.extend(indexed);
+        stats
+    }
+    // share of the rows whose `column` compares with `cell` as `operator` tells, assuming the numbers
+    // are spread evenly between the bounds
+    fn get_range_selectivity(&self, column: &str, operator: BinaryOperator, cell: &ValueType) -> f64 {
+        let value = match cell {
+            ValueType::Int(value) => value.get_value() as f64,
+            ValueType::Real(value) => value.get_value(),
+            _ => return RANGE_SELECTIVITY,
+        };
+        let (min, max) = match self.bounds.get(column) {
+            Some(&(min, max)) if min < max => (min, max),
+            _ => return RANGE_SELECTIVITY,
+        };
+        let below = ((value - min) / (max - min)).clamp(0.0, 1.0);
+        match operator {
+            BinaryOperator::Less | BinaryOperator::LessOrEqual => below,
+            _ => 1.0 - below,
+        }
+    }
+    // share of the rows whose `column` equals a given value
+    fn get_equality_selectivity(&self, column: &str) -> f64 {
+        match self.distinct.get(column) {
+            Some(&distinct) if distinct > 0 => 1.0 / distinct as f64,
+            _ => EQUALITY_SELECTIVITY,
+        }
+    }
+}
+
+// how the rows of a table that may pass a filter are found
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessPath {
+    Scan,
+    // the rows whose indexed column holds the key
+    IndexLookup { index: String, key: IndexKey },
+    // the rows whose indexed column is within the bounds
+    IndexRange { index: String, lower: Bound<IndexKey>, upper: Bound<IndexKey> },
+}
+
+impl AccessPath {
+    // positions of the rows to check with the filter, in the order of the rows; `None` for a scan
+    pub fn find_rows(&self, table: &Table) -> Option<Vec<usize>> {
+        let indexes = table.get_indexes();
+        let find_index = |name: &str| indexes.iter().find(|index| index.get_name() == name);
+        match self {
+            AccessPath::Scan => None,
+            AccessPath::IndexLookup { index, key } => find_index(index)?.find(std::slice::from_ref(key)),
+            AccessPath::IndexRange { index, lower, upper } => find_index(index)?.find_range(lower.as_ref(), upper.as_ref()),
+        }
+    }
+}
+
+// a step of a plan
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    Scan { table: String },
+    IndexLookup { table: String, index: String, kind: IndexKind, condition: String },
+    IndexRange { table: String, index: String, condition: String },
+    Filter { condition: String },
+    Sort { order: String },
+    // walks an index over the whole table keeping the rows that passed the steps below
+    IndexOrder { index: String, order: String },
+    HashJoin { column: String, build: String },
+    MergeJoin { column: String },
+    Project { columns: Vec<String> },
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Scan { table } => write!(f, "Scan {}", table),
+            Operation::IndexLookup { table, index, kind, condition } => write!(f, "Index lookup {}.{} ({}) on {}", table, index, kind, condition),
+            Operation::IndexRange { table, index, condition } => write!(f, "Index range {}.{} on {}", table, index, condition),
+            Operation::Filter { condition } => write!(f, "Filter {}", condition),
+            Operation::Sort { order } => write!(f, "Sort by {}", order),
+            Operation::IndexOrder { index, order } => write!(f, "Index order {} by {}", index, order),
+            Operation::HashJoin { column, build } => write!(f, "Hash join on {}, built from {}", column, build),
+            Operation::MergeJoin { column } => write!(f, "Merge join on {}", column),
+            Operation::Project { columns } => write!(f, "Project {}", columns.join(", ")),
+        }
+    }
+}
+
+// a plan as a tree of steps, each reading the rows its children give; `rows` is the estimate of the
+// rows the step gives and `cost` the estimate of the rows handled up to it, itself included
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanNode {
+    pub operation: Operation,
+    pub rows: f64,
+    pub cost: f64,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    pub fn new(operation: Operation, rows: f64, cost: f64) -> Self {
+        Self {
+            operation,
+            rows,
+            cost,
+            children: Vec::default(),
+        }
+    }
+    pub fn with_child(mut self, child: PlanNode) -> Self {
+        self.children.push(child);
+        self
+    }
+    fn write_tree(&self, f: &mut Formatter<'_>, prefix: &str, child_prefix: &str) -> std::fmt::Result {
+        writeln!(f, "{}{} (rows {:.0}, cost {:.1})", prefix, self.operation, self.rows, self.cost)?;
+        for (number, child) in self.children.iter().enumerate() {
+            match number + 1 == self.children.len() {
+                true => child.write_tree(f, &format!("{}└─ ", child_prefix), &format!("{}   ", child_prefix))?,
+                false => child.write_tree(f, &format!("{}├─ ", child_prefix), &format!("{}│  ", child_prefix))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// the tree, a step per line with its children below it
+impl Display for PlanNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_tree(f, "", "")
+    }
+}
+
+// what `View::apply` does to read the view
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewPlan {
+    pub access: AccessPath,
+    // the index giving the rows in the order of the view, if walking it is cheaper than sorting them
+    pub order_index: Option<String>,
+    pub tree: PlanNode,
+}
+
+// plans the view over the rows of `source`; `input` is the plan giving them unless they are
+// the rows of a table, in which case its indexes may find them
+pub fn plan_view(view: &View, source: &Table, input: Option<PlanNode>) -> Result<ViewPlan, String> {
+    let scheme = source.get_scheme();
+    view.check(&scheme.get_columns())?;
+    let stats = TableStats::collect(source);
+    let filter = view.filter.as_ref().map(|filter| Expression::parse(filter)).transpose()?;
+    let terms = filter.as_ref().map(get_and_terms).unwrap_or_default();
+
+    let from_table = input.is_none();
+    let rows = input.as_ref().map(|input| input.rows).unwrap_or(stats.rows as f64);
+    let (access, mut tree) = match input {
+        Some(input) => (AccessPath::Scan, input),
+        None => choose_access_path(&terms, source, &stats),
+    };
+    if let Some(filter) = &filter {
+        let selectivity = terms.iter().map(|term| estimate_selectivity(term, source, &stats)).product::<f64>();
+        let filtered = (rows * selectivity).min(tree.rows);
+        let cost = tree.cost + tree.rows;
+        tree = PlanNode::new(Operation::Filter { condition: filter.to_string() }, filtered, cost).with_child(tree);
+    }
+
+    let mut order_index = None;
+    if !view.order_by.is_empty() {
+        let order = view.order_by.iter()
+            .map(|(column, descending)| if *descending { format!("{} DESC", column) } else { column.clone() })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let sort_cost = tree.cost + get_sort_cost(tree.rows);
+        let index_cost = tree.cost + rows;
+        order_index = match from_table && index_cost < sort_cost {
+            true => find_order_index(view, source),
+            false => None,
+        };
+        let operation = match &order_index {
+            Some(index) => Operation::IndexOrder { index: index.clone(), order },
+            None => Operation::Sort { order },
+        };
+        let cost = if order_index.is_some() { index_cost } else { sort_cost };
+        tree = PlanNode::new(operation, tree.rows, cost).with_child(tree);
+    }
+    if !view.columns.is_empty() {
+        tree = PlanNode::new(Operation::Project { columns: view.columns.clone() }, tree.rows, tree.cost).with_child(tree);
+    }
+    Ok(ViewPlan {
+        access,
+        order_index,
+        tree,
+    })
+}
+
+// the parts of an `and` chain
+fn get_and_terms(filter: &Expression) -> Vec<&Expression> {
+    match filter {
+        Expression::Binary(BinaryOperator::And, lhs, rhs) => {
+            let mut terms = get_and_terms(lhs);
+            terms.extend(get_and_terms(rhs));
+            terms
+        },
+        filter => vec![filter],
+    }
+}
+
+// (column, operator, cell) of a term comparing a column of the table with a value, the column first
+fn get_comparison(term: &Expression, source: &Table) -> Option<(String, BinaryOperator, ValueType)> {
+    let (column, value, operator) = match term {
+        Expression::Binary(operator, lhs, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+            (Expression::Column(column), Expression::Literal(value)) => (column, value, *operator),
+            (Expression::Literal(value), Expression::Column(column)) => (column, value, operator.flip()?),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let scheme = source.get_scheme();
+    let index = scheme.get_column_index(column)?;
+    let cell = scheme.generate_value(index, value.to_raw_value()?).ok()?.get_value();
+    Some((column.clone(), operator, cell))
+}
+
+fn estimate_selectivity(term: &Expression, source: &Table, stats: &TableStats) -> f64 {
+    match get_comparison(term, source) {
+        Some((column, BinaryOperator::Equal, _)) => stats.get_equality_selectivity(&column),
+        Some((column, operator @ (BinaryOperator::Less | BinaryOperator::LessOrEqual | BinaryOperator::Greater | BinaryOperator::GreaterOrEqual), cell)) => {
+            stats.get_range_selectivity(&column, operator, &cell)
+        },
+        _ => OTHER_SELECTIVITY,
+    }
+}
+
+// the cheapest way to find the rows that may pass the filter made of `terms`: a scan, or an index on
+// a column one of them compares with a value. Ranges are only looked up in the columns an index
+// orders as the filter compares them
+fn choose_access_path(terms: &[&Expression], source: &Table, stats: &TableStats) -> (AccessPath, PlanNode) {
+    let table = source.get_name().to_string();
+    let rows = stats.rows as f64;
+    let mut best = (AccessPath::Scan, PlanNode::new(Operation::Scan { table: table.clone() }, rows, rows));
+    let scheme = source.get_scheme();
+    let null = IndexKey::Null;
+    for term in terms {
+        let Some((column, operator, cell)) = get_comparison(term, source) else {
+            continue;
+        };
+        let key = IndexKey::from_value(&cell);
+        let ordered = scheme.get_column_index(&column).is_some_and(|index| is_ordered_type(&scheme.get_types()[index]));
+        let bounds = match operator {
+            BinaryOperator::Less => Some((Bound::Unbounded, Bound::Excluded(key.clone()))),
+            BinaryOperator::LessOrEqual => Some((Bound::Unbounded, Bound::Included(key.clone()))),
+            BinaryOperator::Greater => Some((Bound::Excluded(key.clone()), Bound::Excluded(null.clone()))),
+            BinaryOperator::GreaterOrEqual => Some((Bound::Included(key.clone()), Bound::Excluded(null.clone()))),
+            _ => None,
+        };
+        for index in source.get_indexes().iter().filter(|index| index.get_columns()[0] == column) {
+            let lookup_cost = match index.get_kind() {
+                IndexKind::Hash => 1.0,
+                _ => rows.max(2.0).log2(),
+            };
+            let (path, operation, found) = match (index.get_kind(), operator, &bounds) {
+                (IndexKind::Hash, BinaryOperator::Equal, _) if index.get_columns().len() == 1 => (
+                    AccessPath::IndexLookup { index: index.get_name().to_string(), key: key.clone() },
+                    Operation::IndexLookup { table: table.clone(), index: index.get_name().to_string(), kind: IndexKind::Hash, condition: term.to_string() },
+                    rows * stats.get_equality_selectivity(&column),
+                ),
+                (IndexKind::BTree, BinaryOperator::Equal, _) => (
+                    AccessPath::IndexLookup { index: index.get_name().to_string(), key: key.clone() },
+                    Operation::IndexLookup { table: table.clone(), index: index.get_name().to_string(), kind: IndexKind::BTree, condition: term.to_string() },
+                    rows * stats.get_equality_selectivity(&column),
+                ),
+                (IndexKind::BTree, _, Some((lower, upper))) if ordered => (
+                    AccessPath::IndexRange { index: index.get_name().to_string(), lower: lower.clone(), upper: upper.clone() },
+                    Operation::IndexRange { table: table.clone(), index: index.get_name().to_string(), condition: term.to_string() },
+                    rows * stats.get_range_selectivity(&column, operator, &cell),
+                ),
+                _ => continue,
+            };
+            let cost = lookup_cost + found;
+            if cost < best.1.cost {
+                best = (path, PlanNode::new(operation, found, cost));
+            }
+        }
+    }
+    best
+}
+
+// an index on exactly the columns of the order, sorted all one way, of the types it orders as cells
+fn find_order_index(view: &View, source: &Table) -> Option<String> {
+    let (_, descending) = *view.order_by.first()?;
+    if view.order_by.iter().any(|(_, other)| *other != descending) {
+        return None;
+    }
+    let scheme = source.get_scheme();
+    let columns = view.order_by.iter().map(|(column, _)| column.clone()).collect::<Vec<String>>();
+    let ordered = columns.iter().all(|column| scheme.get_column_index(column)
+        .is_some_and(|index| is_ordered_type(&scheme.get_types()[index])));
+    if !ordered {
+        return None;
+    }
+    source.get_indexes()
+        .iter()
+        .find(|index| index.get_kind() == IndexKind::BTree && index.get_columns() == columns)
+        .map(|index| index.get_name().to_string())
+}
+
+fn get_sort_cost(rows: f64) -> f64 {
+    rows * rows.max(2.0).log2()
+}
+
+// how the rows of two tables are matched by the text of their cells in the join column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JoinMethod {
+    // a hash table of the smaller table is probed with the rows of the other one
+    Hash { build_left: bool },
+    // both tables are walked in the order of the cells, given by the index if there is one
+    Merge { lhs_index: Option<String>, rhs_index: Option<String> },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinPlan {
+    pub method: JoinMethod,
+    pub tree: PlanNode,
+}
+
+impl JoinPlan {
+    // (cell, position of the row of the left table, of the right one) for every distinct cell of
+    // the join column in either table, ordered by the cells, `keys` being the cells as text;
+    // of the rows sharing a cell only the first one is matched
+    pub fn join_rows(&self, lhs: &Table, lhs_keys: &[String], rhs: &Table, rhs_keys: &[String]) -> Vec<(String, Option<usize>, Option<usize>)> {
+        match &self.method {
+            JoinMethod::Hash { build_left } => hash_join(lhs_keys, rhs_keys, *build_left),
+            JoinMethod::Merge { lhs_index, rhs_index } => {
+                let sorted = |table: &Table, index: &Option<String>| index.as_ref().and_then(|name| table.get_indexes()
+                    .iter()
+                    .find(|index| index.get_name() == name)
+                    .and_then(|index| index.get_sorted(false)));
+                merge_join(lhs_keys, sorted(lhs, lhs_index), rhs_keys, sorted(rhs, rhs_index))
+            },
+        }
+    }
+}
+
+// the cheaper of the hash and the merge join of the tables on `column`. A hash join is built from the
+// smaller table and sorts what it gives, a merge join sorts both tables unless an index keeps the
+// cells of one in the order of their text, which is so for the strings
+pub fn plan_join(lhs: &Table, rhs: &Table, column: &str) -> JoinPlan {
+    let (lhs_rows, rhs_rows) = (lhs.get_rows().len() as f64, rhs.get_rows().len() as f64);
+    let rows = lhs_rows.max(rhs_rows);
+    let scan = |table: &Table, rows: f64| PlanNode::new(Operation::Scan { table: table.get_name().to_string() }, rows, rows);
+    let text_index = |table: &Table| {
+        let scheme = table.get_scheme();
+        let is_string = scheme.get_column_index(column).is_some_and(|index| scheme.get_types()[index] == "StringValue");
+        table.get_indexes()
+            .iter()
+            .filter(|_| is_string)
+            .find(|index| index.get_kind() == IndexKind::BTree && index.get_columns() == [column])
+            .map(|index| index.get_name().to_string())
+    };
+    let sorted = |table: &Table, rows: f64, index: &Option<String>| match index {
+        Some(index) => PlanNode::new(Operation::IndexOrder { index: index.clone(), order: column.to_string() }, rows, rows)
+            .with_child(scan(table, rows)),
+        None => PlanNode::new(Operation::Sort { order: column.to_string() }, rows, rows + get_sort_cost(rows))
+            .with_child(scan(table, rows)),
+    };
+
+    let (lhs_index, rhs_index) = (text_index(lhs), text_index(rhs));
+    let lhs_sorted = sorted(lhs, lhs_rows, &lhs_index);
+    let rhs_sorted = sorted(rhs, rhs_rows, &rhs_index);
+    let merge_cost = lhs_sorted.cost + rhs_sorted.cost + lhs_rows + rhs_rows;
+    let hash_cost = lhs_rows + rhs_rows + get_sort_cost(rows);
+    if merge_cost < hash_cost {
+        return JoinPlan {
+            method: JoinMethod::Merge { lhs_index, rhs_index },
+            tree: PlanNode::new(Operation::MergeJoin { column: column.to_string() }, rows, merge_cost)
+                .with_child(lhs_sorted)
+                .with_child(rhs_sorted),
+        };
+    }
+    let build_left = lhs_rows <= rhs_rows;
+    let (build, probe) = match build_left {
+        true => (scan(lhs, lhs_rows), scan(rhs, rhs_rows)),
+        false => (scan(rhs, rhs_rows), scan(lhs, lhs_rows)),
+    };
+    let build_name = if build_left { lhs.get_name() } else { rhs.get_name() };
+    JoinPlan {
+        method: JoinMethod::Hash { build_left },
+        tree: PlanNode::new(Operation::HashJoin { column: column.to_string(), build: build_name.to_string() }, rows, hash_cost)
+            .with_child(build)
+            .with_child(probe),
+    }
+}
+
+fn hash_join(lhs_keys: &[String], rhs_keys: &[String], build_left: bool) -> Vec<(String, Option<usize>, Option<usize>)> {
+    let (build_keys, probe_keys) = if build_left { (lhs_keys, rhs_keys) } else { (rhs_keys, lhs_keys) };
+    let mut built = HashMap::<&str, usize>::default();
+    for (position, key) in build_keys.iter().enumerate() {
+        built.entry(key.as_str()).or_insert(position);
+    }
+    let mut probed = HashSet::<&str>::default();
+    let mut joined = Vec::<(String, Option<usize>, Option<usize>)>::default();
+    for (position, key) in probe_keys.iter().enumerate() {
+        if probed.insert(key.as_str()) {
+            joined.push((key.clone(), built.remove(key.as_str()), Some(position)));
+        }
+    }
+    joined.extend(built.into_iter().map(|(key, position)| (key.to_string(), Some(position), None)));
+    joined.sort_by(|(lhs, _, _), (rhs, _, _)| lhs.cmp(rhs));
+    joined.into_iter()
+        .map(|(key, built, probed)| if build_left { (key, built, probed) } else { (key, probed, built) })
+        .collect()
+}
+
+// `order` is the positions sorted by the cells, if known; it's sorted again as a NULL goes last in an
+// index but by its text here, which costs next to nothing for the rest
+fn merge_join(lhs_keys: &[String], lhs_order: Option<Vec<usize>>, rhs_keys: &[String], rhs_order: Option<Vec<usize>>) -> Vec<(String, Option<usize>, Option<usize>)> {
+    let sorted = |keys: &[String], order: Option<Vec<usize>>| {
+        let mut order = order.unwrap_or_else(|| (0..keys.len()).collect());
+        order.sort_by(|&lhs, &rhs| keys[lhs].cmp(&keys[rhs]).then(lhs.cmp(&rhs)));
+        order
+    };
+    let (lhs, rhs) = (sorted(lhs_keys, lhs_order), sorted(rhs_keys, rhs_order));
+    let (mut lhs_at, mut rhs_at) = (0, 0);
+    let mut joined = Vec::<(String, Option<usize>, Option<usize>)>::default();
+    while lhs_at < lhs.len() || rhs_at < rhs.len() {
+        let lhs_key = lhs.get(lhs_at).map(|&position| lhs_keys[position].as_str());
+        let rhs_key = rhs.get(rhs_at).map(|&position| rhs_keys[position].as_str());
+        let key = match (lhs_key, rhs_key) {
+            (Some(lhs_key), Some(rhs_key)) => lhs_key.min(rhs_key),
+            (Some(key), None) | (None, Some(key)) => key,
+            (None, None) => break,
+        };
+        let lhs_row = (lhs_key == Some(key)).then(|| lhs[lhs_at]);
+        let rhs_row = (rhs_key == Some(key)).then(|| rhs[rhs_at]);
+        while lhs_at < lhs.len() && lhs_keys[lhs[lhs_at]] == key {
+            lhs_at += 1;
+        }
+        while rhs_at < rhs.len() && rhs_keys[rhs[rhs_at]] == key {
+            rhs_at += 1;
+        }
+        joined.push((key.to_string(), lhs_row, rhs_row));
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index::IndexKind;
+    use crate::planner::{hash_join, merge_join, plan_join, plan_view, AccessPath, JoinMethod, Operation, TableStats};
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::table::Table;
+    use crate::types::{CellValue, SUPPORTED_TYPES};
+    use crate::view::{parse_order_by, View, ViewSource};
+
+    // `rows` rows named `n0000` on, their values going round 0 to 49
+    fn numbers(name: &str, rows: usize) -> Table {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("value", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .build();
+        let table = Table::new(name.to_string(), scheme);
+        for number in 0..rows {
+            let scheme = table.get_scheme();
+            table.add_row(Row::new(vec![
+                scheme.generate_value(0, format!("n{:04}", number)).unwrap(),
+                scheme.generate_value(1, (number % 50).to_string()).unwrap(),
+            ]));
+        }
+        table
+    }
+
+    fn view(filter: Option<&str>, order: &str) -> View {
+        View::new("view".to_string(), ViewSource::Table("numbers".to_string()))
+            .with_filter(filter)
+            .unwrap()
+            .with_order_by(parse_order_by(order).unwrap())
+    }
+
+    #[test]
+    fn test_plan_picks_access_paths() {
+        let table = numbers("numbers", 1000);
+        let scanned = table.clone();
+        let equal = view(Some("value = 7 and name != 'n0007'"), "name desc");
+        let range = view(Some("value < 10"), "");
+        let ordered = view(None, "value");
+        assert_eq!(plan_view(&equal, &table, None).unwrap().access, AccessPath::Scan);
+        assert_eq!(plan_view(&range, &table, None).unwrap().tree.rows.round(), 333.0);
+        table.update_profile();
+        let stats = TableStats::collect(&table);
+        assert_eq!((stats.distinct.get("value"), stats.bounds.get("value")), (Some(&50), Some(&(0.0, 49.0))));
+        assert_eq!(plan_view(&range, &table, None).unwrap().tree.rows.round(), 204.0);
+
+        table.add_index("by_value", vec!["value".to_string()], IndexKind::Hash).unwrap();
+        table.add_index("by_value_ordered", vec!["value".to_string()], IndexKind::BTree).unwrap();
+        let plan = plan_view(&equal, &table, None).unwrap();
+        assert!(matches!(plan.access, AccessPath::IndexLookup { ref index, .. } if index == "by_value"));
+        assert_eq!(plan.order_index, None);
+        assert_eq!(plan.tree.children[0].rows, 10.0);
+        assert_eq!(plan.tree.children[0].children[0].rows, 20.0);
+        assert!(matches!(plan.tree.operation, Operation::Sort { .. }));
+        assert!(matches!(plan_view(&range, &table, None).unwrap().access, AccessPath::IndexRange { .. }));
+        let plan = plan_view(&ordered, &table, None).unwrap();
+        assert_eq!(plan.order_index.as_deref(), Some("by_value_ordered"));
+        assert!(plan.tree.to_string().starts_with("Index order by_value_ordered by value"));
+        assert!(plan.tree.to_string().ends_with("└─ Scan numbers (rows 1000, cost 1000.0)\n"));
+
+        for view in [equal, range, ordered] {
+            let rows = |table: &Table| view.apply(table).unwrap()
+                .get_rows()
+                .iter()
+                .map(|row| row.get_values().iter().map(|value| value.get_value().to_raw_value()).collect::<Vec<Option<String>>>())
+                .collect::<Vec<Vec<Option<String>>>>();
+            assert_eq!(rows(&table), rows(&scanned));
+        }
+    }
+
+    #[test]
+    fn test_plan_join_methods() {
+        let (lhs, rhs) = (numbers("lhs", 100), numbers("rhs", 30));
+        assert_eq!(plan_join(&lhs, &rhs, "name").method, JoinMethod::Hash { build_left: false });
+        lhs.add_index("by_name", vec!["name".to_string()], IndexKind::BTree).unwrap();
+        rhs.add_index("by_name", vec!["name".to_string()], IndexKind::BTree).unwrap();
+        let plan = plan_join(&lhs, &rhs, "name");
+        assert_eq!(plan.method, JoinMethod::Merge { lhs_index: Some("by_name".to_string()), rhs_index: Some("by_name".to_string()) });
+        assert_eq!(plan.tree.children.len(), 2);
+
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<String>>();
+        let (lhs_keys, rhs_keys) = (keys(&["b", "a", "NULL", "c", "NULL"]), keys(&["c", "d", "NULL"]));
+        let joined = vec![
+            ("NULL".to_string(), Some(2), Some(2)),
+            ("a".to_string(), Some(1), None),
+            ("b".to_string(), Some(0), None),
+            ("c".to_string(), Some(3), Some(0)),
+            ("d".to_string(), None, Some(1)),
+        ];
+        assert_eq!(hash_join(&lhs_keys, &rhs_keys, true), joined);
+        assert_eq!(hash_join(&lhs_keys, &rhs_keys, false), joined);
+        assert_eq!(merge_join(&lhs_keys, None, &rhs_keys, Some(vec![2, 0, 1])), joined);
+    }
+}
\ No newline at end of file
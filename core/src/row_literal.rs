@@ -0,0 +1,260 @@
+// Row literals are what users type for a row: `1; "Bob; Jr"; NULL` or `name='Bob'; email=b@x.io`,
+// and what an imported file holds, one per line.
+// Fields are separated by `;`, a field is optionally prefixed with `column=`. Unquoted values are
+// trimmed and may escape special characters with `\`, quoted ones (`"..."` or `'...'`) are taken as
+// they are, so they can hold `;`, `=`, surrounding spaces or the empty string. An unquoted `NULL`
+// is a null cell and an empty unquoted field is an omitted one
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LiteralValue {
+    // nothing was written, the column decides what goes there
+    Omitted,
+    Null,
+    Value(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiteralField {
+    pub column: Option<String>,
+    pub value: LiteralValue,
+}
+
+impl LiteralField {
+    pub fn new(column: Option<String>, value: LiteralValue) -> Self {
+        Self {
+            column,
+            value,
+        }
+    }
+}
+
+pub fn parse_row_literal(input: &str) -> Result<Vec<LiteralField>, String> {
+    let mut fields = Vec::default();
+    let mut chars = input.chars().peekable();
+    loop {
+        fields.push(parse_field(&mut chars)?);
+        match chars.next() {
+            Some(';') => continue,
+            None => break,
+            Some(c) => return Err(format!("unexpected {} in the row literal: {}", c, input)),
+        }
+    }
+    Ok(fields)
+}
+
+// writes the values back as a positional row literal that `parse_row_literal` reads unchanged
+pub fn format_row_literal(values: &[LiteralValue]) -> String {
+    values.iter()
+        .map(|value| match value {
+            LiteralValue::Omitted => String::new(),
+            LiteralValue::Null => "NULL".to_string(),
+            LiteralValue::Value(value) => quote_value(value),
+        })
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+// quotes the value if reading it unquoted would change it
+pub fn quote_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.trim() != value
+        || value.eq_ignore_ascii_case("null")
+        || value.contains([';', '=', '"', '\'', '\\', '\n', '\t']);
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn parse_field(chars: &mut Chars) -> Result<LiteralField, String> {
+    skip_whitespace(chars);
+    let column = parse_column_prefix(chars);
+    skip_whitespace(chars);
+    let value = match chars.peek() {
+        Some(&quote) if quote == '"' || quote == '\'' => {
+            chars.next();
+            let value = parse_quoted(chars, quote)?;
+            skip_whitespace(chars);
+            if let Some(&c) = chars.peek() {
+                if c != ';' {
+                    return Err(format!("unexpected {} after the quoted value \"{}\"", c, value));
+                }
+            }
+            LiteralValue::Value(value)
+        },
+        _ => {
+            let (value, escaped) = parse_unquoted(chars)?;
+            if value.is_empty() && !escaped {
+                LiteralValue::Omitted
+            } else if value.eq_ignore_ascii_case("null") && !escaped {
+                LiteralValue::Null
+            } else {
+                LiteralValue::Value(value)
+            }
+        },
+    };
+    Ok(LiteralField::new(column, value))
+}
+
+// `column=` in front of the value; anything else is left for the value itself
+fn parse_column_prefix(chars: &mut Chars) -> Option<String> {
+    let mut lookahead = chars.clone();
+    let mut column = String::new();
+    while let Some(&c) = lookahead.peek() {
+        if !(c.is_alphanumeric() || c == '_') {
+            break;
+        }
+        column.push(c);
+        lookahead.next();
+    }
+    while lookahead.peek().is_some_and(|c| c.is_whitespace()) {
+        lookahead.next();
+    }
+    if column.is_empty() || lookahead.next() != Some('=') {
+        return None;
+    }
+    *chars = lookahead;
+    Some(column)
+}
+
+fn parse_quoted(chars: &mut Chars, quote: char) -> Result<String, String> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some(c) if c == quote => return Ok(value),
+            Some('\\') => value.push(parse_escape(chars)?),
+            Some(c) => value.push(c),
+            None => return Err(format!("missing closing {} after {}", quote, value)),
+        }
+    }
+}
+
+// the value is trimmed, escaped characters count as part of it even when they are spaces
+fn parse_unquoted(chars: &mut Chars) -> Result<(String, bool), String> {
+    let mut value = String::new();
+    let mut escaped = false;
+    let mut kept_length = 0;
+    while let Some(&c) = chars.peek() {
+        if c == ';' {
+            break;
+        }
+        chars.next();
+        if c == '\\' {
+            value.push(parse_escape(chars)?);
+            escaped = true;
+            kept_length = value.len();
+        } else {
+            value.push(c);
+            if !c.is_whitespace() {
+                kept_length = value.len();
+            }
+        }
+    }
+    value.truncate(kept_length);
+    Ok((value, escaped))
+}
+
+fn parse_escape(chars: &mut Chars) -> Result<char, String> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some(c) => Ok(c),
+        None => Err("the row literal ends with an unfinished escape".to_string()),
+    }
+}
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::row_literal::{format_row_literal, parse_row_literal, quote_value, LiteralField, LiteralValue};
+
+    fn value(value: &str) -> LiteralField {
+        LiteralField::new(None, LiteralValue::Value(value.to_string()))
+    }
+
+    #[test]
+    fn test_parse_positional_values() {
+        let fields = parse_row_literal(" 1 ; Bob Marley ;NULL;; \"\"").unwrap();
+        assert_eq!(fields, vec![
+            value("1"),
+            value("Bob Marley"),
+            LiteralField::new(None, LiteralValue::Null),
+            LiteralField::new(None, LiteralValue::Omitted),
+            value(""),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_quoted_and_escaped_values() {
+        let fields = parse_row_literal(r#""a; b";' padded ';"say \"hi\"";semi\;colon;"NULL";trailing\ ;x=\=y"#).unwrap();
+        assert_eq!(fields, vec![
+            value("a; b"),
+            value(" padded "),
+            value("say \"hi\""),
+            value("semi;colon"),
+            value("NULL"),
+            value("trailing "),
+            LiteralField::new(Some("x".to_string()), LiteralValue::Value("=y".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_named_values() {
+        let fields = parse_row_literal("name = 'Bob'; email=b@x.io; age=NULL; note=").unwrap();
+        assert_eq!(fields, vec![
+            LiteralField::new(Some("name".to_string()), LiteralValue::Value("Bob".to_string())),
+            LiteralField::new(Some("email".to_string()), LiteralValue::Value("b@x.io".to_string())),
+            LiteralField::new(Some("age".to_string()), LiteralValue::Null),
+            LiteralField::new(Some("note".to_string()), LiteralValue::Omitted),
+        ]);
+        assert_eq!(parse_row_literal("\"a=b\"").unwrap(), vec![value("a=b")]);
+    }
+
+    #[test]
+    fn test_parse_invalid_literals() {
+        assert!(parse_row_literal("\"unterminated").is_err());
+        assert!(parse_row_literal("\"quoted\" tail").is_err());
+        assert!(parse_row_literal("ends with \\").is_err());
+    }
+
+    #[test]
+    fn test_format_row_literal_round_trip() {
+        let values = vec![
+            LiteralValue::Value("plain".to_string()),
+            LiteralValue::Value("a; b".to_string()),
+            LiteralValue::Value(" padded\t".to_string()),
+            LiteralValue::Value("null".to_string()),
+            LiteralValue::Value("".to_string()),
+            LiteralValue::Value("quote \" and \\".to_string()),
+            LiteralValue::Null,
+            LiteralValue::Omitted,
+        ];
+        let literal = format_row_literal(&values);
+        let parsed = parse_row_literal(&literal).unwrap()
+            .into_iter()
+            .map(|field| field.value)
+            .collect::<Vec<LiteralValue>>();
+        assert_eq!(parsed, values);
+        assert_eq!(quote_value("plain"), "plain");
+        assert_eq!(quote_value("a=b"), "\"a=b\"");
+    }
+}
\ No newline at end of file
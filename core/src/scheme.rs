@@ -1,7 +1,331 @@
 #![allow(clippy::type_complexity)]
+use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 use std::sync::Arc;
-use crate::types::CellValue;
+use regex::Regex;
+use crate::expression::{Datum, Expression};
+use crate::types::{CellValue, ValueBuilder, ValueType, SUPPORTED_TYPES};
+use crate::types::{check_type_parameters, parse_type_declaration};
+use crate::types::decimal_value::DecimalValue;
+use crate::types::int_value::IntValue;
+use crate::types::null_value::NullValue;
+use crate::types::string_value::StringValue;
+
+// restrictions a column puts on its cells on top of the type, declared after the type as
+// `IntValue range(0, 120) not_null default(18)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    NotNull,
+    // bounds on the number of characters of textual values
+    Length { min: Option<usize>, max: Option<usize> },
+    // raw bounds, parsed with the column type on every check
+    Range { min: Option<String>, max: Option<String> },
+    // the whole textual value has to match the pattern
+    Regex(Pattern),
+    // raw value used when a row doesn't provide the cell
+    Default(String),
+    // the table fills an omitted cell with the next value of the column's sequence
+    AutoIncrement,
+    // the cell is always the value of the expression over the other cells of the row, see
+    // `crate::expression`; kept in the canonical form the expression prints itself in
+    Computed(String),
+}
+
+impl Constraint {
+    // parses a single constraint of a column declared with `ty(parameters)`
+    pub fn parse(ty: &str, parameters: &[String], declaration: &str) -> Result<Self, String> {
+        let (kind, arguments) = match declaration.split_once('(') {
+            Some((kind, rest)) => match rest.strip_suffix(')') {
+                Some(arguments) => (kind.trim().to_lowercase(), Some(arguments)),
+                None => return Err(format!("missing closing bracket in the constraint: {}", declaration)),
+            },
+            None => (declaration.trim().to_lowercase(), None),
+        };
+        let constraint = match (kind.as_str(), arguments) {
+            ("not_null", None) => Constraint::NotNull,
+            ("auto_increment", None) if ty == IntValue::get_type_name() => Constraint::AutoIncrement,
+            ("auto_increment", None) => return Err(format!("auto_increment constraint can't be applied to {}", ty)),
+            ("length", Some(arguments)) => {
+                let bounds = arguments.split(',').map(|bound| bound.trim()).collect::<Vec<&str>>();
+                let parse_bound = |bound: &str| -> Result<Option<usize>, String> {
+                    if bound.is_empty() {
+                        return Ok(None);
+                    }
+                    bound.parse::<usize>()
+                        .map(Some)
+                        .map_err(|_| format!("invalid length bound: {}", bound))
+                };
+                let (min, max) = match bounds.as_slice() {
+                    [max] => (None, parse_bound(max)?),
+                    [min, max] => (parse_bound(min)?, parse_bound(max)?),
+                    _ => return Err(format!("length expects (max) or (min, max), got ({})", arguments)),
+                };
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(format!("length bounds are reversed: {} > {}", min, max));
+                    }
+                }
+                Constraint::Length { min, max }
+            },
+            ("range", Some(arguments)) => parse_range(ty, parameters, arguments)?,
+            ("regex", Some(pattern)) => Constraint::Regex(Pattern::new(pattern)?),
+            ("default", Some(value)) => Constraint::Default(value.trim().to_string()),
+            ("computed", Some(source)) => Constraint::Computed(Expression::parse(source)?.to_string()),
+            _ => return Err(format!("unknown constraint: {}", declaration)),
+        };
+        match &constraint {
+            Constraint::Length { .. } | Constraint::Regex(_) if !is_textual(ty) => {
+                Err(format!("{} constraint can't be applied to {}", constraint.get_kind(), ty))
+            },
+            _ => Ok(constraint),
+        }
+    }
+
+    // builds a constraint back from its persisted form, see `get_kind` and `get_arguments`
+    pub fn from_parts(kind: &str, arguments: &[String]) -> Result<Self, String> {
+        let argument = |index: usize| -> Option<String> {
+            arguments.get(index).filter(|argument| !argument.is_empty()).cloned()
+        };
+        match kind {
+            "not_null" => Ok(Constraint::NotNull),
+            "auto_increment" => Ok(Constraint::AutoIncrement),
+            "length" => {
+                let parse_bound = |bound: Option<String>| -> Result<Option<usize>, String> {
+                    bound.map(|bound| bound.parse::<usize>().map_err(|err| err.to_string())).transpose()
+                };
+                Ok(Constraint::Length { min: parse_bound(argument(0))?, max: parse_bound(argument(1))? })
+            },
+            "range" => Ok(Constraint::Range { min: argument(0), max: argument(1) }),
+            "regex" => match argument(0) {
+                Some(pattern) => Ok(Constraint::Regex(Pattern::new(&pattern)?)),
+                None => Err("regex constraint without a pattern".to_string()),
+            },
+            "default" => argument(0).map(Constraint::Default).ok_or("default constraint without a value".to_string()),
+            "computed" => argument(0).map(Constraint::Computed).ok_or("computed constraint without an expression".to_string()),
+            _ => Err(format!("unknown constraint: {}", kind)),
+        }
+    }
+
+    pub fn get_kind(&self) -> String {
+        match self {
+            Constraint::NotNull => "not_null",
+            Constraint::Length { .. } => "length",
+            Constraint::Range { .. } => "range",
+            Constraint::Regex(_) => "regex",
+            Constraint::Default(_) => "default",
+            Constraint::AutoIncrement => "auto_increment",
+            Constraint::Computed(_) => "computed",
+        }.to_string()
+    }
+
+    // unset bounds are stored as empty strings
+    pub fn get_arguments(&self) -> Vec<String> {
+        let bound = |bound: &Option<String>| bound.clone().unwrap_or_default();
+        match self {
+            Constraint::NotNull | Constraint::AutoIncrement => Vec::default(),
+            Constraint::Length { min, max } => vec![
+                min.map(|min| min.to_string()).unwrap_or_default(),
+                max.map(|max| max.to_string()).unwrap_or_default(),
+            ],
+            Constraint::Range { min, max } => vec![bound(min), bound(max)],
+            Constraint::Regex(pattern) => vec![pattern.get_source().to_string()],
+            Constraint::Default(value) => vec![value.clone()],
+            Constraint::Computed(expression) => vec![expression.clone()],
+        }
+    }
+
+    // checks a non null cell of a column declared with `ty(parameters)`
+    pub fn check(&self, ty: &str, parameters: &[String], value: &ValueType) -> Result<(), String> {
+        match self {
+            Constraint::NotNull | Constraint::Default(_) | Constraint::AutoIncrement | Constraint::Computed(_) => Ok(()),
+            Constraint::Length { min, max } => {
+                let length = get_text(value).unwrap_or_default().chars().count();
+                if min.is_some_and(|min| length < min) || max.is_some_and(|max| length > max) {
+                    return Err(format!("length {} is out of {}", length, self));
+                }
+                Ok(())
+            },
+            Constraint::Range { min, max } => {
+                if let Some(min) = min {
+                    let min = build_value(ty, parameters, min.clone())?.get_value();
+                    if !value.partial_cmp(&min).is_some_and(|ordering| ordering.is_ge()) {
+                        return Err(format!("value is out of {}", self));
+                    }
+                }
+                if let Some(max) = max {
+                    let max = build_value(ty, parameters, max.clone())?.get_value();
+                    if !value.partial_cmp(&max).is_some_and(|ordering| ordering.is_le()) {
+                        return Err(format!("value is out of {}", self));
+                    }
+                }
+                Ok(())
+            },
+            Constraint::Regex(pattern) => {
+                if !pattern.is_match(get_text(value).unwrap_or_default().as_str()) {
+                    return Err(format!("value doesn't match {}", self));
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+// a pattern of the regex constraint, compiled once when the declaration is parsed
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    source: String,
+    // matches the whole value, not a part of it
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, String> {
+        let regex = Regex::new(&format!("^(?:{})$", source))
+            .map_err(|err| format!("invalid regex {}: {}", source, err))?;
+        Ok(Self { source: source.to_string(), regex })
+    }
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::NotNull | Constraint::AutoIncrement => write!(f, "{}", self.get_kind()),
+            _ => write!(f, "{}({})", self.get_kind(), self.get_arguments().join(", ")),
+        }
+    }
+}
+
+// full declaration of a column: `name: DecimalValue(10, 2) range(0, ) not_null`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub ty: String,
+    pub parameters: Vec<String>,
+    pub constraints: Vec<Constraint>,
+}
+
+impl ColumnDefinition {
+    // parses the type declaration followed by whitespace separated constraints
+    pub fn parse(name: &str, declaration: &str) -> Result<Self, String> {
+        let tokens = split_declaration(declaration)?;
+        let (ty, parameters) = match tokens.split_first() {
+            Some((type_declaration, _)) => parse_type_declaration(type_declaration)?,
+            None => return Err(format!("column {} has no type", name)),
+        };
+        if !SUPPORTED_TYPES.contains_key(&ty) {
+            return Err(format!("No such supported data type: {}", ty));
+        }
+        check_type_parameters(&ty, &parameters)?;
+        let mut constraints = Vec::with_capacity(tokens.len() - 1);
+        for token in tokens.iter().skip(1) {
+            let constraint = Constraint::parse(&ty, &parameters, token)?;
+            if constraints.iter().any(|added: &Constraint| added.get_kind() == constraint.get_kind()) {
+                return Err(format!("column {} declares {} twice", name, constraint.get_kind()));
+            }
+            constraints.push(constraint);
+        }
+        let definition = Self {
+            name: name.to_string(),
+            ty,
+            parameters,
+            constraints,
+        };
+        if definition.is_auto_increment() && definition.get_default().is_some() {
+            return Err(format!("column {} can't have both a default and auto_increment", name));
+        }
+        if definition.is_computed() && (definition.is_auto_increment() || definition.get_default().is_some()) {
+            return Err(format!("computed column {} can't have a default or auto_increment", name));
+        }
+        if let Some(default) = definition.get_default() {
+            definition.build_value(default.to_string())
+                .map_err(|err| format!("invalid default of column {}: {}", name, err))?;
+        }
+        Ok(definition)
+    }
+    pub fn get_default(&self) -> Option<&str> {
+        self.constraints.iter().find_map(|constraint| match constraint {
+            Constraint::Default(value) => Some(value.as_str()),
+            _ => None,
+        })
+    }
+    pub fn is_auto_increment(&self) -> bool {
+        self.constraints.contains(&Constraint::AutoIncrement)
+    }
+    pub fn get_expression(&self) -> Option<&str> {
+        self.constraints.iter().find_map(|constraint| match constraint {
+            Constraint::Computed(expression) => Some(expression.as_str()),
+            _ => None,
+        })
+    }
+    pub fn is_computed(&self) -> bool {
+        self.get_expression().is_some()
+    }
+    pub fn is_nullable(&self) -> bool {
+        !self.constraints.contains(&Constraint::NotNull)
+    }
+    // builds a cell from the raw value and checks it against the type parameters and constraints
+    pub fn build_value(&self, raw_value: String) -> Result<Rc<dyn CellValue>, String> {
+        let value = build_value(&self.ty, &self.parameters, raw_value)?;
+        self.check_constraints(value)
+    }
+    // cell for a value of a row literal, whose tokenizer has already trimmed what had to be trimmed:
+    // a string keeps its surrounding whitespace, which the string builder would drop
+    pub fn build_literal_value(&self, raw_value: String) -> Result<Rc<dyn CellValue>, String> {
+        if self.ty != StringValue::get_type_name() {
+            return self.build_value(raw_value);
+        }
+        let value = StringValue::new(raw_value);
+        value.check_max_length(&self.parameters)?;
+        self.check_constraints(Rc::new(value))
+    }
+    fn check_constraints(&self, value: Rc<dyn CellValue>) -> Result<Rc<dyn CellValue>, String> {
+        let value_type = value.get_value();
+        for constraint in self.constraints.iter() {
+            constraint.check(&self.ty, &self.parameters, &value_type)?;
+        }
+        Ok(value)
+    }
+    // cell for a row that doesn't provide this column
+    pub fn build_missing_value(&self) -> Result<Rc<dyn CellValue>, String> {
+        match self.get_default() {
+            Some(default) => self.build_value(default.to_string()),
+            None => self.build_null_value(),
+        }
+    }
+    pub fn build_null_value(&self) -> Result<Rc<dyn CellValue>, String> {
+        if !self.is_nullable() {
+            return Err(format!("column {} can't be null", self.name));
+        }
+        Ok(Rc::new(NullValue::new()))
+    }
+}
+
+impl Display for ColumnDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ty)?;
+        if !self.parameters.is_empty() {
+            write!(f, "({})", self.parameters.join(", "))?;
+        }
+        for constraint in self.constraints.iter() {
+            write!(f, " {}", constraint)?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub struct Scheme<T>
@@ -12,6 +336,13 @@ where
     pub value_generators: Vec<Arc<fn(String) -> Result<Rc<T>, String>>>,
     // TODO: add columns name
     pub columns: Vec<String>,
+    // type parameters of every column, e.g. the allowed values of `EnumValue(red, green, blue)`
+    pub parameters: Vec<Vec<String>>,
+    pub constraints: Vec<Vec<Constraint>>,
+    // names of the columns forming the primary key, empty if the table has none
+    pub primary_key: Vec<String>,
+    // column sets whose values can't repeat across rows
+    pub unique: Vec<Vec<String>>,
 }
 impl<T> Clone for Scheme<T>
 where
@@ -22,6 +353,10 @@ where
             types: self.types.clone(),
             value_generators: self.value_generators.clone(),
             columns: self.columns.clone(),
+            parameters: self.parameters.clone(),
+            constraints: self.constraints.clone(),
+            primary_key: self.primary_key.clone(),
+            unique: self.unique.clone(),
         }
     }
 }
@@ -31,12 +366,34 @@ where
     T: CellValue + ?Sized,
 {
     pub fn new(types: Vec<String>, columns: Vec<String>, value_generators: Vec<Arc<fn(String) -> Result<Rc<T>, String>>>) -> Self {
+        let parameters = vec![Vec::default(); types.len()];
+        let constraints = vec![Vec::default(); types.len()];
         Self {
             types,
             value_generators,
             columns,
+            parameters,
+            constraints,
+            primary_key: Vec::default(),
+            unique: Vec::default(),
         }
     }
+    pub fn with_parameters(mut self, parameters: Vec<Vec<String>>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+    pub fn with_constraints(mut self, constraints: Vec<Vec<Constraint>>) -> Self {
+        self.constraints = constraints;
+        self
+    }
+    pub fn with_primary_key(mut self, primary_key: Vec<String>) -> Self {
+        self.primary_key = primary_key;
+        self
+    }
+    pub fn with_unique(mut self, unique: Vec<Vec<String>>) -> Self {
+        self.unique = unique;
+        self
+    }
     pub fn builder() -> SchemeBuilder<T> {
         SchemeBuilder::<T>::new()
     }
@@ -50,10 +407,261 @@ where
     pub fn get_types(&self) -> Vec<String> {
         self.types.clone()
     }
+    pub fn get_parameters(&self) -> Vec<Vec<String>> {
+        self.parameters.clone()
+    }
+    pub fn get_column_parameters(&self, index: usize) -> &[String] {
+        self.parameters.get(index).map(|parameters| parameters.as_slice()).unwrap_or_default()
+    }
+    pub fn get_constraints(&self) -> Vec<Vec<Constraint>> {
+        self.constraints.clone()
+    }
+    pub fn get_column_constraints(&self, index: usize) -> &[Constraint] {
+        self.constraints.get(index).map(|constraints| constraints.as_slice()).unwrap_or_default()
+    }
+    pub fn is_auto_increment_column(&self, index: usize) -> bool {
+        self.get_column_constraints(index).contains(&Constraint::AutoIncrement)
+    }
+    pub fn is_computed_column(&self, index: usize) -> bool {
+        self.get_column_constraints(index).iter().any(|constraint| matches!(constraint, Constraint::Computed(_)))
+    }
+    // the expression of the column `index` if it's computed; one that doesn't parse is an error rather
+    // than a stored column, which would misplace the stored cells
+    pub fn get_computed_expression(&self, index: usize) -> Result<Option<Expression>, String> {
+        self.get_column_constraints(index).iter()
+            .find_map(|constraint| match constraint {
+                Constraint::Computed(expression) => Some(expression),
+                _ => None,
+            })
+            .map(|expression| Expression::parse(expression).map_err(|err| format!("column {}: {}", self.columns[index], err)))
+            .transpose()
+    }
+    // names of the computed columns whose expressions read `column`
+    pub fn get_dependent_columns(&self, column: &str) -> Result<Vec<String>, String> {
+        let mut dependent_columns = Vec::default();
+        for index in 0..self.columns.len() {
+            if let Some(expression) = self.get_computed_expression(index)? {
+                if expression.get_columns().iter().any(|name| name == column) {
+                    dependent_columns.push(self.columns[index].clone());
+                }
+            }
+        }
+        Ok(dependent_columns)
+    }
+    // indexes of the computed columns and their expressions, every one after the computed columns
+    // it reads; expressions may only read the columns of the table and can't go round in a circle
+    pub fn get_computation_order(&self) -> Result<Vec<(usize, Expression)>, String> {
+        let mut pending = Vec::default();
+        for index in 0..self.columns.len() {
+            if let Some(expression) = self.get_computed_expression(index)? {
+                if let Some(column) = expression.get_columns().iter().find(|column| !self.columns.contains(column)) {
+                    return Err(format!("column {} is computed from unknown column {}", self.columns[index], column));
+                }
+                pending.push((index, expression));
+            }
+        }
+        let mut order: Vec<(usize, Expression)> = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|(_, expression)| {
+                expression.get_columns().iter().all(|column| {
+                    !pending.iter().any(|(index, _)| self.columns[*index] == *column)
+                })
+            });
+            match ready {
+                Some(position) => order.push(pending.remove(position)),
+                None => {
+                    let columns = pending.iter().map(|(index, _)| self.columns[*index].clone()).collect::<Vec<String>>();
+                    return Err(format!("computed columns {} depend on each other", columns.join(", ")));
+                },
+            }
+        }
+        Ok(order)
+    }
+    pub fn check_computed(&self) -> Result<(), String> {
+        self.get_computation_order().map(|_| ())
+    }
+    pub fn get_column_definition(&self, index: usize) -> Result<ColumnDefinition, String> {
+        match (self.columns.get(index), self.types.get(index)) {
+            (Some(name), Some(ty)) => Ok(ColumnDefinition {
+                name: name.clone(),
+                ty: ty.clone(),
+                parameters: self.get_column_parameters(index).to_vec(),
+                constraints: self.get_column_constraints(index).to_vec(),
+            }),
+            _ => Err(format!("there is no column with index {}", index)),
+        }
+    }
+    pub fn get_primary_key(&self) -> &[String] {
+        self.primary_key.as_slice()
+    }
+    pub fn get_unique(&self) -> &[Vec<String>] {
+        self.unique.as_slice()
+    }
+    pub fn get_column_index(&self, column: &str) -> Option<usize> {
+        self.columns.iter().position(|name| name == column)
+    }
+    // true if no two rows can share a value of the column, NULL included
+    pub fn is_unique_column(&self, column: &str) -> bool {
+        let not_null = self.get_column_index(column)
+            .is_some_and(|index| self.get_column_constraints(index).contains(&Constraint::NotNull));
+        self.primary_key == [column] || (not_null && self.unique.iter().any(|columns| columns == &[column]))
+    }
+    // short marker of the keys a column takes part in, e.g. `PK` or `U`
+    pub fn get_key_marker(&self, column: &str) -> Option<String> {
+        let column = column.to_string();
+        let mut markers = Vec::default();
+        if self.primary_key.contains(&column) {
+            markers.push("PK");
+        }
+        if self.unique.iter().any(|columns| columns.contains(&column)) {
+            markers.push("U");
+        }
+        if markers.is_empty() {
+            return None;
+        }
+        Some(markers.join(","))
+    }
+    // every key has to name existing columns, each of them once
+    pub fn check_keys(&self) -> Result<(), String> {
+        let keys = std::iter::once(&self.primary_key)
+            .filter(|primary_key| !primary_key.is_empty())
+            .chain(self.unique.iter());
+        for key in keys {
+            if key.is_empty() {
+                return Err("unique constraint needs at least one column".to_string());
+            }
+            for (index, column) in key.iter().enumerate() {
+                if !self.columns.contains(column) {
+                    return Err(format!("key refers to unknown column {}", column));
+                }
+                if key[..index].contains(column) {
+                    return Err(format!("column {} is listed twice in ({})", column, key.join(", ")));
+                }
+            }
+        }
+        Ok(())
+    }
+    // keys and computed columns follow their columns when those are renamed
     pub fn set_columns(&mut self, columns: Vec<String>) {
+        let new_name = |column: &str| {
+            self.columns.iter().position(|name| name == column).and_then(|index| columns.get(index)).cloned()
+        };
+        let rename = |column: &mut String| {
+            if let Some(new_name) = new_name(column) {
+                *column = new_name;
+            }
+        };
+        let mut primary_key = self.primary_key.clone();
+        primary_key.iter_mut().for_each(rename);
+        let mut unique = self.unique.clone();
+        unique.iter_mut().flatten().for_each(rename);
+        let mut constraints = self.constraints.clone();
+        for constraint in constraints.iter_mut().flatten() {
+            if let Constraint::Computed(source) = constraint {
+                if let Ok(mut expression) = Expression::parse(source) {
+                    expression.rename_columns(&new_name);
+                    *source = expression.to_string();
+                }
+            }
+        }
+        self.primary_key = primary_key;
+        self.unique = unique;
+        self.constraints = constraints;
         self.columns = columns;
     }
+    // puts the column at `index`, the columns from `index` on move one place to the right
+    pub fn insert_column(&mut self, index: usize, definition: ColumnDefinition, validator: Arc<fn(String) -> Result<Rc<T>, String>>) {
+        self.value_generators.insert(index, validator);
+        self.columns.insert(index, definition.name);
+        self.types.insert(index, definition.ty);
+        self.parameters.insert(index, definition.parameters);
+        self.constraints.insert(index, definition.constraints);
+    }
+    // the keys are expected not to mention the removed column
+    pub fn remove_column(&mut self, index: usize) {
+        self.value_generators.remove(index);
+        self.columns.remove(index);
+        self.types.remove(index);
+        self.parameters.remove(index);
+        self.constraints.remove(index);
+    }
+    // `order[i]` is the current index of the column that becomes the i-th one
+    pub fn reorder_columns(&mut self, order: &[usize]) {
+        self.value_generators = order.iter().map(|&index| self.value_generators[index].clone()).collect();
+        self.columns = order.iter().map(|&index| self.columns[index].clone()).collect();
+        self.types = order.iter().map(|&index| self.types[index].clone()).collect();
+        self.parameters = order.iter().map(|&index| self.parameters[index].clone()).collect();
+        self.constraints = order.iter().map(|&index| self.constraints[index].clone()).collect();
+    }
 }
+
+impl Scheme<dyn CellValue> {
+    // builds a cell of the column `index` and checks it against the column's type parameters
+    // and constraints
+    pub fn generate_value(&self, index: usize, raw_value: String) -> Result<Rc<dyn CellValue>, String> {
+        let definition = self.get_column_definition(index)?;
+        definition.build_value(raw_value)
+            .map_err(|err| format!("column {}: {}", definition.name, err))
+    }
+    // the same as `generate_value` for a value taken from a row literal, see `build_literal_value`
+    pub fn generate_literal_value(&self, index: usize, raw_value: String) -> Result<Rc<dyn CellValue>, String> {
+        let definition = self.get_column_definition(index)?;
+        definition.build_literal_value(raw_value)
+            .map_err(|err| format!("column {}: {}", definition.name, err))
+    }
+    // cell for an explicit NULL in the column `index`
+    pub fn generate_null_value(&self, index: usize) -> Result<Rc<dyn CellValue>, String> {
+        self.get_column_definition(index)?.build_null_value()
+    }
+    // cell for the column `index` when a row doesn't provide it: the default value if there is one,
+    // NULL otherwise
+    pub fn generate_missing_value(&self, index: usize) -> Result<Rc<dyn CellValue>, String> {
+        let definition = self.get_column_definition(index)?;
+        definition.build_missing_value()
+            .map_err(|err| format!("column {}: {}", definition.name, err))
+    }
+    // replaces the cells of the computed columns of a row with the values of their expressions
+    pub fn compute_values(&self, values: &mut [Rc<dyn CellValue>]) -> Result<(), String> {
+        for (index, expression) in self.get_computation_order()? {
+            let row = |column: &str| {
+                self.get_column_index(column)
+                    .and_then(|index| values.get(index))
+                    .map(|value| Datum::from_value(&value.get_value()))
+            };
+            let value = expression.evaluate(&row)
+                .map_err(|err| format!("column {}: {}", self.columns[index], err))?;
+            values[index] = match value.to_raw_value() {
+                Some(raw_value) => self.generate_value(index, raw_value)?,
+                None => self.generate_null_value(index)
+                    .map_err(|err| format!("column {}: {}", self.columns[index], err))?,
+            };
+        }
+        Ok(())
+    }
+    // the cells of a row that are kept on disk, computed ones are left out
+    pub fn get_stored_values(&self, values: &[Rc<dyn CellValue>]) -> Vec<Rc<dyn CellValue>> {
+        values.iter()
+            .enumerate()
+            .filter(|(index, _)| !self.is_computed_column(*index))
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+    // the full row out of the cells `get_stored_values` has left; computed cells which can't be
+    // computed any more stay NULL rather than lose the row
+    pub fn complete_values(&self, stored_values: Vec<Rc<dyn CellValue>>) -> Vec<Rc<dyn CellValue>> {
+        let mut stored_values = stored_values.into_iter();
+        let mut values = (0..self.columns.len())
+            .map(|index| match self.is_computed_column(index) {
+                true => None,
+                false => stored_values.next(),
+            })
+            .map(|value| value.unwrap_or_else(|| Rc::new(NullValue::new())))
+            .collect::<Vec<Rc<dyn CellValue>>>();
+        let _ = self.compute_values(&mut values);
+        values
+    }
+}
+
 #[derive(Default)]
 pub struct SchemeBuilder<T>
 where
@@ -62,6 +670,8 @@ where
     types: Vec<String>,
     value_validators: Vec<Arc<fn(String) -> Result<Rc<T>, String>>>,
     columns: Vec<String>,
+    parameters: Vec<Vec<String>>,
+    constraints: Vec<Vec<Constraint>>,
 }
 
 impl<T> SchemeBuilder<T>
@@ -73,22 +683,302 @@ where
             value_validators: Vec::default(),
             columns: Vec::default(),
             types: Vec::default(),
+            parameters: Vec::default(),
+            constraints: Vec::default(),
         }
     }
 
-    pub fn with_column(mut self, ty: String, column: String, validator: Arc<fn(String) -> Result<Rc<T>, String>>) -> Self {
+    pub fn with_column(self, ty: String, column: String, validator: Arc<fn(String) -> Result<Rc<T>, String>>) -> Self {
+        self.with_parameterized_column(ty, column, Vec::default(), validator)
+    }
+
+    pub fn with_parameterized_column(self, ty: String, column: String, parameters: Vec<String>, validator: Arc<fn(String) -> Result<Rc<T>, String>>) -> Self {
+        self.with_column_definition(
+            ColumnDefinition {
+                name: column,
+                ty,
+                parameters,
+                constraints: Vec::default(),
+            },
+            validator,
+        )
+    }
+
+    pub fn with_column_definition(mut self, definition: ColumnDefinition, validator: Arc<fn(String) -> Result<Rc<T>, String>>) -> Self {
         self.value_validators.push(validator);
-        self.columns.push(column);
-        self.types.push(ty);
+        self.columns.push(definition.name);
+        self.types.push(definition.ty);
+        self.parameters.push(definition.parameters);
+        self.constraints.push(definition.constraints);
         self
     }
 
     pub fn build(self) -> Scheme<T> {
         Scheme::<T>::new(self.types, self.columns, self.value_validators)
+            .with_parameters(self.parameters)
+            .with_constraints(self.constraints)
     }
 }
 
+// builds a cell of `ty(parameters)` without looking at constraints
+fn build_value(ty: &str, parameters: &[String], raw_value: String) -> Result<Rc<dyn CellValue>, String> {
+    if ty == DecimalValue::get_type_name() && !parameters.is_empty() {
+        let value = DecimalValue::builder_from_declaration(parameters)?
+            .with_raw_value(raw_value)
+            .build()?;
+        return Ok(Rc::new(value));
+    }
+    let generator = match SUPPORTED_TYPES.get(ty) {
+        Some(generator) => generator,
+        None => return Err(format!("No such supported data type: {}", ty)),
+    };
+    let value = generator(raw_value)?;
+    match value.get_value() {
+        ValueType::Enum(enum_value) => enum_value.check_domain(parameters)?,
+        ValueType::Str(string_value) => string_value.check_max_length(parameters)?,
+        _ => (),
+    }
+    Ok(value)
+}
+
+fn is_textual(ty: &str) -> bool {
+    ["StringValue", "CharValue", "EmailValue", "EnumValue"].contains(&ty)
+}
+
+fn get_text(value: &ValueType) -> Option<String> {
+    match value {
+        ValueType::Str(value) => Some(value.get_value().to_string()),
+        ValueType::Char(value) => Some(value.get_value().to_string()),
+        ValueType::Email(value) => Some(value.get_value().to_string()),
+        ValueType::Enum(value) => Some(value.get_value().to_string()),
+        _ => None,
+    }
+}
+
+// bounds may contain commas themselves (dates do), so every comma is tried as the separator;
+// an empty side leaves the range open
+fn parse_range(ty: &str, parameters: &[String], arguments: &str) -> Result<Constraint, String> {
+    let parse_bound = |bound: &str| -> Result<Option<ValueType>, String> {
+        let bound = bound.trim();
+        if bound.is_empty() {
+            return Ok(None);
+        }
+        build_value(ty, parameters, bound.to_string()).map(|value| Some(value.get_value()))
+    };
+    let mut last_error = format!("range expects (min, max), got ({})", arguments);
+    for (separator, _) in arguments.match_indices(',') {
+        let (min, max) = (&arguments[..separator], &arguments[separator + 1..]);
+        match (parse_bound(min), parse_bound(max)) {
+            (Ok(None), Ok(None)) => return Err("range needs at least one bound".to_string()),
+            (Ok(min_value), Ok(max_value)) => {
+                let comparable = match (&min_value, &max_value) {
+                    (Some(min_value), Some(max_value)) => min_value.partial_cmp(max_value).map(|ordering| ordering.is_le()),
+                    (Some(bound), None) | (None, Some(bound)) => bound.partial_cmp(bound).map(|_| true),
+                    (None, None) => unreachable!(),
+                };
+                return match comparable {
+                    Some(true) => Ok(Constraint::Range {
+                        min: min_value.map(|_| min.trim().to_string()),
+                        max: max_value.map(|_| max.trim().to_string()),
+                    }),
+                    Some(false) => Err(format!("range bounds are reversed: ({})", arguments)),
+                    None => Err(format!("{} values can't be compared", ty)),
+                };
+            },
+            (Err(err), _) | (_, Err(err)) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+// splits `DecimalValue(10, 2) range(0, 100) not_null` on whitespace outside of brackets
+fn split_declaration(declaration: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::default();
+    let mut token = String::new();
+    let mut depth = 0usize;
+    // brackets inside the quoted strings of a computed expression don't count
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in declaration.trim().chars() {
+        let is_expression = token.to_lowercase().starts_with("computed(");
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(opening), c) if c == opening => quote = None,
+            (Some(_), _) => (),
+            (None, '\'' | '"') if is_expression => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.checked_sub(1)
+                .ok_or_else(|| format!("unbalanced brackets in the column declaration: {}", declaration))?,
+            _ => (),
+        }
+        if c.is_whitespace() && depth == 0 {
+            if !token.is_empty() {
+                tokens.push(std::mem::take(&mut token));
+            }
+            continue;
+        }
+        token.push(c);
+    }
+    if depth != 0 {
+        return Err(format!("unbalanced brackets in the column declaration: {}", declaration));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
 #[cfg(test)]
 mod tests {
-    // TODO: implement tests
+    use crate::scheme::{ColumnDefinition, Constraint, Pattern, Scheme};
+    use crate::types::{CellValue, ValueType, SUPPORTED_TYPES};
+
+    #[test]
+    fn test_generate_value_checks_enum_domain() {
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column("IntValue".to_string(), "id".to_string(), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .with_parameterized_column(
+                "EnumValue".to_string(),
+                "color".to_string(),
+                vec!["red".to_string(), "green".to_string()],
+                SUPPORTED_TYPES.get("EnumValue").unwrap().clone(),
+            )
+            .build();
+        assert!(scheme.generate_value(0, "1".to_string()).is_ok());
+        assert!(scheme.generate_value(1, "green".to_string()).is_ok());
+        assert!(scheme.generate_value(1, "blue".to_string()).is_err());
+        assert!(scheme.generate_value(2, "red".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_column_definition() {
+        let definition = ColumnDefinition::parse("age", "Int range(0, 120) not_null default(18)").unwrap();
+        assert_eq!(definition.ty, "IntValue");
+        assert_eq!(definition.constraints, vec![
+            Constraint::Range { min: Some("0".to_string()), max: Some("120".to_string()) },
+            Constraint::NotNull,
+            Constraint::Default("18".to_string()),
+        ]);
+        assert_eq!(definition.to_string(), "IntValue range(0, 120) not_null default(18)");
+
+        let definition = ColumnDefinition::parse("price", "DecimalValue(6, 2, usd)  range(0, )").unwrap();
+        assert_eq!(definition.parameters, vec!["6", "2", "usd"]);
+        assert_eq!(definition.constraints, vec![Constraint::Range { min: Some("0".to_string()), max: None }]);
+
+        assert!(ColumnDefinition::parse("age", "IntValue range(120, 0)").is_err());
+        assert!(ColumnDefinition::parse("age", "IntValue regex([0-9]+)").is_err());
+        assert!(ColumnDefinition::parse("age", "IntValue range(0, 120) default(121)").is_err());
+        assert!(ColumnDefinition::parse("age", "IntValue not_null not_null").is_err());
+        assert!(ColumnDefinition::parse("name", "StringValue regex([a-z)").is_err());
+        assert!(ColumnDefinition::parse("name", "StringValue(0)").is_err());
+        assert!(ColumnDefinition::parse("pic", "PictureValue range(1, 2)").is_err());
+        assert!(ColumnDefinition::parse("id", "IntValue auto_increment").unwrap().is_auto_increment());
+        assert!(ColumnDefinition::parse("id", "IntValue auto_increment default(1)").is_err());
+        assert!(ColumnDefinition::parse("id", "StringValue auto_increment").is_err());
+    }
+
+    #[test]
+    fn test_generate_value_checks_constraints() {
+        let definitions = [
+            ColumnDefinition::parse("age", "IntValue range(0, 120) not_null default(18)").unwrap(),
+            ColumnDefinition::parse("name", "StringValue(8) length(2, ) regex([A-Z][a-z]+)").unwrap(),
+            ColumnDefinition::parse("price", "DecimalValue(6, 2) range(0, 9999.99)").unwrap(),
+        ];
+        let scheme = definitions.into_iter()
+            .fold(Scheme::<dyn CellValue>::builder(), |builder, definition| {
+                let generator = SUPPORTED_TYPES.get(&definition.ty).unwrap().clone();
+                builder.with_column_definition(definition, generator)
+            })
+            .build();
+
+        assert!(scheme.generate_value(0, "120".to_string()).is_ok());
+        assert!(scheme.generate_value(0, "121".to_string()).is_err());
+        assert!(scheme.generate_value(0, "-1".to_string()).is_err());
+        assert!(scheme.generate_value(1, "Illia".to_string()).is_ok());
+        assert!(scheme.generate_value(1, "illia".to_string()).is_err());
+        assert!(scheme.generate_value(1, "I".to_string()).is_err());
+        assert!(scheme.generate_value(1, "Constantinople".to_string()).is_err());
+        assert!(scheme.generate_value(2, "12.5".to_string()).is_ok());
+        assert!(scheme.generate_value(2, "12.505".to_string()).is_err());
+        assert!(scheme.generate_value(2, "-1".to_string()).is_err());
+
+        match scheme.generate_missing_value(0).unwrap().get_value() {
+            ValueType::Int(value) => assert_eq!(value.get_value(), 18),
+            _ => unreachable!(),
+        };
+        assert!(scheme.generate_null_value(0).is_err());
+        match scheme.generate_missing_value(1).unwrap().get_value() {
+            ValueType::Null(_) => (),
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn test_constraint_parts() {
+        let constraints = [
+            Constraint::NotNull,
+            Constraint::Length { min: None, max: Some(64) },
+            Constraint::Range { min: Some("0".to_string()), max: None },
+            Constraint::Regex(Pattern::new("[a-z, ]+").unwrap()),
+            Constraint::Default("18".to_string()),
+            Constraint::AutoIncrement,
+            Constraint::Computed("price * qty".to_string()),
+        ];
+        for constraint in constraints {
+            let restored = Constraint::from_parts(&constraint.get_kind(), &constraint.get_arguments());
+            assert_eq!(restored, Ok(constraint));
+        }
+    }
+
+    #[test]
+    fn test_computed_columns() {
+        let definition = ColumnDefinition::parse("label", "StringValue computed(concat( name,' (',qty , ')' )) not_null").unwrap();
+        assert_eq!(definition.get_expression(), Some("concat(name, ' (', qty, ')')"));
+        assert!(ColumnDefinition::parse("total", "IntValue computed(price *)").is_err());
+        assert!(ColumnDefinition::parse("total", "IntValue computed(qty) auto_increment").is_err());
+        assert!(ColumnDefinition::parse("total", "IntValue computed(qty) default(1)").is_err());
+
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let generator = |ty: &str| SUPPORTED_TYPES.get(ty).unwrap().clone();
+        let mut scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("label", "StringValue computed(concat(name, ': ', total))"), generator("StringValue"))
+            .with_column_definition(column("name", "StringValue"), generator("StringValue"))
+            .with_column_definition(column("price", "RealValue"), generator("RealValue"))
+            .with_column_definition(column("qty", "IntValue"), generator("IntValue"))
+            .with_column_definition(column("total", "RealValue computed(price * qty)"), generator("RealValue"))
+            .build();
+        let order = scheme.get_computation_order().unwrap().into_iter().map(|(index, _)| index).collect::<Vec<usize>>();
+        assert_eq!(order, [4, 0]);
+        assert_eq!(scheme.get_dependent_columns("total"), Ok(vec!["label".to_string()]));
+
+        let stored_values = vec![
+            scheme.generate_value(1, "pen".to_string()).unwrap(),
+            scheme.generate_value(2, "2.5".to_string()).unwrap(),
+            scheme.generate_value(3, "4".to_string()).unwrap(),
+        ];
+        let values = scheme.complete_values(stored_values);
+        assert_eq!(scheme.get_stored_values(&values).len(), 3);
+        match (values[0].get_value(), values[4].get_value()) {
+            (ValueType::Str(label), ValueType::Real(total)) => {
+                assert_eq!(label.get_value(), "pen: 10");
+                assert_eq!(total.get_value(), 10.0);
+            },
+            _ => unreachable!(),
+        }
+
+        scheme.set_columns(["label", "title", "price", "qty", "total"].map(|column| column.to_string()).to_vec());
+        assert_eq!(scheme.get_column_definition(0).unwrap().get_expression(), Some("concat(title, ': ', total)"));
+        scheme.constraints[4] = vec![Constraint::Computed("label".to_string())];
+        assert!(scheme.check_computed().is_err());
+        scheme.constraints[4] = vec![Constraint::Computed("weight".to_string())];
+        assert!(scheme.check_computed().is_err());
+        // an expression that doesn't parse still marks the column computed
+        scheme.constraints[4] = vec![Constraint::Computed("price *".to_string())];
+        assert!(scheme.is_computed_column(4));
+        assert!(scheme.get_computed_expression(4).is_err());
+
+        let definition = ColumnDefinition::parse("fee", "RealValue computed(price * 0.00001)").unwrap();
+        assert_eq!(definition.get_expression(), Some("price * 0.00001"));
+    }
 }
\ No newline at end of file
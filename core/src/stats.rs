@@ -0,0 +1,313 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use image::GenericImageView;
+use crate::index::IndexKey;
+use crate::table::Table;
+use crate::types::ValueType;
+use crate::types::null_value::NullValue;
+
+// how many of the most frequent values a column profile keeps
+const MOST_FREQUENT_COUNT: usize = 5;
+const CSV_HEADER: [&str; 11] = [
+    "column", "type", "nulls", "distinct", "min", "max", "mean", "stddev", "most_frequent", "lengths", "picture_sizes",
+];
+
+// how sizes are spread: buckets doubling in width, 0, 1, 2-3, 4-7 and so on
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Histogram {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    // (smallest size, largest size, count) of the buckets that aren't empty, smallest first
+    pub buckets: Vec<(usize, usize, usize)>,
+}
+
+impl Histogram {
+    // `None` if there are no sizes
+    pub fn collect(sizes: &[usize]) -> Option<Self> {
+        let min = *sizes.iter().min()?;
+        let max = *sizes.iter().max()?;
+        let mut counts = vec![0; get_bucket(max) + 1];
+        for &size in sizes {
+            counts[get_bucket(size)] += 1;
+        }
+        let buckets = counts.into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(bucket, count)| match bucket {
+                0 => (0, 0, count),
+                bucket => (1 << (bucket - 1), (1 << bucket) - 1, count),
+            })
+            .collect();
+        Some(Self {
+            min,
+            max,
+            mean: sizes.iter().sum::<usize>() as f64 / sizes.len() as f64,
+            buckets,
+        })
+    }
+}
+
+fn get_bucket(size: usize) -> usize {
+    (usize::BITS - size.leading_zeros()) as usize
+}
+
+// `min 1, max 12, mean 5.5; 1: 1, 8-15: 1`
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let buckets = self.buckets.iter()
+            .map(|(from, to, count)| match from == to {
+                true => format!("{}: {}", from, count),
+                false => format!("{}-{}: {}", from, to, count),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "min {}, max {}, mean {:.1}; {}", self.min, self.max, self.mean, buckets.join(", "))
+    }
+}
+
+// what a scan of a column tells about it; the numbers leave out NULL
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub ty: String,
+    pub nulls: usize,
+    pub distinct: usize,
+    // as the cells are written, for the types whose cells compare
+    pub min: Option<String>,
+    pub max: Option<String>,
+    // for the numeric types
+    pub mean: Option<f64>,
+    pub stddev: Option<f64>,
+    // the values met most often with how many times, the most frequent first
+    pub most_frequent: Vec<(String, usize)>,
+    // lengths of the strings in characters
+    pub lengths: Option<Histogram>,
+    // sizes of the pictures in pixels
+    pub picture_sizes: Option<Histogram>,
+}
+
+impl ColumnProfile {
+    fn collect(name: &str, ty: &str, cells: Vec<ValueType>) -> Self {
+        let mut profile = Self {
+            name: name.to_string(),
+            ty: ty.to_string(),
+            nulls: cells.iter().filter(|cell| matches!(cell, ValueType::Null(_))).count(),
+            ..Self::default()
+        };
+        let cells = cells.into_iter()
+            .filter(|cell| !matches!(cell, ValueType::Null(_)))
+            .collect::<Vec<ValueType>>();
+
+        let mut counts = HashMap::<IndexKey, (usize, usize)>::default();
+        let mut pictures = HashSet::<u64>::default();
+        for (position, cell) in cells.iter().enumerate() {
+            match cell {
+                ValueType::Pic(picture) => {
+                    let mut hasher = DefaultHasher::new();
+                    picture.get_value().dimensions().hash(&mut hasher);
+                    picture.get_value().as_bytes().hash(&mut hasher);
+                    pictures.insert(hasher.finish());
+                },
+                cell => counts.entry(IndexKey::from_value(cell)).or_insert((0, position)).0 += 1,
+            }
+        }
+        profile.distinct = counts.len() + pictures.len();
+        let mut frequent = counts.into_values().collect::<Vec<(usize, usize)>>();
+        frequent.sort_by(|(lhs_count, lhs_position), (rhs_count, rhs_position)| rhs_count.cmp(lhs_count).then(lhs_position.cmp(rhs_position)));
+        profile.most_frequent = frequent.into_iter()
+            .take(MOST_FREQUENT_COUNT)
+            .filter_map(|(count, position)| cells[position].to_raw_value().map(|value| (value, count)))
+            .collect();
+
+        let comparable = cells.iter().filter(|cell| cell.partial_cmp(cell).is_some());
+        let min = comparable.clone().reduce(|min, cell| if cell < min { cell } else { min });
+        let max = comparable.reduce(|max, cell| if cell > max { cell } else { max });
+        profile.min = min.and_then(|cell| cell.to_raw_value());
+        profile.max = max.and_then(|cell| cell.to_raw_value());
+
+        let numbers = cells.iter()
+            .filter_map(|cell| match cell {
+                ValueType::Int(value) => Some(value.get_value() as f64),
+                ValueType::Real(value) => Some(value.get_value()),
+                ValueType::Decimal(value) => value.to_string().parse::<f64>().ok(),
+                _ => None,
+            })
+            .collect::<Vec<f64>>();
+        if !numbers.is_empty() {
+            let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
+            let variance = numbers.iter().map(|number| (number - mean).powi(2)).sum::<f64>() / numbers.len() as f64;
+            profile.mean = Some(mean);
+            profile.stddev = Some(variance.sqrt());
+        }
+
+        let lengths = cells.iter()
+            .filter_map(|cell| match cell {
+                ValueType::Str(value) => Some(value.get_value().chars().count()),
+                ValueType::Email(value) => Some(value.get_value().to_string().chars().count()),
+                _ => None,
+            })
+            .collect::<Vec<usize>>();
+        profile.lengths = Histogram::collect(&lengths);
+        let picture_sizes = cells.iter()
+            .filter_map(|cell| match cell {
+                ValueType::Pic(picture) => {
+                    let (width, height) = picture.get_value().dimensions();
+                    Some(width as usize * height as usize)
+                },
+                _ => None,
+            })
+            .collect::<Vec<usize>>();
+        profile.picture_sizes = Histogram::collect(&picture_sizes);
+        profile
+    }
+    // the cells of the row of the CSV export, in the order of `CSV_HEADER`
+    fn get_csv_cells(&self) -> Vec<String> {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        let number = |value: Option<f64>| value.map(|value| format!("{:.3}", value)).unwrap_or_default();
+        let histogram = |value: &Option<Histogram>| value.as_ref().map(|value| value.to_string()).unwrap_or_default();
+        vec![
+            self.name.clone(),
+            self.ty.clone(),
+            self.nulls.to_string(),
+            self.distinct.to_string(),
+            optional(&self.min),
+            optional(&self.max),
+            number(self.mean),
+            number(self.stddev),
+            self.get_most_frequent(),
+            histogram(&self.lengths),
+            histogram(&self.picture_sizes),
+        ]
+    }
+    // `Taras (3); Ivan (2)`
+    pub fn get_most_frequent(&self) -> String {
+        self.most_frequent.iter()
+            .map(|(value, count)| format!("{} ({})", value, count))
+            .collect::<Vec<String>>()
+            .join("; ")
+    }
+}
+
+// the profile of every column of a table, as the `stats` command shows it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableProfile {
+    pub table: String,
+    pub rows: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+impl TableProfile {
+    pub fn collect(table: &Table) -> Self {
+        let scheme = table.get_scheme();
+        let rows = table.get_rows();
+        let columns = scheme.get_columns()
+            .iter()
+            .zip(scheme.get_types())
+            .enumerate()
+            .map(|(index, (column, ty))| {
+                let cells = rows.iter()
+                    .map(|row| match row.get_values().get(index) {
+                        Some(value) => value.get_value(),
+                        None => ValueType::Null(NullValue::new()),
+                    })
+                    .collect::<Vec<ValueType>>();
+                ColumnProfile::collect(column, &ty, cells)
+            })
+            .collect();
+        Self {
+            table: table.get_name().to_string(),
+            rows: rows.len(),
+            columns,
+        }
+    }
+    pub fn get_column(&self, column: &str) -> Option<&ColumnProfile> {
+        self.columns.iter().find(|profile| profile.name == column)
+    }
+    // a line per column under a header, the fields quoted where they hold a comma, a quote or a line break
+    pub fn to_csv(&self) -> String {
+        let line = |cells: Vec<String>| cells.iter()
+            .map(|cell| match cell.contains([',', '"', '\n']) {
+                true => format!("\"{}\"", cell.replace('"', "\"\"")),
+                false => cell.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        std::iter::once(line(CSV_HEADER.iter().map(|cell| cell.to_string()).collect()))
+            .chain(self.columns.iter().map(|column| line(column.get_csv_cells())))
+            .map(|line| line + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::stats::Histogram;
+    use crate::table::Table;
+    use crate::test_resources;
+    use crate::types::{CellValue, SUPPORTED_TYPES};
+
+    #[test]
+    fn test_profile_columns() {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("age", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .with_column_definition(column("photo", "PictureValue"), SUPPORTED_TYPES.get("PictureValue").unwrap().clone())
+            .build();
+        let table = Table::new("people".to_string(), scheme);
+        let (happy, sad) = (test_resources!("happy_cat.jpg"), test_resources!("sad_cat.png"));
+        for raw_values in [["Taras", "21", happy], ["Ivan", "40", sad], ["Taras", "NULL", "NULL"], ["Lesya", "17", happy]] {
+            let scheme = table.get_scheme();
+            let values = raw_values.iter()
+                .enumerate()
+                .map(|(index, raw_value)| match *raw_value {
+                    "NULL" => scheme.generate_null_value(index).unwrap(),
+                    raw_value => scheme.generate_value(index, raw_value.to_string()).unwrap(),
+                })
+                .collect();
+            table.add_row(Row::new(values));
+        }
+        let profile = table.update_profile();
+        assert_eq!(table.get_profile().as_ref(), Some(&profile));
+        assert_eq!(profile.rows, 4);
+
+        let name = profile.get_column("name").unwrap();
+        assert_eq!((name.nulls, name.distinct), (0, 3));
+        assert_eq!((name.min.as_deref(), name.max.as_deref()), (Some("Ivan"), Some("Taras")));
+        assert_eq!(name.most_frequent[0], ("Taras".to_string(), 2));
+        assert_eq!(name.lengths.as_ref().unwrap().buckets, [(4, 7, 4)]);
+        assert_eq!(name.mean, None);
+
+        let age = profile.get_column("age").unwrap();
+        assert_eq!((age.nulls, age.distinct), (1, 3));
+        assert_eq!(age.mean, Some(26.0));
+        assert!((age.stddev.unwrap() - 10.033).abs() < 0.001);
+        assert_eq!((age.min.as_deref(), age.max.as_deref()), (Some("17"), Some("40")));
+
+        let photo = profile.get_column("photo").unwrap();
+        assert_eq!((photo.nulls, photo.distinct, photo.min.clone()), (1, 2, None));
+        assert!(photo.most_frequent.is_empty());
+        assert_eq!(photo.picture_sizes.as_ref().unwrap().buckets.iter().map(|(_, _, count)| count).sum::<usize>(), 3);
+
+        let csv = profile.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("column,type,nulls,distinct,min,max,mean,stddev,most_frequent,lengths,picture_sizes\n"));
+        assert!(csv.contains("\nage,IntValue,1,3,17,40,26.000,10.033,21 (1); 40 (1); 17 (1),,\n"));
+
+        // a change of the rows or the columns drops the profile
+        table.erase(3).unwrap();
+        assert_eq!(*table.get_profile(), None);
+        let mut table = table;
+        table.update_profile();
+        table.set_columns(vec!["name".to_string(), "years".to_string(), "photo".to_string()]);
+        assert_eq!(*table.get_profile(), None);
+
+        let histogram = Histogram::collect(&[0, 1, 3, 12]).unwrap();
+        assert_eq!(histogram.to_string(), "min 0, max 12, mean 4.0; 0: 1, 1: 1, 2-3: 1, 8-15: 1");
+        assert!(Histogram::collect(&[]).is_none());
+    }
+}
\ No newline at end of file
@@ -1,8 +1,26 @@
-use std::cell::{Ref, RefCell};
+use std::cell::{Cell, Ref, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Bound;
 use std::rc::Rc;
+use std::sync::Arc;
+use crate::conversion::convert_value;
+use crate::index::{Index, IndexKey, IndexKind};
 use crate::row::Row;
-use crate::scheme::Scheme;
-use crate::types::{CellValue};
+use crate::scheme::{ColumnDefinition, Scheme};
+use crate::stats::TableProfile;
+use crate::types::{CellValue, ValueType};
+use crate::types::null_value::NullValue;
+
+// how many of the last changes of its rows a table keeps, see `Table::get_changes_since`
+const KEPT_CHANGES: usize = 1024;
+
+// a change of a row of a table, which the materialized views over the table replay to follow it
+#[derive(Clone, Debug)]
+pub enum RowChange {
+    Inserted(usize, Rc<Row<dyn CellValue>>),
+    Replaced(usize, Rc<Row<dyn CellValue>>),
+    Removed(usize),
+}
 
 #[derive(Clone, Debug)]
 pub struct Table {
@@ -10,40 +28,344 @@ pub struct Table {
     #[allow(dead_code)]
     pub scheme: Scheme<dyn CellValue>,
     pub rows: RefCell<Vec<Rc<Row<dyn CellValue>>>>,
+    // the last value every auto_increment column has issued, kept after the rows are deleted
+    pub sequences: RefCell<HashMap<String, i64>>,
+    // kept in step with the rows by every method changing them
+    pub indexes: RefCell<Vec<Index>>,
+    // hash indexes over the primary key and the unique column sets, in the order of `get_keys`, which
+    // `check_keys` looks the rows up in; kept in step like `indexes`, but never stored or listed
+    pub key_indexes: RefCell<Vec<Index>>,
+    // the last profile taken of the table, which the planner reads; dropped by any change of the rows
+    // or the columns, so it never describes rows the table no longer has
+    pub profile: RefCell<Option<TableProfile>>,
+    // grows with every change of the rows and is stored with the table, so a materialized view can
+    // tell the rows it was taken from have changed since
+    pub version: Cell<u64>,
+    // the last changes of the rows, the newest one making the current version; `set_rows` drops them
+    pub changes: RefCell<VecDeque<RowChange>>,
+    // the table is as it was read from its file, so it can be read from there again and saving it
+    // keeps its pages; any change of the rows, the columns, the indexes or the name clears it
+    pub stored: Cell<bool>,
 }
 
 impl Table
 {
     pub fn new(name: String, scheme: Scheme<dyn CellValue>) -> Self {
+        let key_indexes = Self::get_keys(&scheme)
+            .map(|key| Index::with_kind(key.join(", "), key.to_vec(), IndexKind::Hash))
+            .collect();
         Self {
             name,
             scheme,
             rows: RefCell::new(Vec::default()),
+            sequences: RefCell::new(HashMap::default()),
+            indexes: RefCell::new(Vec::default()),
+            key_indexes: RefCell::new(key_indexes),
+            profile: RefCell::new(None),
+            version: Cell::new(0),
+            changes: RefCell::new(VecDeque::default()),
+            stored: Cell::new(false),
         }
     }
     pub fn builder() -> TableBuilder {
         TableBuilder::default()
     }
     pub fn add_row(&self, new_row: Row<dyn CellValue>) {
-        self.rows.borrow_mut().push(Rc::new(new_row));
+        let position = self.rows.borrow().len();
+        self.advance_sequences(&new_row);
+        for index in self.indexes.borrow_mut().iter_mut().chain(self.key_indexes.borrow_mut().iter_mut()) {
+            index.insert(index.get_key(&self.scheme, &new_row), position);
+        }
+        let new_row = Rc::new(new_row);
+        self.record_change(Some(RowChange::Inserted(position, new_row.clone())));
+        self.rows.borrow_mut().push(new_row);
+    }
+    // adds the row unless it breaks the primary key or a unique constraint
+    pub fn insert_row(&self, new_row: Row<dyn CellValue>) -> Result<(), String> {
+        self.check_keys(&new_row, None)?;
+        self.add_row(new_row);
+        Ok(())
+    }
+    pub fn update_row(&self, index: u64, new_row: Row<dyn CellValue>) -> Result<(), String> {
+        let size = self.rows.borrow().len();
+        if index >= size as u64 {
+            return Err(format!(
+                "index is bigger that actual table size. Table - {}, size - {}, requested index - {}",
+                self.name.as_str(),
+                size,
+                index
+            ));
+        }
+        self.check_keys(&new_row, Some(index as usize))?;
+        self.replace_row(index as usize, new_row);
+        Ok(())
+    }
+    // puts the row in place of the one at `position` as it is, with no checks
+    pub fn replace_row(&self, position: usize, new_row: Row<dyn CellValue>) {
+        let old_row = self.rows.borrow()[position].clone();
+        self.advance_sequences(&new_row);
+        for index in self.indexes.borrow_mut().iter_mut().chain(self.key_indexes.borrow_mut().iter_mut()) {
+            index.remove(&index.get_key(&self.scheme, &old_row), position);
+            index.insert(index.get_key(&self.scheme, &new_row), position);
+        }
+        let new_row = Rc::new(new_row);
+        self.record_change(Some(RowChange::Replaced(position, new_row.clone())));
+        self.rows.borrow_mut()[position] = new_row;
+    }
+    // the primary key, if the table has one, and the unique column sets
+    fn get_keys(scheme: &Scheme<dyn CellValue>) -> impl Iterator<Item = &[String]> {
+        std::iter::once(scheme.get_primary_key())
+            .filter(|primary_key| !primary_key.is_empty())
+            .chain(scheme.get_unique().iter().map(|key| key.as_slice()))
+    }
+    // looks the row up in the key indexes for any other row (but `skip_index`) with the same primary
+    // key or unique column set; NULL never collides with anything within a unique set, while the
+    // primary key can't hold it
+    pub fn check_keys(&self, row: &Row<dyn CellValue>, skip_index: Option<usize>) -> Result<(), String> {
+        let scheme = self.get_scheme();
+        let primary_key = scheme.get_primary_key();
+        if !primary_key.is_empty() {
+            let values = self.get_key_values(row, primary_key);
+            if let Some(position) = values.iter().position(|value| matches!(value, ValueType::Null(_))) {
+                return Err(format!("primary key column {} can't be null", primary_key[position]));
+            }
+        }
+        let key_indexes = self.key_indexes.borrow();
+        for (key, index) in Self::get_keys(scheme).zip(key_indexes.iter()) {
+            let values = index.get_key(scheme, row);
+            if values.contains(&IndexKey::Null) {
+                continue;
+            }
+            let duplicate = index.find(&values)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|&position| Some(position) != skip_index);
+            if let Some(duplicate) = duplicate {
+                let kind = if key == primary_key { "primary key" } else { "unique constraint" };
+                return Err(format!(
+                    "{} ({}) of table {} is violated by the row {}",
+                    kind,
+                    key.join(", "),
+                    self.name,
+                    duplicate
+                ));
+            }
+        }
+        Ok(())
     }
     pub fn pop(&self) {
+        let size = self.rows.borrow().len();
+        if size > 0 {
+            self.remove_from_indexes(size - 1);
+            self.record_change(Some(RowChange::Removed(size - 1)));
+        }
         self.rows.borrow_mut().pop();
     }
 
     pub fn erase(&self, index: u64) -> Result<(), String> {
-        let mut borrows_rows = self.rows.borrow_mut();
-        if index > borrows_rows.len() as u64 {
+        let size = self.rows.borrow().len();
+        if index >= size as u64 {
             return Err(format!(
                 "index is bigger that actual table size. Table - {}, size - {}, requested index - {}",
                 self.name.as_str(),
-                borrows_rows.len(),
+                size,
                 index
             ));
         }
-        borrows_rows.remove(index as usize);
+        self.remove_from_indexes(index as usize);
+        self.record_change(Some(RowChange::Removed(index as usize)));
+        self.rows.borrow_mut().remove(index as usize);
         Ok(())
     }
+    // moves the version on; a change that isn't given, such as all the rows being replaced, can't be
+    // replayed, so the ones before it are dropped as well
+    fn record_change(&self, change: Option<RowChange>) {
+        self.version.set(self.version.get() + 1);
+        self.profile.borrow_mut().take();
+        self.stored.set(false);
+        let mut changes = self.changes.borrow_mut();
+        match change {
+            Some(change) => {
+                if changes.len() == KEPT_CHANGES {
+                    changes.pop_front();
+                }
+                changes.push_back(change);
+            },
+            None => changes.clear(),
+        }
+    }
+    pub fn get_version(&self) -> u64 {
+        self.version.get()
+    }
+    // the version the table was stored with, the changes made before it are gone
+    pub fn set_version(&self, version: u64) {
+        self.version.set(version);
+        self.changes.borrow_mut().clear();
+    }
+    // the changes made since the table had the version, oldest first; `None` if they aren't kept
+    pub fn get_changes_since(&self, version: u64) -> Option<Vec<RowChange>> {
+        let changes = self.changes.borrow();
+        let count = usize::try_from(self.version.get().checked_sub(version)?).ok()?;
+        if count > changes.len() {
+            return None;
+        }
+        Some(changes.iter().skip(changes.len() - count).cloned().collect())
+    }
+    pub fn is_stored(&self) -> bool {
+        self.stored.get()
+    }
+    pub fn set_stored(&self, stored: bool) {
+        self.stored.set(stored);
+    }
+    fn remove_from_indexes(&self, position: usize) {
+        let row = self.rows.borrow()[position].clone();
+        for index in self.indexes.borrow_mut().iter_mut().chain(self.key_indexes.borrow_mut().iter_mut()) {
+            index.remove(&index.get_key(&self.scheme, &row), position);
+            index.shift_after(position);
+        }
+    }
+
+    pub fn get_indexes(&self) -> Ref<'_, Vec<Index>> {
+        self.indexes.borrow()
+    }
+    // builds the index over the rows the table has and keeps it from now on
+    pub fn add_index(&self, name: &str, columns: Vec<String>, kind: IndexKind) -> Result<(), String> {
+        let mut index = Index::with_kind(name.to_string(), columns, kind);
+        index.check(&self.scheme)?;
+        if self.get_indexes().iter().any(|other| other.get_name() == name) {
+            return Err(format!("table {} already has index {}", self.name, name));
+        }
+        index.build(&self.scheme, &self.get_rows());
+        self.indexes.borrow_mut().push(index);
+        self.stored.set(false);
+        Ok(())
+    }
+    pub fn drop_index(&self, name: &str) -> Result<(), String> {
+        if !self.get_indexes().iter().any(|index| index.get_name() == name) {
+            return Err(format!("table {} has no index {}", self.name, name));
+        }
+        self.indexes.borrow_mut().retain(|index| index.get_name() != name);
+        self.stored.set(false);
+        Ok(())
+    }
+    // name of an index that contains the column
+    pub fn find_index_using(&self, column: &str) -> Option<String> {
+        self.get_indexes()
+            .iter()
+            .find(|index| index.get_columns().iter().any(|indexed| indexed == column))
+            .map(|index| index.get_name().to_string())
+    }
+    pub fn get_profile(&self) -> Ref<'_, Option<TableProfile>> {
+        self.profile.borrow()
+    }
+    // profiles the rows the table has and keeps the profile for the planner
+    pub fn update_profile(&self) -> TableProfile {
+        let profile = TableProfile::collect(self);
+        *self.profile.borrow_mut() = Some(profile.clone());
+        profile
+    }
+    // estimated bytes all the indexes of the table take, see `Index::get_memory_size`
+    pub fn get_indexes_memory_size(&self) -> usize {
+        self.get_indexes().iter().map(|index| index.get_memory_size()).sum()
+    }
+    // positions of the rows whose `column` holds `value`, found by a hash index on just the column or
+    // else by a B-tree index led by it; `None` if there is no such index
+    pub fn find_rows(&self, column: &str, value: &ValueType) -> Option<Vec<usize>> {
+        let key = [IndexKey::from_value(value)];
+        let indexes = self.get_indexes();
+        let hashed = indexes.iter().filter(|index| index.get_kind() == IndexKind::Hash);
+        let ordered = indexes.iter().filter(|index| index.get_kind() == IndexKind::BTree);
+        hashed.chain(ordered)
+            .filter(|index| index.get_columns()[0] == column)
+            .find_map(|index| index.find(&key))
+    }
+    // the same as `find_rows` for the cells of `column` within the bounds
+    pub fn find_rows_in_range(&self, column: &str, lower: Bound<&ValueType>, upper: Bound<&ValueType>) -> Option<Vec<usize>> {
+        let key = |bound: Bound<&ValueType>| bound.map(IndexKey::from_value);
+        let (lower, upper) = (key(lower), key(upper));
+        self.get_indexes()
+            .iter()
+            .filter(|index| index.get_columns()[0] == column)
+            .find_map(|index| index.find_range(lower.as_ref(), upper.as_ref()))
+    }
+    // positions of the rows sorted by `columns`, all ascending or all descending, if an index on
+    // exactly these columns can give them; rows with equal cells keep their order, as in a stable sort
+    pub fn get_sorted_rows(&self, columns: &[String], descending: bool) -> Option<Vec<usize>> {
+        self.get_indexes()
+            .iter()
+            .filter(|index| index.get_columns() == columns)
+            .find_map(|index| index.get_sorted(descending))
+    }
+    // positions of the rows that any full-text index of the table finds for the query with the sum of
+    // their scores, best first and in the order of the rows otherwise, see `Index::search`
+    pub fn search(&self, query: &str) -> Result<Vec<(usize, f64)>, String> {
+        let rows_count = self.get_rows().len();
+        let indexes = self.get_indexes();
+        let found = indexes.iter()
+            .filter_map(|index| index.search(query, rows_count))
+            .collect::<Vec<Vec<(usize, f64)>>>();
+        if found.is_empty() {
+            return Err(format!("table {} has no full-text index to search", self.name));
+        }
+        let mut scores = BTreeMap::<usize, f64>::default();
+        for (position, score) in found.into_iter().flatten() {
+            *scores.entry(position).or_default() += score;
+        }
+        let mut scores = scores.into_iter().collect::<Vec<(usize, f64)>>();
+        scores.sort_by(|(_, lhs), (_, rhs)| rhs.total_cmp(lhs));
+        Ok(scores)
+    }
+    // positions of the rows holding `values` in the primary key or the unique column set made of
+    // `columns`, looked up in its key index; `None` if the columns make no key
+    pub fn find_by_key(&self, columns: &[String], values: &[ValueType]) -> Option<Vec<usize>> {
+        let key = values.iter().map(IndexKey::from_value).collect::<Vec<IndexKey>>();
+        self.key_indexes.borrow()
+            .iter()
+            .find(|index| index.get_columns() == columns)
+            .and_then(|index| index.find(&key))
+    }
+    // values of the given columns in the row, unknown columns are skipped
+    pub fn get_key_values(&self, row: &Row<dyn CellValue>, columns: &[String]) -> Vec<ValueType> {
+        columns.iter()
+            .filter_map(|column| self.scheme.get_column_index(column))
+            .filter_map(|index| row.get_values().get(index).map(|value| value.get_value()))
+            .collect()
+    }
+    pub fn get_sequences(&self) -> HashMap<String, i64> {
+        self.sequences.borrow().clone()
+    }
+    pub fn set_sequences(&self, sequences: HashMap<String, i64>) {
+        *self.sequences.borrow_mut() = sequences;
+        self.stored.set(false);
+    }
+    // the value after the last issued one, starting from 1; the sequence never falls behind the
+    // values the column holds, see `advance_sequences`
+    pub fn next_sequence_value(&self, column: &str) -> Result<i64, String> {
+        let last_issued = self.sequences.borrow().get(column).copied().unwrap_or(0);
+        last_issued.checked_add(1).ok_or(format!("sequence of column {} is exhausted", column))
+    }
+    // cell for the column `index` when a row doesn't provide it
+    pub fn generate_missing_value(&self, index: usize) -> Result<Rc<dyn CellValue>, String> {
+        if self.scheme.is_auto_increment_column(index) {
+            let next_value = self.next_sequence_value(&self.scheme.get_columns()[index])?;
+            return self.scheme.generate_value(index, next_value.to_string());
+        }
+        self.scheme.generate_missing_value(index)
+    }
+    // explicit values move the sequence too, so that it never hands them out again; every row the
+    // table gets goes through here
+    fn advance_sequences(&self, row: &Row<dyn CellValue>) {
+        let mut sequences = self.sequences.borrow_mut();
+        for (index, column) in self.scheme.get_columns().iter().enumerate() {
+            if !self.scheme.is_auto_increment_column(index) {
+                continue;
+            }
+            if let Some(ValueType::Int(value)) = row.get_values().get(index).map(|value| value.get_value()) {
+                let last_issued = sequences.entry(column.clone()).or_insert(0);
+                *last_issued = (*last_issued).max(value.get_value());
+            }
+        }
+    }
     pub fn get_name(&self) -> &str {
         self.name.as_str()
     }
@@ -52,16 +374,230 @@ impl Table
     }
 
     pub fn get_scheme_mut(&mut self) -> &mut Scheme<dyn CellValue> {
+        self.stored.set(false);
         &mut self.scheme
     }
 
-    pub fn get_rows(&self) -> Ref<Vec<Rc<Row<dyn CellValue>>>> {
+    pub fn get_rows(&self) -> Ref<'_, Vec<Rc<Row<dyn CellValue>>>> {
         self.rows.borrow()
     }
     pub fn get_columns(&self) -> Vec<String> {
         self.scheme.get_columns()
     }
+    // renames the columns together with their keys and sequences
+    pub fn set_columns(&mut self, columns: Vec<String>) {
+        let old_columns = self.scheme.get_columns();
+        let sequences = self.sequences.take()
+            .into_iter()
+            .map(|(column, last_issued)| {
+                match old_columns.iter().position(|name| *name == column).and_then(|index| columns.get(index)) {
+                    Some(new_name) => (new_name.clone(), last_issued),
+                    None => (column, last_issued),
+                }
+            })
+            .collect();
+        self.set_sequences(sequences);
+        for index in self.indexes.borrow_mut().iter_mut().chain(self.key_indexes.borrow_mut().iter_mut()) {
+            let renamed = index.get_columns()
+                .iter()
+                .map(|column| match old_columns.iter().position(|name| name == column).and_then(|index| columns.get(index)) {
+                    Some(new_name) => new_name.clone(),
+                    None => column.clone(),
+                })
+                .collect();
+            index.set_columns(renamed);
+        }
+        self.scheme.set_columns(columns);
+        self.profile.get_mut().take();
+        self.stored.set(false);
+    }
+    // adds the column at `position`, the end if `None`; the existing rows get its default, NULL or,
+    // for an auto_increment column, the values 1, 2, ... in the order of the rows
+    #[allow(clippy::type_complexity)]
+    pub fn add_column(&mut self, definition: ColumnDefinition, validator: Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>, position: Option<usize>) -> Result<(), String> {
+        let columns_count = self.scheme.get_columns().len();
+        let position = position.unwrap_or(columns_count);
+        if position > columns_count {
+            return Err(format!("position {} is out of range, table {} has {} columns", position, self.name, columns_count));
+        }
+        if self.scheme.get_column_index(&definition.name).is_some() {
+            return Err(format!("table {} already has column {}", self.name, definition.name));
+        }
+        let rows_count = self.get_rows().len();
+        let mut cells = Vec::with_capacity(rows_count);
+        for row_index in 0..rows_count {
+            let cell = if definition.is_computed() {
+                Ok(Rc::new(NullValue::new()) as Rc<dyn CellValue>)
+            } else if definition.is_auto_increment() {
+                definition.build_value((row_index + 1).to_string())
+            } else {
+                definition.build_missing_value()
+            };
+            cells.push(cell.map_err(|err| format!("column {} can't be added to the existing rows: {}", definition.name, err))?);
+        }
+        let mut scheme = self.scheme.clone();
+        let is_auto_increment = definition.is_auto_increment();
+        let name = definition.name.clone();
+        scheme.insert_column(position, definition, validator);
+        scheme.check_computed()?;
+        let rows = self.get_rows()
+            .iter()
+            .zip(cells)
+            .map(|(row, cell)| {
+                let mut values = row.get_values().to_vec();
+                values.insert(position, cell);
+                Row::new(values)
+            })
+            .collect::<Vec<Row<dyn CellValue>>>();
+        let rows = Self::compute_rows(&scheme, rows)
+            .map_err(|err| format!("column {} can't be added to the existing rows: {}", name, err))?;
+        if is_auto_increment && rows_count > 0 {
+            self.sequences.borrow_mut().insert(name, rows_count as i64);
+        }
+        self.scheme = scheme;
+        self.set_rows(rows);
+        Ok(())
+    }
+    pub fn drop_column(&mut self, column: &str) -> Result<(), String> {
+        let index = match self.scheme.get_column_index(column) {
+            Some(index) => index,
+            None => return Err(format!("There is no column {} in table {}", column, self.name)),
+        };
+        if self.scheme.get_columns().len() == 1 {
+            return Err(format!("column {} is the last one of table {}", column, self.name));
+        }
+        if self.scheme.get_key_marker(column).is_some() {
+            return Err(format!("column {} is a part of a key of table {}", column, self.name));
+        }
+        if let Some(index) = self.find_index_using(column) {
+            return Err(format!("column {} is used by the index {}", column, index));
+        }
+        let dependent_columns = self.scheme.get_dependent_columns(column)?
+            .into_iter()
+            .filter(|dependent| dependent != column)
+            .collect::<Vec<String>>();
+        if !dependent_columns.is_empty() {
+            return Err(format!("column {} is used by computed columns {}", column, dependent_columns.join(", ")));
+        }
+        let rows = self.get_rows()
+            .iter()
+            .map(|row| {
+                let mut values = row.get_values().to_vec();
+                values.remove(index);
+                Rc::new(Row::new(values))
+            })
+            .collect();
+        self.sequences.borrow_mut().remove(column);
+        self.scheme.remove_column(index);
+        self.set_rows(rows);
+        Ok(())
+    }
+    // converts every cell of the column `index` as if it was declared by `definition`
+    pub fn convert_column(&self, index: usize, definition: &ColumnDefinition) -> Vec<Result<Rc<dyn CellValue>, String>> {
+        self.get_rows()
+            .iter()
+            .map(|row| convert_value(&row.get_values()[index], definition))
+            .collect()
+    }
+    // the scheme and the rows the table would have with the column `index` declared by `definition`
+    // and holding `cells`, one per row, skipping the rows whose cell is `None`. The computed columns
+    // are computed again and the keys are checked over the result
+    #[allow(clippy::type_complexity)]
+    pub fn convert_rows(
+        &self,
+        index: usize,
+        definition: &ColumnDefinition,
+        validator: Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>,
+        cells: &[Option<Rc<dyn CellValue>>],
+    ) -> Result<(Scheme<dyn CellValue>, Vec<Rc<Row<dyn CellValue>>>), String> {
+        if cells.len() != self.get_rows().len() {
+            return Err(format!("table {} has {} rows, but {} cells were given", self.name, self.get_rows().len(), cells.len()));
+        }
+        let mut scheme = self.scheme.clone();
+        scheme.remove_column(index);
+        scheme.insert_column(index, definition.clone(), validator);
+        scheme.check_computed()?;
+        let rows = self.get_rows()
+            .iter()
+            .zip(cells)
+            .filter_map(|(row, cell)| cell.as_ref().map(|cell| {
+                let mut values = row.get_values().to_vec();
+                values[index] = cell.clone();
+                Row::new(values)
+            }))
+            .collect::<Vec<Row<dyn CellValue>>>();
+        let rows = Self::compute_rows(&scheme, rows)?;
+        let primary_key = self.scheme.get_primary_key();
+        for key in Self::get_keys(&self.scheme) {
+            let index = Index::with_kind(key.join(", "), key.to_vec(), IndexKind::Hash);
+            let mut seen: HashSet<Vec<IndexKey>> = HashSet::with_capacity(rows.len());
+            for row in rows.iter() {
+                let values = index.get_key(&scheme, row);
+                if values.contains(&IndexKey::Null) {
+                    if key == primary_key {
+                        return Err(format!("primary key ({}) of table {} can't be null", key.join(", "), self.name));
+                    }
+                    continue;
+                }
+                if !seen.insert(values) {
+                    return Err(format!("converted values repeat in the key ({}) of table {}", key.join(", "), self.name));
+                }
+            }
+        }
+        Ok((scheme, rows))
+    }
+    // replaces the definition of the column `index` and its cells, one per row, with the converted ones
+    #[allow(clippy::type_complexity)]
+    pub fn retype_column(&mut self, index: usize, definition: ColumnDefinition, validator: Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>, cells: Vec<Rc<dyn CellValue>>) -> Result<(), String> {
+        let converted = cells.into_iter().map(Some).collect::<Vec<Option<Rc<dyn CellValue>>>>();
+        let (scheme, rows) = self.convert_rows(index, &definition, validator, &converted)?;
+        if !definition.is_auto_increment() {
+            self.sequences.borrow_mut().remove(&definition.name);
+        }
+        self.scheme = scheme;
+        self.set_rows(rows);
+        Ok(())
+    }
+    // fills the computed cells of every row, a row which can't be computed fails them all
+    fn compute_rows(scheme: &Scheme<dyn CellValue>, rows: Vec<Row<dyn CellValue>>) -> Result<Vec<Rc<Row<dyn CellValue>>>, String> {
+        rows.into_iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let mut values = row.get_values().to_vec();
+                scheme.compute_values(&mut values).map_err(|err| format!("row {}: {}", index, err))?;
+                Ok(Rc::new(Row::new(values)))
+            })
+            .collect()
+    }
+    // `columns` lists every column of the table once, in the new order
+    pub fn reorder_columns(&mut self, columns: &[String]) -> Result<(), String> {
+        let current_columns = self.scheme.get_columns();
+        if columns.len() != current_columns.len() {
+            return Err(format!("table {} has {} columns, but {} were given", self.name, current_columns.len(), columns.len()));
+        }
+        let mut order = Vec::with_capacity(columns.len());
+        for column in columns {
+            match self.scheme.get_column_index(column) {
+                Some(index) if !order.contains(&index) => order.push(index),
+                Some(_) => return Err(format!("column {} is listed twice", column)),
+                None => return Err(format!("There is no column {} in table {}", column, self.name)),
+            }
+        }
+        let rows = self.get_rows()
+            .iter()
+            .map(|row| Rc::new(Row::new(order.iter().map(|&index| row.get_values()[index].clone()).collect())))
+            .collect();
+        self.scheme.reorder_columns(&order);
+        self.set_rows(rows);
+        Ok(())
+    }
+    // the indexes are built again over the new rows and the sequences catch up with them
     pub fn set_rows(&self, rows: Vec<Rc<Row<dyn CellValue>>>) {
+        rows.iter().for_each(|row| self.advance_sequences(row));
+        self.record_change(None);
+        for index in self.indexes.borrow_mut().iter_mut().chain(self.key_indexes.borrow_mut().iter_mut()) {
+            index.build(&self.scheme, &rows);
+        }
         *self.rows.borrow_mut() = rows;
     }
 
@@ -101,6 +637,27 @@ impl Table
                 crate::types::ValueType::Email(email) => {
                     email.get_value().to_string().len()
                 },
+                crate::types::ValueType::Bool(bool) => {
+                    bool.get_value().to_string().len()
+                },
+                crate::types::ValueType::Decimal(decimal) => {
+                    decimal.to_string().len()
+                },
+                crate::types::ValueType::IntInterval(interval) => {
+                    interval.to_string().len()
+                },
+                crate::types::ValueType::RealInterval(interval) => {
+                    interval.to_string().len()
+                },
+                crate::types::ValueType::DateInterval(interval) => {
+                    interval.to_string().len()
+                },
+                crate::types::ValueType::Enum(enumeration) => {
+                    enumeration.get_value().len()
+                },
+                crate::types::ValueType::Null(_) => {
+                    4
+                },
             };
             if local_size > max_size {
                 max_size = local_size;
@@ -133,6 +690,8 @@ impl TableBuilder {
             Some(name) => name,
             None => return Err("name wasn't specified while constructing the table".to_string())
         };
+        scheme.check_keys()?;
+        scheme.check_computed()?;
         Ok(
             Table::new(name, scheme)
         )
@@ -141,5 +700,216 @@ impl TableBuilder {
 
 #[cfg(test)]
 mod tests {
-    // TODO: implement tests
+    use std::rc::Rc;
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::table::Table;
+    use crate::types::{CellValue, ValueType, SUPPORTED_TYPES};
+    use crate::types::null_value::NullValue;
+
+    fn row(scheme: &Scheme<dyn CellValue>, raw_values: &[&str]) -> Row<dyn CellValue> {
+        let values = raw_values.iter()
+            .enumerate()
+            .map(|(index, raw_value)| match *raw_value {
+                "NULL" => Rc::new(NullValue::new()) as Rc<dyn CellValue>,
+                raw_value => scheme.generate_value(index, raw_value.to_string()).unwrap(),
+            })
+            .collect();
+        Row::new(values)
+    }
+
+    fn people() -> Table {
+        let scheme = ["first_name", "last_name", "email"].iter()
+            .fold(Scheme::<dyn CellValue>::builder(), |builder, column| {
+                builder.with_column("StringValue".to_string(), column.to_string(), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            })
+            .build()
+            .with_primary_key(vec!["first_name".to_string(), "last_name".to_string()])
+            .with_unique(vec![vec!["email".to_string()]]);
+        Table::builder()
+            .with_name("people".to_string())
+            .with_scheme(scheme)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_insert_row_checks_keys() {
+        let table = people();
+        let scheme = table.get_scheme().clone();
+        assert!(table.insert_row(row(&scheme, &["Taras", "Shevchenko", "kobzar@ukr.net"])).is_ok());
+        assert!(table.insert_row(row(&scheme, &["Taras", "Bulba", "NULL"])).is_ok());
+        assert!(table.insert_row(row(&scheme, &["Ivan", "Franko", "NULL"])).is_ok());
+        assert!(table.insert_row(row(&scheme, &["Taras", "Shevchenko", "taras@ukr.net"])).is_err());
+        assert!(table.insert_row(row(&scheme, &["Lesya", "Ukrainka", "kobzar@ukr.net"])).is_err());
+        assert!(table.insert_row(row(&scheme, &["NULL", "Ukrainka", "lesya@ukr.net"])).is_err());
+        assert_eq!(table.get_rows().len(), 3);
+    }
+
+    #[test]
+    fn test_update_row_checks_keys() {
+        let table = people();
+        let scheme = table.get_scheme().clone();
+        table.insert_row(row(&scheme, &["Taras", "Shevchenko", "kobzar@ukr.net"])).unwrap();
+        table.insert_row(row(&scheme, &["Ivan", "Franko", "kamenyar@ukr.net"])).unwrap();
+        assert!(table.update_row(0, row(&scheme, &["Taras", "Shevchenko", "taras@ukr.net"])).is_ok());
+        assert!(table.update_row(1, row(&scheme, &["Taras", "Shevchenko", "ivan@ukr.net"])).is_err());
+        assert!(table.update_row(1, row(&scheme, &["Ivan", "Franko", "taras@ukr.net"])).is_err());
+        assert!(table.update_row(2, row(&scheme, &["Lesya", "Ukrainka", "lesya@ukr.net"])).is_err());
+        // the keys of an erased row are free again and the rows after it keep theirs
+        table.erase(0).unwrap();
+        assert!(table.update_row(0, row(&scheme, &["Ivan", "Franko", "taras@ukr.net"])).is_ok());
+        assert!(table.insert_row(row(&scheme, &["Ivan", "Franko", "franko@ukr.net"])).is_err());
+        assert!(table.insert_row(row(&scheme, &["Taras", "Shevchenko", "kobzar@ukr.net"])).is_ok());
+    }
+
+    #[test]
+    fn test_decimal_keys_are_exact() {
+        let scheme = [("price", "DecimalValue(10, 2, USD)"), ("rate", "DecimalValue(38, 20)")].iter()
+            .fold(Scheme::<dyn CellValue>::builder(), |builder, (column, declaration)| {
+                builder.with_column_definition(
+                    ColumnDefinition::parse(column, declaration).unwrap(),
+                    SUPPORTED_TYPES.get("DecimalValue").unwrap().clone(),
+                )
+            })
+            .build()
+            .with_primary_key(vec!["price".to_string()])
+            .with_unique(vec![vec!["rate".to_string()]]);
+        let table = Table::builder().with_name("rates".to_string()).with_scheme(scheme.clone()).build().unwrap();
+        table.insert_row(row(&scheme, &["10.00 USD", "0.12345678901234567890"])).unwrap();
+        assert!(table.insert_row(row(&scheme, &["10 USD", "0.5"])).is_err());
+        // the rates differ only past the digits a real number keeps
+        assert!(table.insert_row(row(&scheme, &["12.00 USD", "0.12345678901234567891"])).is_ok());
+        assert!(table.insert_row(row(&scheme, &["14.00 USD", "0.1234567890123456789"])).is_err());
+        assert_eq!(table.get_rows().len(), 2);
+    }
+
+    #[test]
+    fn test_keys_follow_renamed_columns() {
+        let mut table = people();
+        assert_eq!(table.get_scheme().get_key_marker("email"), Some("U".to_string()));
+        table.insert_row(row(table.get_scheme(), &["Taras", "Shevchenko", "kobzar@ukr.net"])).unwrap();
+        table.set_columns(vec!["name".to_string(), "surname".to_string(), "mail".to_string()]);
+        assert_eq!(table.get_scheme().get_primary_key(), ["name", "surname"]);
+        assert_eq!(table.get_scheme().get_key_marker("surname"), Some("PK".to_string()));
+        assert!(!table.get_scheme().is_unique_column("name"));
+        assert!(!table.get_scheme().is_unique_column("mail"));
+        let scheme = table.get_scheme().clone();
+        assert!(table.insert_row(row(&scheme, &["Taras", "Shevchenko", "taras@ukr.net"])).is_err());
+        assert!(table.insert_row(row(&scheme, &["Lesya", "Ukrainka", "kobzar@ukr.net"])).is_err());
+    }
+
+    #[test]
+    fn test_table_with_unknown_key_column() {
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column("IntValue".to_string(), "id".to_string(), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .build()
+            .with_primary_key(vec!["uuid".to_string()]);
+        assert!(Table::builder().with_name("t".to_string()).with_scheme(scheme).build().is_err());
+    }
+
+    #[test]
+    fn test_sequences_follow_the_rows() {
+        let scheme = [("first_name", "StringValue"), ("id", "IntValue auto_increment")].iter()
+            .fold(Scheme::<dyn CellValue>::builder(), |builder, (column, declaration)| {
+                let definition = ColumnDefinition::parse(column, declaration).unwrap();
+                let generator = SUPPORTED_TYPES.get(&definition.ty).unwrap().clone();
+                builder.with_column_definition(definition, generator)
+            })
+            .build();
+        let table = Table::builder().with_name("people".to_string()).with_scheme(scheme.clone()).build().unwrap();
+        table.insert_row(row(&scheme, &["Taras", "41"])).unwrap();
+        assert_eq!(table.next_sequence_value("id"), Ok(42));
+        table.erase(0).unwrap();
+        assert_eq!(table.next_sequence_value("id"), Ok(42));
+        table.set_rows(vec![Rc::new(row(&scheme, &["Ivan", "100"]))]);
+        assert_eq!(table.next_sequence_value("id"), Ok(101));
+        table.insert_row(row(&scheme, &["Lesya", &i64::MAX.to_string()])).unwrap();
+        assert!(table.next_sequence_value("id").is_err());
+        assert!(table.generate_missing_value(1).is_err());
+    }
+
+    #[test]
+    fn test_alter_columns_keeps_rows_consistent() {
+        let mut table = people();
+        let scheme = table.get_scheme().clone();
+        table.insert_row(row(&scheme, &["Taras", "Shevchenko", "kobzar@ukr.net"])).unwrap();
+        table.insert_row(row(&scheme, &["Ivan", "Franko", "NULL"])).unwrap();
+        let column = |declaration: &str, name: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let generator = |ty: &str| SUPPORTED_TYPES.get(ty).unwrap().clone();
+
+        assert!(table.add_column(column("IntValue not_null", "age"), generator("IntValue"), None).is_err());
+        assert!(table.add_column(column("IntValue", "email"), generator("IntValue"), None).is_err());
+        assert!(table.add_column(column("IntValue", "age"), generator("IntValue"), Some(4)).is_err());
+        table.add_column(column("IntValue default(30)", "age"), generator("IntValue"), None).unwrap();
+        table.add_column(column("IntValue auto_increment", "id"), generator("IntValue"), Some(0)).unwrap();
+        assert_eq!(table.get_columns(), ["id", "first_name", "last_name", "email", "age"]);
+        assert_eq!(table.next_sequence_value("id"), Ok(3));
+
+        table.add_column(column("StringValue", "nickname"), generator("StringValue"), Some(3)).unwrap();
+        assert!(table.drop_column("last_name").is_err());
+        assert!(table.drop_column("email").is_err());
+        assert!(table.drop_column("phone").is_err());
+        table.drop_column("nickname").unwrap();
+        assert!(table.reorder_columns(&["age".to_string(), "id".to_string()]).is_err());
+        assert!(table.reorder_columns(&["age".to_string(), "age".to_string(), "id".to_string(), "first_name".to_string(), "email".to_string()]).is_err());
+        table.reorder_columns(&["last_name".to_string(), "first_name".to_string(), "age".to_string(), "id".to_string(), "email".to_string()]).unwrap();
+        assert_eq!(table.get_scheme().get_types(), ["StringValue", "StringValue", "IntValue", "IntValue", "StringValue"]);
+        assert!(table.get_scheme().is_auto_increment_column(3));
+
+        let cells = table.get_rows()
+            .iter()
+            .map(|row| row.get_values()
+                .iter()
+                .map(|value| match value.get_value() {
+                    ValueType::Int(value) => value.get_value().to_string(),
+                    ValueType::Str(value) => value.get_value().to_string(),
+                    ValueType::Null(_) => "NULL".to_string(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<String>>())
+            .collect::<Vec<Vec<String>>>();
+        assert_eq!(cells, [["Shevchenko", "Taras", "30", "1", "kobzar@ukr.net"], ["Franko", "Ivan", "30", "2", "NULL"]]);
+        let scheme = table.get_scheme().clone();
+        assert!(table.insert_row(row(&scheme, &["Shevchenko", "Taras", "40", "3", "NULL"])).is_err());
+    }
+
+    #[test]
+    fn test_computed_columns_follow_alterations() {
+        let mut table = people();
+        let scheme = table.get_scheme().clone();
+        table.insert_row(row(&scheme, &["Taras", "Shevchenko", "kobzar@ukr.net"])).unwrap();
+        table.insert_row(row(&scheme, &["Ivan", "Franko", "NULL"])).unwrap();
+        let column = |declaration: &str, name: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let generator = |ty: &str| SUPPORTED_TYPES.get(ty).unwrap().clone();
+        let texts = |table: &Table, index: usize| table.get_rows()
+            .iter()
+            .map(|row| match row.get_values()[index].get_value() {
+                ValueType::Str(value) => value.get_value().to_string(),
+                ValueType::Int(value) => value.get_value().to_string(),
+                ValueType::Null(_) => "NULL".to_string(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<String>>();
+
+        assert!(table.add_column(column("StringValue computed(email_domain(mail))", "domain"), generator("StringValue"), None).is_err());
+        assert!(table.add_column(column("StringValue not_null computed(email_domain(email))", "domain"), generator("StringValue"), None).is_err());
+        table.add_column(column("StringValue computed(email_domain(email))", "domain"), generator("StringValue"), None).unwrap();
+        table.add_column(column("IntValue computed(length(first_name + last_name))", "letters"), generator("IntValue"), Some(0)).unwrap();
+        assert_eq!(texts(&table, 4), ["ukr.net", "NULL"]);
+        assert_eq!(texts(&table, 0), ["15", "10"]);
+
+        assert!(table.drop_column("email").is_err());
+        table.drop_column("domain").unwrap();
+        table.set_columns(["letters", "name", "surname", "email"].map(|column| column.to_string()).to_vec());
+        assert_eq!(table.get_scheme().get_column_definition(0).unwrap().get_expression(), Some("length(name + surname)"));
+
+        let cells = table.convert_column(0, &column("StringValue", "letters"))
+            .into_iter()
+            .collect::<Result<Vec<Rc<dyn CellValue>>, String>>()
+            .unwrap();
+        table.retype_column(0, column("StringValue", "letters"), generator("StringValue"), cells).unwrap();
+        assert!(!table.get_scheme().is_computed_column(0));
+        assert_eq!(texts(&table, 0), ["15", "10"]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::types::{CellValue, ValueType};
+use super::ValueBuilder;
+use value_generator::ValueGenerator;
+#[derive(Clone, Debug, Default, ValueGenerator, PartialEq)]
+pub struct BoolValue {
+    value: bool,
+}
+
+impl CellValue for BoolValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::Bool(self.clone())
+    }
+}
+#[derive(Default)]
+pub struct BoolValueBuilder {
+    row_value: Option<String>,
+}
+
+impl ValueBuilder for BoolValueBuilder {
+    type Value = BoolValue;
+    type RowValueType = bool;
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        match &self.row_value {
+            Some(value) => {
+                let trimmed_value = value.trim();
+                match trimmed_value.to_lowercase().as_str() {
+                    "true" | "yes" | "1" => Ok(true),
+                    "false" | "no" | "0" => Ok(false),
+                    _ => Err(format!("validation has failed: {}", trimmed_value)),
+                }
+            },
+            None => {
+                Err("the value is expected to be set up".to_string())
+            }
+        }
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok(value) => Ok(BoolValue::new(value)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl BoolValue {
+    pub fn new(value: bool) -> Self {
+        Self {
+            value
+        }
+    }
+    pub fn builder() -> BoolValueBuilder {
+        BoolValueBuilder::default()
+    }
+    pub fn get_value(&self) -> bool {
+        self.value
+    }
+    pub fn get_type_name() -> String {
+        "BoolValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::bool_value::BoolValue;
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+    use super::get_value_generator;
+    #[test]
+    fn test_bool_creation_success_true() {
+        const RAW_VALUE: &str = "true";
+        const EXPECTED_RESULT: bool = true;
+        let builder = BoolValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap().get_value(), EXPECTED_RESULT);
+    }
+    #[test]
+    fn test_bool_creation_success_no() {
+        const RAW_VALUE: &str = " No  ";
+        const EXPECTED_RESULT: bool = false;
+        let builder = BoolValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap().get_value(), EXPECTED_RESULT);
+    }
+    #[test]
+    fn test_bool_creation_success_digits() {
+        for (raw_value, expected_result) in [("1", true), ("0", false), ("YES", true), ("False", false)] {
+            let builder = BoolValue::builder()
+                .with_raw_value(raw_value.to_string());
+            assert!(builder.validate().is_ok());
+            let value = builder.build();
+            assert!(value.is_ok());
+            assert_eq!(value.unwrap().get_value(), expected_result);
+        }
+    }
+    #[test]
+    fn test_bool_creation_failure_1() {
+        // https://www.youtube.com/watch?v=hB-WHw6uMWg
+        const RAW_VALUE: &str = "throw away your television";
+        let builder = BoolValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_bool_creation_failure_2() {
+        const RAW_VALUE: &str = "2";
+        let builder = BoolValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_bool_creation_failure_3() {
+        let builder = BoolValue::builder();
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let generator = get_value_generator();
+        let value = generator("yes".to_string()).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::Bool(value) => assert!(value.get_value()),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
@@ -141,7 +141,7 @@ mod tests {
         let value = generator("a".to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Char(value) => assert_eq!(value.get_value(), 'a'),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use crate::types::{CellValue, ValueType};
+use crate::types::date_value::DateValue;
+use crate::types::interval::{check_order, parse_bounds};
+use super::ValueBuilder;
+use value_generator::ValueGenerator;
+
+#[derive(Clone, Debug, Default, PartialEq, ValueGenerator)]
+pub struct DateIntervalValue {
+    lower: DateTime<Utc>,
+    upper: DateTime<Utc>,
+}
+
+impl CellValue for DateIntervalValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::DateInterval(self.clone())
+    }
+}
+#[derive(Default)]
+pub struct DateIntervalValueBuilder {
+    row_value: Option<String>,
+}
+
+impl ValueBuilder for DateIntervalValueBuilder {
+    type Value = DateIntervalValue;
+    type RowValueType = (DateTime<Utc>, DateTime<Utc>);
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        match &self.row_value {
+            Some(value) => {
+                // bounds use the same format as DateValue
+                let (lower, upper) = parse_bounds(value, |bound| {
+                    DateValue::builder()
+                        .with_raw_value(bound.to_string())
+                        .validate()
+                })?;
+                check_order(lower, upper)
+            },
+            None => {
+                Err("the value is expected to be set up".to_string())
+            }
+        }
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok((lower, upper)) => Ok(DateIntervalValue::new(lower, upper)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl DateIntervalValue {
+    pub fn new(lower: DateTime<Utc>, upper: DateTime<Utc>) -> Self {
+        Self {
+            lower,
+            upper,
+        }
+    }
+    pub fn builder() -> DateIntervalValueBuilder {
+        DateIntervalValueBuilder::default()
+    }
+    pub fn get_lower(&self) -> DateTime<Utc> {
+        self.lower
+    }
+    pub fn get_upper(&self) -> DateTime<Utc> {
+        self.upper
+    }
+    pub fn contains(&self, value: DateTime<Utc>) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.lower <= other.lower && other.upper <= self.upper
+    }
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+    pub fn get_type_name() -> String {
+        "DateIntervalValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+}
+
+impl std::fmt::Display for DateIntervalValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+    use crate::types::date_interval_value::{DateIntervalValue, get_value_generator};
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+
+    const VALID_FROM: &str = "Dec  5, 2004 21:16:24.317453000 EET";
+    const VALID_TO: &str = "Sep 18, 2013 07:49:07.000000000 EEST";
+
+    fn parse(raw_value: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(raw_value, "%b %d, %Y %H:%M:%S.%f %Z").unwrap().and_utc()
+    }
+
+    #[test]
+    fn test_date_interval_creation_success() {
+        let raw_value = format!("[{}, {}]", VALID_FROM, VALID_TO);
+        let builder = DateIntervalValue::builder()
+            .with_raw_value(raw_value);
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        let value = value.unwrap();
+        assert_eq!(value.get_lower(), parse(VALID_FROM));
+        assert_eq!(value.get_upper(), parse(VALID_TO));
+    }
+    #[test]
+    fn test_date_interval_creation_failure_reversed() {
+        let raw_value = format!("[{}, {}]", VALID_TO, VALID_FROM);
+        let builder = DateIntervalValue::builder()
+            .with_raw_value(raw_value);
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_date_interval_creation_failure() {
+        let raw_value = format!("[{}]", VALID_FROM);
+        let builder = DateIntervalValue::builder()
+            .with_raw_value(raw_value);
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_date_interval_predicates() {
+        let interval = DateIntervalValue::new(parse(VALID_FROM), parse(VALID_TO));
+        assert!(interval.contains(parse("Jan  1, 2010 00:00:00.000000000 UTC")));
+        assert!(!interval.contains(parse("Jan  1, 2020 00:00:00.000000000 UTC")));
+        let later = DateIntervalValue::new(parse(VALID_TO), parse("Jan  1, 2020 00:00:00.000000000 UTC"));
+        assert!(interval.overlaps(&later));
+        assert!(!interval.contains_interval(&later));
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let raw_value = format!("[{}, {}]", VALID_FROM, VALID_TO);
+        let generator = get_value_generator();
+        let value = generator(raw_value).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::DateInterval(value) => assert_eq!(value.get_upper(), parse(VALID_TO)),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
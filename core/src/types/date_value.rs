@@ -5,6 +5,8 @@ use crate::types::{CellValue, ValueType};
 use super::ValueBuilder;
 use value_generator::ValueGenerator;
 
+// "Sep 18, 2013 07:49:07.000000000 EEST"
+const RAW_VALUE_FORMAT: &str = "%b %d, %Y %H:%M:%S.%f %Z";
 
 #[derive(Clone, Debug, Default, PartialEq, ValueGenerator)]
 pub struct DateValue {
@@ -26,6 +28,10 @@ impl DateValue {
     pub fn get_type(&self) -> String {
         Self::get_type_name()
     }
+    // the date in the format the builder reads
+    pub fn to_raw_value(&self) -> String {
+        self.value.format(RAW_VALUE_FORMAT).to_string()
+    }
 }
 
 impl CellValue for DateValue {
@@ -48,7 +54,7 @@ impl ValueBuilder for CharValueBuilder {
                 let trimmed_value = value.trim();
                 // "Sep 18, 2013 07:49:07.000000000 EEST"
                 // "Dec  5, 2004 21:16:24.317453000 UTC"
-                if let Ok(res) = NaiveDateTime::parse_from_str(trimmed_value, "%b %d, %Y %H:%M:%S.%f %Z") {
+                if let Ok(res) = NaiveDateTime::parse_from_str(trimmed_value, RAW_VALUE_FORMAT) {
                     return Ok(res.and_utc());
                 };
                 Err(format!("validation has failed: {}", trimmed_value))
@@ -107,7 +113,15 @@ mod tests {
         let value = generator(RAW_VALUE.to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Date(value) => assert_eq!(value.get_value(), expected_result),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
+    #[test]
+    fn test_raw_value_round_trip() {
+        const RAW_VALUE: &str = "Dec  5, 2004 21:16:24.317453000 EET";
+        let value = DateValue::builder().with_raw_value(RAW_VALUE.to_string()).build().unwrap();
+        assert_eq!(value.to_raw_value(), "Dec 05, 2004 21:16:24.317453000 UTC");
+        let parsed = DateValue::builder().with_raw_value(value.to_raw_value()).build().unwrap();
+        assert_eq!(parsed, value);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,527 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use std::sync::Arc;
+use value_generator::ValueGenerator;
+use crate::types::{CellValue, ValueType};
+use super::ValueBuilder;
+
+pub const MAX_PRECISION: u32 = 38;
+
+// (symbol, ISO 4217 code) pairs recognised in raw input like `$1,200.00`
+const CURRENCY_SYMBOLS: [(char, &str); 5] = [
+    ('$', "USD"),
+    ('€', "EUR"),
+    ('£', "GBP"),
+    ('¥', "JPY"),
+    ('₴', "UAH"),
+];
+
+// Fixed-point number stored as `units * 10^-scale`, so that sums of prices don't pick up
+// the rounding errors RealValue has
+#[derive(Clone, Debug, ValueGenerator)]
+pub struct DecimalValue {
+    units: i128,
+    scale: u32,
+    precision: u32,
+    currency: Option<String>,
+}
+
+impl CellValue for DecimalValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::Decimal(self.clone())
+    }
+}
+
+impl Default for DecimalValue {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+#[derive(Default)]
+pub struct DecimalValueBuilder {
+    row_value: Option<String>,
+    precision: Option<u32>,
+    scale: Option<u32>,
+    currency: Option<String>,
+}
+
+impl DecimalValueBuilder {
+    pub fn with_precision(mut self, precision: u32) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+    pub fn with_scale(mut self, scale: u32) -> Result<Self, String> {
+        if scale > MAX_PRECISION {
+            return Err(format!("decimal scale {} is bigger than the maximum of {}", scale, MAX_PRECISION));
+        }
+        self.scale = Some(scale);
+        Ok(self)
+    }
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_uppercase());
+        self
+    }
+}
+
+impl ValueBuilder for DecimalValueBuilder {
+    type Value = DecimalValue;
+    type RowValueType = (i128, u32, Option<String>);
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        let value = match &self.row_value {
+            Some(value) => value.trim(),
+            None => return Err("the value is expected to be set up".to_string()),
+        };
+        let (number, parsed_currency) = split_currency(value)?;
+        let (mut units, mut scale) = parse_number(number.as_str())
+            .ok_or_else(|| format!("validation has failed: {}", value))?;
+
+        if let Some(expected_scale) = self.scale {
+            if scale > expected_scale {
+                return Err(format!("{} has more than {} fractional digits", value, expected_scale));
+            }
+            units = units.checked_mul(pow10(expected_scale - scale))
+                .ok_or_else(|| format!("{} is out of the decimal range", value))?;
+            scale = expected_scale;
+        }
+        let precision = self.precision.unwrap_or(MAX_PRECISION);
+        if precision > MAX_PRECISION {
+            return Err(format!("precision {} is bigger than the maximum of {}", precision, MAX_PRECISION));
+        }
+        if digits(units).max(scale) > precision {
+            return Err(format!("{} doesn't fit into precision {}", value, precision));
+        }
+
+        let currency = match (parsed_currency, self.currency.as_ref()) {
+            (Some(parsed), Some(expected)) if parsed != *expected => {
+                return Err(format!("expected currency {}, got {}", expected, parsed));
+            },
+            (Some(parsed), _) => Some(parsed),
+            (None, expected) => expected.cloned(),
+        };
+        Ok((units, scale, currency))
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok((units, scale, currency)) => {
+                let mut value = DecimalValue::new(units, scale);
+                value.precision = self.precision.unwrap_or(MAX_PRECISION);
+                value.currency = currency;
+                Ok(value)
+            },
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl DecimalValue {
+    pub fn new(units: i128, scale: u32) -> Self {
+        Self {
+            units,
+            scale,
+            precision: MAX_PRECISION,
+            currency: None,
+        }
+    }
+    pub fn builder() -> DecimalValueBuilder {
+        DecimalValueBuilder::default()
+    }
+    // builder for a column declared as `DecimalValue(precision, scale[, currency])`
+    pub fn builder_from_declaration(parameters: &[String]) -> Result<DecimalValueBuilder, String> {
+        if parameters.len() < 2 || parameters.len() > 3 {
+            return Err(format!("{} expects (precision, scale[, currency]) parameters", Self::get_type_name()));
+        }
+        let precision = parameters[0].parse::<u32>()
+            .map_err(|_| format!("invalid decimal precision: {}", parameters[0]))?;
+        let scale = parameters[1].parse::<u32>()
+            .map_err(|_| format!("invalid decimal scale: {}", parameters[1]))?;
+        if precision == 0 || precision > MAX_PRECISION {
+            return Err(format!("decimal precision must be between 1 and {}", MAX_PRECISION));
+        }
+        if scale > precision {
+            return Err(format!("decimal scale {} is bigger than its precision {}", scale, precision));
+        }
+        let mut builder = Self::builder()
+            .with_precision(precision)
+            .with_scale(scale)?;
+        if let Some(currency) = parameters.get(2) {
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!("invalid currency code: {}", currency));
+            }
+            builder = builder.with_currency(currency);
+        }
+        Ok(builder)
+    }
+    pub fn with_precision(mut self, precision: u32) -> Self {
+        self.precision = precision;
+        self
+    }
+    pub fn with_currency(mut self, currency: Option<String>) -> Self {
+        self.currency = currency;
+        self
+    }
+    pub fn get_units(&self) -> i128 {
+        self.units
+    }
+    pub fn get_scale(&self) -> u32 {
+        self.scale
+    }
+    pub fn get_precision(&self) -> u32 {
+        self.precision
+    }
+    pub fn get_currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+    pub fn get_type_name() -> String {
+        "DecimalValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+
+    // rounds half away from zero when the scale is reduced
+    pub fn rescale(&self, scale: u32) -> Result<Self, String> {
+        if scale > MAX_PRECISION {
+            return Err(format!("decimal scale {} is bigger than the maximum of {}", scale, MAX_PRECISION));
+        }
+        let units = if scale >= self.scale {
+            self.units.checked_mul(pow10(scale - self.scale))
+                .ok_or_else(|| format!("{} is out of the decimal range at scale {}", self, scale))?
+        } else {
+            let divisor = pow10(self.scale - scale);
+            let quotient = self.units / divisor;
+            let remainder = (self.units % divisor).abs();
+            if remainder >= divisor - remainder {
+                quotient + self.units.signum()
+            } else {
+                quotient
+            }
+        };
+        Ok(Self {
+            units,
+            scale,
+            precision: self.precision,
+            currency: self.currency.clone(),
+        })
+    }
+
+    // the same number with the trailing zeros of the fraction dropped, so `12.50` and `12.5`
+    // have the same text, e.g. when they are compared as join keys
+    pub fn normalize(&self) -> Self {
+        let mut normalized = self.clone();
+        while normalized.scale > 0 && normalized.units % 10 == 0 {
+            normalized.units /= 10;
+            normalized.scale -= 1;
+        }
+        normalized
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Result<Self, String> {
+        let currency = self.merge_currency(other)?;
+        let scale = self.scale.max(other.scale);
+        let units = self.rescale(scale)?.units
+            .checked_add(other.rescale(scale)?.units)
+            .ok_or_else(|| format!("overflow while adding {} and {}", self, other))?;
+        Ok(Self::new(units, scale).with_precision(self.precision.max(other.precision)).with_currency(currency))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, String> {
+        self.checked_add(&other.negate())
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, String> {
+        let currency = self.merge_currency(other)?;
+        let scale = self.scale + other.scale;
+        if scale > MAX_PRECISION {
+            return Err(format!("scale of {} * {} exceeds {}", self, other, MAX_PRECISION));
+        }
+        let units = self.units
+            .checked_mul(other.units)
+            .ok_or_else(|| format!("overflow while multiplying {} and {}", self, other))?;
+        Ok(Self::new(units, scale).with_precision(self.precision.max(other.precision)).with_currency(currency))
+    }
+
+    pub fn negate(&self) -> Self {
+        Self {
+            units: -self.units,
+            scale: self.scale,
+            precision: self.precision,
+            currency: self.currency.clone(),
+        }
+    }
+
+    pub fn sum<'a>(mut values: impl Iterator<Item = &'a DecimalValue>) -> Result<Self, String> {
+        values.try_fold(DecimalValue::default(), |acc, value| acc.checked_add(value))
+    }
+
+    fn merge_currency(&self, other: &Self) -> Result<Option<String>, String> {
+        match (&self.currency, &other.currency) {
+            (Some(lhs), Some(rhs)) if lhs != rhs => Err(format!("can't mix currencies {} and {}", lhs, rhs)),
+            (Some(currency), _) | (None, Some(currency)) => Ok(Some(currency.clone())),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn cmp_numeric(&self, other: &Self) -> Ordering {
+        let lhs_integer = self.units.div_euclid(pow10(self.scale));
+        let rhs_integer = other.units.div_euclid(pow10(other.scale));
+        lhs_integer.cmp(&rhs_integer).then_with(|| {
+            // both fractions are below 10^scale <= 10^38, so aligning them can't overflow
+            let scale = self.scale.max(other.scale);
+            let lhs_fraction = self.units.rem_euclid(pow10(self.scale)) * pow10(scale - self.scale);
+            let rhs_fraction = other.units.rem_euclid(pow10(other.scale)) * pow10(scale - other.scale);
+            lhs_fraction.cmp(&rhs_fraction)
+        })
+    }
+}
+
+impl PartialEq for DecimalValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DecimalValue {}
+
+impl PartialOrd for DecimalValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecimalValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_numeric(other).then_with(|| self.currency.cmp(&other.currency))
+    }
+}
+
+impl Display for DecimalValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.units < 0 { "-" } else { "" };
+        let magnitude = self.units.unsigned_abs();
+        let divisor = pow10(self.scale) as u128;
+        write!(f, "{}{}", sign, magnitude / divisor)?;
+        if self.scale > 0 {
+            write!(f, ".{:0>width$}", magnitude % divisor, width = self.scale as usize)?;
+        }
+        if let Some(currency) = &self.currency {
+            write!(f, " {}", currency)?;
+        }
+        Ok(())
+    }
+}
+
+fn pow10(exponent: u32) -> i128 {
+    10i128.pow(exponent)
+}
+
+fn digits(units: i128) -> u32 {
+    match units.unsigned_abs() {
+        0 => 1,
+        magnitude => magnitude.ilog10() + 1,
+    }
+}
+
+// splits `$1,200.00`, `-€3`, `12.50 UAH` or `USD 12.50` into the number and the currency code
+fn split_currency(value: &str) -> Result<(String, Option<String>), String> {
+    let mut currency = None;
+    let mut number = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match CURRENCY_SYMBOLS.iter().find(|(symbol, _)| *symbol == ch) {
+            Some((_, code)) if currency.is_none() => currency = Some(code.to_string()),
+            Some(_) => return Err(format!("validation has failed: {}", value)),
+            None => number.push(ch),
+        }
+    }
+    let mut words = number.split_whitespace().collect::<Vec<&str>>();
+    let is_code = |word: &str| word.len() == 3 && word.chars().all(|ch| ch.is_ascii_alphabetic());
+    if words.len() == 2 {
+        let position = if is_code(words[0]) { 0 } else { 1 };
+        if !is_code(words[position]) || currency.is_some() {
+            return Err(format!("validation has failed: {}", value));
+        }
+        currency = Some(words.remove(position).to_uppercase());
+    }
+    if words.len() != 1 {
+        return Err(format!("validation has failed: {}", value));
+    }
+    Ok((words[0].replace(',', ""), currency))
+}
+
+fn parse_number(number: &str) -> Option<(i128, u32)> {
+    let (negative, unsigned) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (unsigned, ""),
+    };
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !integer.chars().chain(fraction.chars()).all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let scale = fraction.len() as u32;
+    if scale > MAX_PRECISION {
+        return None;
+    }
+    let mut units: i128 = 0;
+    for ch in integer.chars().chain(fraction.chars()) {
+        units = units.checked_mul(10)?.checked_add(ch.to_digit(10)? as i128)?;
+    }
+    Some((if negative { -units } else { units }, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::decimal_value::{get_value_generator, DecimalValue};
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+    #[test]
+    fn test_decimal_creation_success_1() {
+        const RAW_VALUE: &str = "12.50";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        let value = value.unwrap();
+        assert_eq!(value.get_units(), 1250);
+        assert_eq!(value.get_scale(), 2);
+        assert_eq!(value.get_currency(), None);
+        assert_eq!(value.to_string(), "12.50");
+    }
+    #[test]
+    fn test_decimal_creation_success_dollars() {
+        const RAW_VALUE: &str = " $1,200.00 ";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        let value = value.unwrap();
+        assert_eq!(value.get_units(), 120000);
+        assert_eq!(value.get_currency(), Some("USD"));
+        assert_eq!(value.to_string(), "1200.00 USD");
+    }
+    #[test]
+    fn test_decimal_creation_success_currency_code() {
+        const RAW_VALUE: &str = "-3.5 uah";
+        let value = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_scale(2)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(value.get_units(), -350);
+        assert_eq!(value.get_currency(), Some("UAH"));
+    }
+    #[test]
+    fn test_decimal_creation_failure_1() {
+        // https://www.youtube.com/watch?v=hB-WHw6uMWg
+        const RAW_VALUE: &str = "throw away your television";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_decimal_creation_failure_scale() {
+        const RAW_VALUE: &str = "12.505";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_scale(2)
+            .unwrap();
+        assert!(builder.validate().is_err());
+    }
+    #[test]
+    fn test_decimal_creation_failure_precision() {
+        const RAW_VALUE: &str = "123456.78";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_precision(6);
+        assert!(builder.validate().is_err());
+    }
+    #[test]
+    fn test_decimal_creation_failure_currency() {
+        const RAW_VALUE: &str = "€12";
+        let builder = DecimalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_currency("usd");
+        assert!(builder.validate().is_err());
+    }
+    #[test]
+    fn test_decimal_creation_failure_3() {
+        let builder = DecimalValue::builder();
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_decimal_arithmetic_is_exact() {
+        let lhs = DecimalValue::new(1, 1);
+        let rhs = DecimalValue::new(2, 1);
+        assert_eq!(lhs.checked_add(&rhs).unwrap(), DecimalValue::new(3, 1));
+        assert_eq!(DecimalValue::new(1250, 2).checked_mul(&DecimalValue::new(3, 0)).unwrap(), DecimalValue::new(375, 1));
+        assert_eq!(DecimalValue::new(1, 0).checked_sub(&DecimalValue::new(1, 2)).unwrap().to_string(), "0.99");
+        let values = vec![DecimalValue::new(10, 2); 10];
+        assert_eq!(DecimalValue::sum(values.iter()).unwrap(), DecimalValue::new(1, 0));
+    }
+    #[test]
+    fn test_decimal_mixed_currency_failure() {
+        let lhs = DecimalValue::new(1, 0).with_currency(Some("USD".to_string()));
+        let rhs = DecimalValue::new(1, 0).with_currency(Some("EUR".to_string()));
+        assert!(lhs.checked_add(&rhs).is_err());
+    }
+    #[test]
+    fn test_decimal_ordering() {
+        let mut values = [
+            DecimalValue::new(1250, 2),
+            DecimalValue::new(-5, 1),
+            DecimalValue::new(125, 1),
+            DecimalValue::new(12501, 3),
+        ];
+        values.sort();
+        let sorted = values.iter().map(|value| value.to_string()).collect::<Vec<String>>();
+        assert_eq!(sorted, vec!["-0.5", "12.50", "12.5", "12.501"]);
+        assert_eq!(DecimalValue::new(1250, 2), DecimalValue::new(125, 1));
+    }
+    #[test]
+    fn test_decimal_scale_out_of_range() {
+        assert!(DecimalValue::builder().with_scale(39).is_err());
+        assert!(DecimalValue::new(1, 0).rescale(39).is_err());
+    }
+    #[test]
+    fn test_decimal_normalize() {
+        assert_eq!(DecimalValue::new(1250, 2).normalize().to_string(), "12.5");
+        assert_eq!(DecimalValue::new(1200, 2).normalize().to_string(), "12");
+        assert_eq!(DecimalValue::new(0, 3).normalize().to_string(), "0");
+        assert_eq!(DecimalValue::new(-101, 1).normalize().to_string(), "-10.1");
+    }
+    #[test]
+    fn test_decimal_rescale() {
+        assert_eq!(DecimalValue::new(1255, 3).rescale(2).unwrap().get_units(), 126);
+        assert_eq!(DecimalValue::new(-1255, 3).rescale(2).unwrap().get_units(), -126);
+        assert_eq!(DecimalValue::new(1254, 3).rescale(2).unwrap().get_units(), 125);
+        assert_eq!(DecimalValue::new(10_i128.pow(38) - 1, 38).rescale(0).unwrap().get_units(), 1);
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let generator = get_value_generator();
+        let value = generator("$0.30".to_string()).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::Decimal(value) => assert_eq!(value.to_string(), "0.30 USD"),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
@@ -25,10 +25,9 @@ impl ValueBuilder for EmailValueBuilder {
     type Value = EmailValue;
     type RowValueType = Email;
     fn validate(&self) -> Result<Self::RowValueType, String> {
-        match self.row_value.as_ref() {
-            None => return Err("the value wasn't set up".to_string()),
-            Some(_) => ()
-        };
+        if self.row_value.is_none() {
+            return Err("the value wasn't set up".to_string());
+        }
         match Email::from_str(self.row_value.as_ref().unwrap().as_str()) {
             Ok(email) => Ok(email),
             Err(err) => Err(format!("couldn't get email: {}", err))
@@ -107,7 +106,7 @@ mod tests {
         let value = generator(RAW_VALUE.to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Email(value) => assert_eq!(*value.get_value(), expected_result),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::types::{CellValue, ValueType};
+use super::ValueBuilder;
+use value_generator::ValueGenerator;
+
+// The domain of an enum column lives in the column's type parameters (`EnumValue(red, green, blue)`),
+// so the bare value generator accepts any label and the scheme checks it with `check_domain`
+#[derive(Clone, Debug, Default, PartialEq, ValueGenerator)]
+pub struct EnumValue {
+    value: String,
+}
+
+impl CellValue for EnumValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::Enum(self.clone())
+    }
+}
+#[derive(Default)]
+pub struct EnumValueBuilder {
+    row_value: Option<String>,
+    domain: Option<Vec<String>>,
+}
+
+impl EnumValueBuilder {
+    pub fn with_domain(mut self, domain: Vec<String>) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+}
+
+impl ValueBuilder for EnumValueBuilder {
+    type Value = EnumValue;
+    type RowValueType = String;
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        match &self.row_value {
+            Some(value) => {
+                let trimmed_value = value.trim();
+                if trimmed_value.is_empty() {
+                    return Err("enum value can't be empty".to_string());
+                }
+                let value = EnumValue::new(trimmed_value.to_string());
+                if let Some(domain) = &self.domain {
+                    value.check_domain(domain)?;
+                }
+                Ok(value.value)
+            },
+            None => {
+                Err("the value is expected to be set up".to_string())
+            }
+        }
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok(value) => Ok(EnumValue::new(value)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl EnumValue {
+    pub fn new(value: String) -> Self {
+        Self { value }
+    }
+    pub fn builder() -> EnumValueBuilder {
+        EnumValueBuilder::default()
+    }
+    pub fn get_value(&self) -> &str {
+        self.value.as_str()
+    }
+    pub fn check_domain(&self, domain: &[String]) -> Result<(), String> {
+        if domain.contains(&self.value) {
+            Ok(())
+        } else {
+            Err(format!("{} is not one of the allowed values: {}", self.value, domain.join(", ")))
+        }
+    }
+    pub fn check_declaration(domain: &[String]) -> Result<(), String> {
+        if domain.is_empty() {
+            return Err(format!("{} requires a list of allowed values, e.g. {}(red, green)", Self::get_type_name(), Self::get_type_name()));
+        }
+        for (index, value) in domain.iter().enumerate() {
+            if value.is_empty() {
+                return Err("allowed values of an enum can't be empty".to_string());
+            }
+            if domain[..index].contains(value) {
+                return Err(format!("{} is listed twice in the enum domain", value));
+            }
+        }
+        Ok(())
+    }
+    pub fn get_type_name() -> String {
+        "EnumValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::enum_value::{EnumValue, get_value_generator};
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+
+    fn domain() -> Vec<String> {
+        vec!["red".to_string(), "green".to_string(), "blue".to_string()]
+    }
+
+    #[test]
+    fn test_enum_creation_success_1() {
+        const RAW_VALUE: &str = " green ";
+        const EXPECTED_RESULT: &str = "green";
+        let builder = EnumValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_domain(domain());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap().get_value(), EXPECTED_RESULT);
+    }
+    #[test]
+    fn test_enum_creation_failure_outside_domain() {
+        const RAW_VALUE: &str = "purple";
+        let builder = EnumValue::builder()
+            .with_raw_value(RAW_VALUE.to_string())
+            .with_domain(domain());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_enum_creation_failure_empty() {
+        const RAW_VALUE: &str = "  ";
+        let builder = EnumValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+    }
+    #[test]
+    fn test_enum_creation_failure_3() {
+        let builder = EnumValue::builder();
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_enum_declaration() {
+        assert!(EnumValue::check_declaration(&domain()).is_ok());
+        assert!(EnumValue::check_declaration(&[]).is_err());
+        assert!(EnumValue::check_declaration(&["red".to_string(), "red".to_string()]).is_err());
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let generator = get_value_generator();
+        let value = generator("red".to_string()).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::Enum(value) => assert_eq!(value.get_value(), "red"),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,164 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::types::{CellValue, ValueType};
+use crate::types::interval::{check_order, parse_bounds};
+use super::ValueBuilder;
+use value_generator::ValueGenerator;
+#[derive(Clone, Debug, Default, ValueGenerator, PartialEq)]
+pub struct IntIntervalValue {
+    lower: i64,
+    upper: i64,
+}
+
+impl CellValue for IntIntervalValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::IntInterval(self.clone())
+    }
+}
+#[derive(Default)]
+pub struct IntIntervalValueBuilder {
+    row_value: Option<String>,
+}
+
+impl ValueBuilder for IntIntervalValueBuilder {
+    type Value = IntIntervalValue;
+    type RowValueType = (i64, i64);
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        match &self.row_value {
+            Some(value) => {
+                let (lower, upper) = parse_bounds(value, |bound| {
+                    bound.parse::<i64>().map_err(|err| err.to_string())
+                })?;
+                check_order(lower, upper)
+            },
+            None => {
+                Err("the value is expected to be set up".to_string())
+            }
+        }
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok((lower, upper)) => Ok(IntIntervalValue::new(lower, upper)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl IntIntervalValue {
+    pub fn new(lower: i64, upper: i64) -> Self {
+        Self {
+            lower,
+            upper,
+        }
+    }
+    pub fn builder() -> IntIntervalValueBuilder {
+        IntIntervalValueBuilder::default()
+    }
+    pub fn get_lower(&self) -> i64 {
+        self.lower
+    }
+    pub fn get_upper(&self) -> i64 {
+        self.upper
+    }
+    pub fn contains(&self, value: i64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.lower <= other.lower && other.upper <= self.upper
+    }
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+    pub fn get_type_name() -> String {
+        "IntIntervalValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+}
+
+impl std::fmt::Display for IntIntervalValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::int_interval_value::IntIntervalValue;
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+    use super::get_value_generator;
+    #[test]
+    fn test_int_interval_creation_success_1() {
+        const RAW_VALUE: &str = "[1, 5]";
+        let builder = IntIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap(), IntIntervalValue::new(1, 5));
+    }
+    #[test]
+    fn test_int_interval_creation_success_2() {
+        const RAW_VALUE: &str = "  [-21,-21] ";
+        let builder = IntIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap(), IntIntervalValue::new(-21, -21));
+    }
+    #[test]
+    fn test_int_interval_creation_failure_1() {
+        // https://www.youtube.com/watch?v=hB-WHw6uMWg
+        const RAW_VALUE: &str = "throw away your television";
+        let builder = IntIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_int_interval_creation_failure_reversed() {
+        const RAW_VALUE: &str = "[5, 1]";
+        let builder = IntIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_int_interval_creation_failure_3() {
+        let builder = IntIntervalValue::builder();
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_int_interval_predicates() {
+        let interval = IntIntervalValue::new(18, 30);
+        assert!(interval.contains(18));
+        assert!(interval.contains(30));
+        assert!(!interval.contains(31));
+        assert!(interval.contains_interval(&IntIntervalValue::new(20, 25)));
+        assert!(!interval.contains_interval(&IntIntervalValue::new(20, 35)));
+        assert!(interval.overlaps(&IntIntervalValue::new(30, 40)));
+        assert!(!interval.overlaps(&IntIntervalValue::new(31, 40)));
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let generator = get_value_generator();
+        let value = generator("[1, 2]".to_string()).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::IntInterval(value) => assert_eq!(value, IntIntervalValue::new(1, 2)),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
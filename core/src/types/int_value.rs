@@ -131,7 +131,7 @@ mod tests {
         let value = generator("1".to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Int(value) => assert_eq!(value.get_value(), 1),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
@@ -0,0 +1,26 @@
+// Shared parsing for the `[lower, upper]` syntax used by the interval types.
+// Bounds may contain commas themselves (e.g. `Dec  5, 2004 ...` dates), so every comma is
+// tried as the separator until both halves parse.
+pub(crate) fn parse_bounds<T>(raw_value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<(T, T), String> {
+    let trimmed_value = raw_value.trim();
+    let inner = match trimmed_value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+        Some(inner) => inner,
+        None => return Err(format!("interval is expected to look like [lower, upper]: {}", trimmed_value)),
+    };
+    for (position, _) in inner.match_indices(',') {
+        let lower = parse(inner[..position].trim());
+        let upper = parse(inner[position + 1..].trim());
+        if let (Ok(lower), Ok(upper)) = (lower, upper) {
+            return Ok((lower, upper));
+        }
+    }
+    Err(format!("validation has failed: {}", trimmed_value))
+}
+
+pub(crate) fn check_order<T: PartialOrd>(lower: T, upper: T) -> Result<(T, T), String> {
+    match lower.partial_cmp(&upper) {
+        Some(std::cmp::Ordering::Greater) => Err("lower bound of the interval is bigger than the upper one".to_string()),
+        Some(_) => Ok((lower, upper)),
+        None => Err("bounds of the interval are not comparable".to_string()),
+    }
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
 #![allow(clippy::type_complexity)]
+use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::rc::Rc;
@@ -11,6 +12,13 @@ use std::collections::HashMap;
 use lazy_static::lazy_static;
 use crate::types::date_value::DateValue;
 use crate::types::email_value::EmailValue;
+use crate::types::bool_value::BoolValue;
+use crate::types::decimal_value::DecimalValue;
+use crate::types::int_interval_value::IntIntervalValue;
+use crate::types::real_interval_value::RealIntervalValue;
+use crate::types::date_interval_value::DateIntervalValue;
+use crate::types::enum_value::EnumValue;
+use crate::types::null_value::NullValue;
 
 // TODO: think about how we can refuse from using enum bcs smells like bad design decision
 #[derive(PartialEq)]
@@ -22,6 +30,54 @@ pub enum ValueType {
     Char(CharValue),
     Date(DateValue),
     Email(EmailValue),
+    Bool(BoolValue),
+    Decimal(DecimalValue),
+    IntInterval(IntIntervalValue),
+    RealInterval(RealIntervalValue),
+    DateInterval(DateIntervalValue),
+    Enum(EnumValue),
+    Null(NullValue),
+}
+// values of the same type are ordered, e.g. for range constraints; anything else is incomparable
+impl PartialOrd for ValueType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (ValueType::Int(lhs), ValueType::Int(rhs)) => lhs.get_value().partial_cmp(&rhs.get_value()),
+            (ValueType::Real(lhs), ValueType::Real(rhs)) => lhs.get_value().partial_cmp(&rhs.get_value()),
+            (ValueType::Str(lhs), ValueType::Str(rhs)) => lhs.get_value().partial_cmp(rhs.get_value()),
+            (ValueType::Char(lhs), ValueType::Char(rhs)) => lhs.get_value().partial_cmp(&rhs.get_value()),
+            (ValueType::Date(lhs), ValueType::Date(rhs)) => lhs.get_value().partial_cmp(&rhs.get_value()),
+            (ValueType::Bool(lhs), ValueType::Bool(rhs)) => lhs.get_value().partial_cmp(&rhs.get_value()),
+            (ValueType::Decimal(lhs), ValueType::Decimal(rhs)) => lhs.partial_cmp(rhs),
+            _ => None,
+        }
+    }
+}
+impl ValueType {
+    // text the value generator of the type builds this value from, so a cell can be converted into
+    // another type by feeding it to that type's generator; pictures and NULL have none
+    pub fn to_raw_value(&self) -> Option<String> {
+        match self {
+            ValueType::Int(value) => Some(value.get_value().to_string()),
+            ValueType::Str(value) => Some(value.get_value().to_string()),
+            ValueType::Real(value) => Some(value.get_value().to_string()),
+            ValueType::Pic(_) => None,
+            ValueType::Char(value) => Some(value.get_value().to_string()),
+            ValueType::Date(value) => Some(value.to_raw_value()),
+            ValueType::Email(value) => Some(value.get_value().to_string()),
+            ValueType::Bool(value) => Some(value.get_value().to_string()),
+            ValueType::Decimal(value) => Some(value.to_string()),
+            ValueType::IntInterval(value) => Some(value.to_string()),
+            ValueType::RealInterval(value) => Some(value.to_string()),
+            ValueType::DateInterval(value) => Some(format!(
+                "[{}, {}]",
+                DateValue::new(value.get_lower()).to_raw_value(),
+                DateValue::new(value.get_upper()).to_raw_value()
+            )),
+            ValueType::Enum(value) => Some(value.get_value().to_string()),
+            ValueType::Null(_) => None,
+        }
+    }
 }
 pub trait CellValue: Debug {
     fn get_value(&self) -> ValueType;
@@ -37,10 +93,59 @@ lazy_static! {
         supported_types.insert(StringValue::get_type_name(), crate::types::string_value::get_value_generator());
         supported_types.insert(DateValue::get_type_name(), crate::types::date_value::get_value_generator());
         supported_types.insert(EmailValue::get_type_name(), crate::types::email_value::get_value_generator());
+        supported_types.insert(BoolValue::get_type_name(), crate::types::bool_value::get_value_generator());
+        supported_types.insert(DecimalValue::get_type_name(), crate::types::decimal_value::get_value_generator());
+        supported_types.insert(IntIntervalValue::get_type_name(), crate::types::int_interval_value::get_value_generator());
+        supported_types.insert(RealIntervalValue::get_type_name(), crate::types::real_interval_value::get_value_generator());
+        supported_types.insert(DateIntervalValue::get_type_name(), crate::types::date_interval_value::get_value_generator());
+        supported_types.insert(EnumValue::get_type_name(), crate::types::enum_value::get_value_generator());
         supported_types
     };
 }
 
+// splits a column type declaration like `EnumValue(red, green, blue)` into the type name and its
+// parameters; a name without the `Value` suffix (`Enum(red, green)`) is accepted as well
+pub fn parse_type_declaration(declaration: &str) -> Result<(String, Vec<String>), String> {
+    let declaration = declaration.trim();
+    let (name, parameters) = match declaration.split_once('(') {
+        Some((name, rest)) => {
+            let parameters = match rest.trim_end().strip_suffix(')') {
+                Some(parameters) => parameters,
+                None => return Err(format!("missing closing bracket in the type declaration: {}", declaration)),
+            };
+            let parameters = parameters
+                .split(',')
+                .map(|parameter| parameter.trim().to_string())
+                .filter(|parameter| !parameter.is_empty())
+                .collect::<Vec<String>>();
+            (name.trim(), parameters)
+        },
+        None => (declaration, Vec::default()),
+    };
+    let name = if !SUPPORTED_TYPES.contains_key(name) && SUPPORTED_TYPES.contains_key(&format!("{}Value", name)) {
+        format!("{}Value", name)
+    } else {
+        name.to_string()
+    };
+    Ok((name, parameters))
+}
+
+pub fn check_type_parameters(ty: &str, parameters: &[String]) -> Result<(), String> {
+    if ty == EnumValue::get_type_name() {
+        return EnumValue::check_declaration(parameters);
+    }
+    if ty == StringValue::get_type_name() {
+        return StringValue::check_declaration(parameters);
+    }
+    if ty == DecimalValue::get_type_name() && !parameters.is_empty() {
+        return DecimalValue::builder_from_declaration(parameters).map(|_| ());
+    }
+    if !parameters.is_empty() {
+        return Err(format!("{} doesn't take any parameters", ty));
+    }
+    Ok(())
+}
+
 pub trait ValueBuilder {
     type Value;
     type RowValueType;
@@ -57,3 +162,11 @@ pub mod picture_value;
 pub mod char_value;
 pub mod date_value;
 pub mod email_value;
+pub mod bool_value;
+pub mod decimal_value;
+pub mod int_interval_value;
+pub mod real_interval_value;
+pub mod date_interval_value;
+pub mod enum_value;
+pub mod null_value;
+mod interval;
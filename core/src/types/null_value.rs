@@ -0,0 +1,49 @@
+use crate::types::{CellValue, ValueType};
+
+// A missing cell. It isn't listed in SUPPORTED_TYPES since a column can't be declared as null,
+// any column without NOT NULL constraint may hold it instead of a value of its type
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NullValue;
+
+impl CellValue for NullValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::Null(self.clone())
+    }
+}
+
+impl NullValue {
+    pub fn new() -> Self {
+        Self
+    }
+    pub fn get_type_name() -> String {
+        "NullValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+    // unquoted literal standing for a null cell in row input
+    pub fn is_null_literal(raw_value: &str) -> bool {
+        raw_value.trim().eq_ignore_ascii_case("null")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::null_value::NullValue;
+    use crate::types::{CellValue, ValueType};
+
+    #[test]
+    fn test_null_literal() {
+        assert!(NullValue::is_null_literal("NULL"));
+        assert!(NullValue::is_null_literal(" null "));
+        assert!(!NullValue::is_null_literal("nullable"));
+        assert!(!NullValue::is_null_literal(""));
+    }
+    #[test]
+    fn test_null_value_type() {
+        match NullValue::new().get_value() {
+            ValueType::Null(_) => (),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
@@ -141,7 +141,7 @@ mod tests {
             ValueType::Pic(value) => {
                 assert_eq!(value.get_value().as_bytes(), expected_result.as_bytes())
             },
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::types::{CellValue, ValueType};
+use crate::types::interval::{check_order, parse_bounds};
+use super::ValueBuilder;
+use value_generator::ValueGenerator;
+#[derive(Clone, Debug, Default, ValueGenerator, PartialEq)]
+pub struct RealIntervalValue {
+    lower: f64,
+    upper: f64,
+}
+
+impl CellValue for RealIntervalValue {
+    fn get_value(&self) -> ValueType {
+        ValueType::RealInterval(self.clone())
+    }
+}
+#[derive(Default)]
+pub struct RealIntervalValueBuilder {
+    row_value: Option<String>,
+}
+
+impl ValueBuilder for RealIntervalValueBuilder {
+    type Value = RealIntervalValue;
+    type RowValueType = (f64, f64);
+    fn validate(&self) -> Result<Self::RowValueType, String> {
+        match &self.row_value {
+            Some(value) => {
+                let (lower, upper) = parse_bounds(value, |bound| {
+                    bound.parse::<f64>().map_err(|err| err.to_string())
+                })?;
+                check_order(lower, upper)
+            },
+            None => {
+                Err("the value is expected to be set up".to_string())
+            }
+        }
+    }
+
+    fn build(self) -> Result<Self::Value, String> {
+        match self.validate() {
+            Ok((lower, upper)) => Ok(RealIntervalValue::new(lower, upper)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn with_raw_value(mut self, raw_value: String) -> Self {
+        self.row_value = Some(raw_value);
+        self
+    }
+}
+
+impl RealIntervalValue {
+    pub fn new(lower: f64, upper: f64) -> Self {
+        Self {
+            lower,
+            upper,
+        }
+    }
+    pub fn builder() -> RealIntervalValueBuilder {
+        RealIntervalValueBuilder::default()
+    }
+    pub fn get_lower(&self) -> f64 {
+        self.lower
+    }
+    pub fn get_upper(&self) -> f64 {
+        self.upper
+    }
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.lower <= other.lower && other.upper <= self.upper
+    }
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+    pub fn get_type_name() -> String {
+        "RealIntervalValue".to_string()
+    }
+    pub fn get_type(&self) -> String {
+        Self::get_type_name()
+    }
+}
+
+impl std::fmt::Display for RealIntervalValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::real_interval_value::RealIntervalValue;
+    use crate::types::ValueType;
+    use super::ValueBuilder;
+    use super::get_value_generator;
+    #[test]
+    fn test_real_interval_creation_success_1() {
+        const RAW_VALUE: &str = "[0.5, 1.75]";
+        let builder = RealIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap(), RealIntervalValue::new(0.5, 1.75));
+    }
+    #[test]
+    fn test_real_interval_creation_success_2() {
+        const RAW_VALUE: &str = "  [-21,21.5] ";
+        let builder = RealIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_ok());
+        let value = builder.build();
+        assert!(value.is_ok());
+        assert_eq!(value.unwrap(), RealIntervalValue::new(-21., 21.5));
+    }
+    #[test]
+    fn test_real_interval_creation_failure_1() {
+        // https://www.youtube.com/watch?v=hB-WHw6uMWg
+        const RAW_VALUE: &str = "throw away your television";
+        let builder = RealIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_real_interval_creation_failure_reversed() {
+        const RAW_VALUE: &str = "[5.1, 5]";
+        let builder = RealIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_real_interval_creation_failure_nan() {
+        const RAW_VALUE: &str = "[NaN, 1]";
+        let builder = RealIntervalValue::builder()
+            .with_raw_value(RAW_VALUE.to_string());
+        assert!(builder.validate().is_err());
+    }
+    #[test]
+    fn test_real_interval_creation_failure_3() {
+        let builder = RealIntervalValue::builder();
+        assert!(builder.validate().is_err());
+        let value = builder.build();
+        assert!(value.is_err());
+    }
+    #[test]
+    fn test_real_interval_predicates() {
+        let interval = RealIntervalValue::new(9.99, 19.99);
+        assert!(interval.contains(9.99));
+        assert!(interval.contains(15.));
+        assert!(!interval.contains(20.));
+        assert!(interval.contains_interval(&RealIntervalValue::new(10., 12.5)));
+        assert!(!interval.contains_interval(&RealIntervalValue::new(5., 12.5)));
+        assert!(interval.overlaps(&RealIntervalValue::new(19.99, 29.99)));
+        assert!(!interval.overlaps(&RealIntervalValue::new(0., 9.98)));
+    }
+    #[test]
+    fn test_get_value_generator() {
+        let generator = get_value_generator();
+        let value = generator("[1, 2.5]".to_string()).unwrap();
+        match value.as_ref().get_value() {
+            ValueType::RealInterval(value) => assert_eq!(value, RealIntervalValue::new(1., 2.5)),
+            _ => unreachable!(),
+        };
+    }
+}
\ No newline at end of file
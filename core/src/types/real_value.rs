@@ -153,7 +153,7 @@ mod tests {
         let value = generator("1.23".to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Real(value) => assert_eq!(value.get_value(), 1.23),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
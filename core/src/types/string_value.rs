@@ -52,6 +52,26 @@ impl StringValue {
         StringValueBuilder::default()
     }
     pub fn get_value(&self) -> &str { self.value.as_str() }
+    // `StringValue(64)` limits the value to 64 characters
+    pub fn check_declaration(parameters: &[String]) -> Result<(), String> {
+        match parameters {
+            [] => Ok(()),
+            [max_length] => match max_length.parse::<usize>() {
+                Ok(max_length) if max_length > 0 => Ok(()),
+                _ => Err(format!("invalid max length of a string: {}", max_length)),
+            },
+            _ => Err(format!("{} takes only the max length parameter", Self::get_type_name())),
+        }
+    }
+    pub fn check_max_length(&self, parameters: &[String]) -> Result<(), String> {
+        if let Some(Ok(max_length)) = parameters.first().map(|max_length| max_length.parse::<usize>()) {
+            let length = self.value.chars().count();
+            if length > max_length {
+                return Err(format!("{} characters are longer than the max length of {}", length, max_length));
+            }
+        }
+        Ok(())
+    }
     pub fn get_type_name() -> String {
         "StringValue".to_string()
     }
@@ -104,7 +124,7 @@ mod tests {
         let value = generator(RAW_VALUE.to_string()).unwrap();
         match value.as_ref().get_value() {
             ValueType::Str(value) => assert_eq!(value.get_value(), RAW_VALUE),
-            _ => assert!(false),
+            _ => unreachable!(),
         };
     }
 }
\ No newline at end of file
@@ -0,0 +1,618 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use crate::expression::{Datum, Expression};
+use crate::planner::plan_view;
+use crate::row::Row;
+use crate::scheme::Scheme;
+use crate::table::{RowChange, Table};
+use crate::types::{CellValue, ValueType};
+
+// what a view reads its rows from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViewSource {
+    Table(String),
+    // the join of two tables on the column they share, as `DatabaseManager::join` builds it
+    Join { left_table: String, right_table: String, column: String },
+}
+
+impl ViewSource {
+    pub fn get_tables(&self) -> Vec<&str> {
+        match self {
+            ViewSource::Table(table) => vec![table.as_str()],
+            ViewSource::Join { left_table, right_table, .. } => vec![left_table.as_str(), right_table.as_str()],
+        }
+    }
+}
+
+impl Display for ViewSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ViewSource::Table(table) => write!(f, "{}", table),
+            ViewSource::Join { left_table, right_table, column } => write!(f, "{} JOIN {} ON {}", left_table, right_table, column),
+        }
+    }
+}
+
+// a saved query: the rows of the source for which `filter` is true, sorted by `order_by` and cut down
+// to `columns`. Only the definition is kept, the rows are read anew every time the view is opened
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct View {
+    pub name: String,
+    pub source: ViewSource,
+    // the columns shown, in this order; every column of the source if empty
+    pub columns: Vec<String>,
+    // canonical form of the expression, see `crate::expression`
+    pub filter: Option<String>,
+    // (column, descending), the first one sorts first
+    pub order_by: Vec<(String, bool)>,
+}
+
+impl View {
+    pub fn new(name: String, source: ViewSource) -> Self {
+        Self {
+            name,
+            source,
+            columns: Vec::default(),
+            filter: None,
+            order_by: Vec::default(),
+        }
+    }
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = columns;
+        self
+    }
+    // the filter is parsed and kept in its canonical form
+    pub fn with_filter(mut self, filter: Option<&str>) -> Result<Self, String> {
+        self.filter = filter.map(|filter| Expression::parse(filter).map(|expression| expression.to_string())).transpose()?;
+        Ok(self)
+    }
+    pub fn with_order_by(mut self, order_by: Vec<(String, bool)>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+    pub fn get_source(&self) -> &ViewSource {
+        &self.source
+    }
+    pub fn uses_table(&self, table_name: &str) -> bool {
+        self.source.get_tables().contains(&table_name)
+    }
+    // every column the view names, each of them once
+    pub fn get_used_columns(&self) -> Vec<String> {
+        let mut columns = self.columns.clone();
+        if let ViewSource::Join { column, .. } = &self.source {
+            columns.push(column.clone());
+        }
+        if let Some(filter) = self.filter.as_ref().and_then(|filter| Expression::parse(filter).ok()) {
+            columns.extend(filter.get_columns());
+        }
+        columns.extend(self.order_by.iter().map(|(column, _)| column.clone()));
+        let mut used = Vec::with_capacity(columns.len());
+        for column in columns {
+            if !used.contains(&column) {
+                used.push(column);
+            }
+        }
+        used
+    }
+    pub fn rename_table(&mut self, table_name: &str, new_name: &str) {
+        match &mut self.source {
+            ViewSource::Table(table) if table == table_name => *table = new_name.to_string(),
+            ViewSource::Join { left_table, right_table, .. } => {
+                for table in [left_table, right_table] {
+                    if table == table_name {
+                        *table = new_name.to_string();
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+    // `new_name` gives the new name of a column of the source, `None` keeps the old one
+    pub fn rename_columns(&mut self, new_name: &dyn Fn(&str) -> Option<String>) {
+        let rename = |column: &mut String| {
+            if let Some(new_name) = new_name(column) {
+                *column = new_name;
+            }
+        };
+        self.columns.iter_mut().for_each(rename);
+        self.order_by.iter_mut().for_each(|(column, _)| rename(column));
+        if let ViewSource::Join { column, .. } = &mut self.source {
+            rename(column);
+        }
+        if let Some(filter) = self.filter.as_mut() {
+            if let Ok(mut expression) = Expression::parse(filter) {
+                expression.rename_columns(new_name);
+                *filter = expression.to_string();
+            }
+        }
+    }
+    // the view names only the columns the source has and shows each of them once
+    pub fn check(&self, source_columns: &[String]) -> Result<(), String> {
+        for (index, column) in self.columns.iter().enumerate() {
+            if self.columns[..index].contains(column) {
+                return Err(format!("view {} shows column {} twice", self.name, column));
+            }
+        }
+        if let Some(filter) = &self.filter {
+            Expression::parse(filter).map_err(|err| format!("view {}: {}", self.name, err))?;
+        }
+        match self.get_used_columns().into_iter().find(|column| !source_columns.contains(column)) {
+            Some(column) => Err(format!("view {} refers to unknown column {} of {}", self.name, column, self.source)),
+            None => Ok(()),
+        }
+    }
+    // evaluates the view over the rows of its source; the result is a table named after the view
+    // without keys, as nothing can be written into it
+    pub fn apply(&self, source: &Table) -> Result<Table, String> {
+        self.apply_with_positions(source).map(|(table, _)| table)
+    }
+    // the same as `apply`, together with the position in the source of every row of the result
+    pub fn apply_with_positions(&self, source: &Table) -> Result<(Table, Vec<usize>), String> {
+        let scheme = source.get_scheme();
+        let source_columns = scheme.get_columns();
+        let plan = plan_view(self, source, None)?;
+        let index = |column: &str| scheme.get_column_index(column).unwrap();
+        let filter = self.filter.as_ref().map(|filter| Expression::parse(filter)).transpose()?;
+
+        // the planner may have picked an index narrowing down the rows to look at
+        let candidates = plan.access.find_rows(source)
+            .unwrap_or_else(|| (0..source.get_rows().len()).collect());
+        let source_rows = source.get_rows();
+        let mut selected = vec![false; source_rows.len()];
+        for row_index in candidates {
+            if let Some(filter) = &filter {
+                let matches = evaluate_filter(filter, scheme, &source_rows[row_index])
+                    .map_err(|err| format!("view {}, row {}: {}", self.name, row_index, err))?;
+                if !matches {
+                    continue;
+                }
+            }
+            selected[row_index] = true;
+        }
+        let sorted = plan.order_index.as_ref().and_then(|name| source.get_indexes()
+            .iter()
+            .find(|index| index.get_name() == name)
+            .and_then(|index| index.get_sorted(self.order_by[0].1)));
+        let positions = match sorted {
+            Some(sorted) => sorted.into_iter()
+                .filter(|&row_index| selected[row_index])
+                .collect::<Vec<usize>>(),
+            None => {
+                let mut positions = (0..source_rows.len())
+                    .filter(|&row_index| selected[row_index])
+                    .collect::<Vec<usize>>();
+                let order_by = self.order_by.iter()
+                    .map(|(column, descending)| (index(column), *descending))
+                    .collect::<Vec<(usize, bool)>>();
+                positions.sort_by(|&lhs, &rhs| {
+                    let (lhs, rhs) = (&source_rows[lhs], &source_rows[rhs]);
+                    order_by.iter()
+                        .map(|(index, descending)| {
+                            let ordering = compare_cells(&lhs.get_values()[*index].get_value(), &rhs.get_values()[*index].get_value());
+                            if *descending { ordering.reverse() } else { ordering }
+                        })
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(Ordering::Equal)
+                });
+                positions
+            },
+        };
+
+        let shown = self.get_shown_columns(scheme);
+        let view_scheme = Scheme::new(
+            shown.iter().map(|&index| scheme.get_types()[index].clone()).collect(),
+            shown.iter().map(|&index| source_columns[index].clone()).collect(),
+            shown.iter().map(|&index| scheme.get_validators()[index].clone()).collect(),
+        ).with_parameters(shown.iter().map(|&index| scheme.get_column_parameters(index).to_vec()).collect());
+        let table = Table::new(self.name.clone(), view_scheme);
+        table.set_rows(positions.iter()
+            .map(|&row_index| Rc::new(self.project(scheme, &source_rows[row_index])))
+            .collect());
+        Ok((table, positions))
+    }
+    // true if the row of the source passes the filter
+    pub fn accepts(&self, scheme: &Scheme<dyn CellValue>, row: &Row<dyn CellValue>) -> Result<bool, String> {
+        match &self.filter {
+            Some(filter) => evaluate_filter(&Expression::parse(filter)?, scheme, row)
+                .map_err(|err| format!("view {}: {}", self.name, err)),
+            None => Ok(true),
+        }
+    }
+    // the cells of a row of the source the view shows
+    pub fn project(&self, scheme: &Scheme<dyn CellValue>, row: &Row<dyn CellValue>) -> Row<dyn CellValue> {
+        Row::new(self.get_shown_columns(scheme).into_iter().map(|index| row.get_values()[index].clone()).collect())
+    }
+    fn get_shown_columns(&self, scheme: &Scheme<dyn CellValue>) -> Vec<usize> {
+        match self.columns.is_empty() {
+            true => (0..scheme.get_columns().len()).collect(),
+            false => self.columns.iter().filter_map(|column| scheme.get_column_index(column)).collect(),
+        }
+    }
+}
+
+// when a materialized view catches up with its sources
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RefreshMode {
+    // only when asked to, it's flagged stale in between
+    #[default]
+    OnDemand,
+    // after every change of a source
+    Incremental,
+}
+
+impl RefreshMode {
+    pub fn parse(raw_value: &str) -> Result<Self, String> {
+        match raw_value.trim().to_lowercase().replace(['_', '-'], " ").as_str() {
+            "on demand" | "demand" | "manual" => Ok(RefreshMode::OnDemand),
+            "incremental" | "auto" => Ok(RefreshMode::Incremental),
+            _ => Err(format!("unknown refresh mode: {}, expected on_demand or incremental", raw_value)),
+        }
+    }
+}
+
+impl Display for RefreshMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefreshMode::OnDemand => write!(f, "ON DEMAND"),
+            RefreshMode::Incremental => write!(f, "INCREMENTAL"),
+        }
+    }
+}
+
+// a view whose rows are kept in a table of its own instead of being read from the sources every time.
+// An incremental one over a single table with no order follows the changes of its source one by one,
+// see `catch_up`; the ones ordered or over a join are evaluated again instead
+#[derive(Clone, Debug)]
+pub struct MaterializedView {
+    pub view: View,
+    pub refresh: RefreshMode,
+    // the sources have changed since the rows were taken
+    pub stale: bool,
+    pub table: Table,
+    // the versions of the sources the rows were taken at, see `Table::get_version`
+    pub source_versions: Vec<(String, u64)>,
+    // the position in the source of every row of the table, for a view following the changes of its
+    // source; `None` when they aren't known
+    pub positions: Option<Vec<usize>>,
+}
+
+impl MaterializedView {
+    pub fn new(view: View, refresh: RefreshMode, table: Table) -> Self {
+        Self {
+            view,
+            refresh,
+            stale: false,
+            table,
+            source_versions: Vec::default(),
+            positions: None,
+        }
+    }
+    pub fn get_name(&self) -> &str {
+        self.view.get_name()
+    }
+    pub fn get_view(&self) -> &View {
+        &self.view
+    }
+    pub fn get_refresh(&self) -> RefreshMode {
+        self.refresh
+    }
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+    pub fn get_table(&self) -> &Table {
+        &self.table
+    }
+    pub fn get_source_versions(&self) -> &[(String, u64)] {
+        self.source_versions.as_slice()
+    }
+    // takes the rows of a fresh evaluation of the view over the sources with the given versions
+    pub fn set_table(&mut self, table: Table, positions: Option<Vec<usize>>, source_versions: Vec<(String, u64)>) {
+        self.table = table;
+        self.positions = positions;
+        self.source_versions = source_versions;
+        self.stale = false;
+    }
+    pub fn rename_table(&mut self, table_name: &str, new_name: &str) {
+        self.view.rename_table(table_name, new_name);
+        for (source, _) in self.source_versions.iter_mut().filter(|(source, _)| source == table_name) {
+            *source = new_name.to_string();
+        }
+    }
+    // replays the changes the source has had since the rows were taken, see `Table::get_changes_since`;
+    // returns false if the view has to be evaluated again instead
+    pub fn catch_up(&mut self, source: &Table) -> Result<bool, String> {
+        if self.stale || !self.view.order_by.is_empty() || self.view.get_source() != &ViewSource::Table(source.get_name().to_string()) {
+            return Ok(false);
+        }
+        let version = match self.source_versions.as_slice() {
+            [(table_name, version)] if table_name == source.get_name() => *version,
+            _ => return Ok(false),
+        };
+        let (positions, changes) = match (self.positions.as_mut(), source.get_changes_since(version)) {
+            (Some(positions), Some(changes)) => (positions, changes),
+            _ => return Ok(false),
+        };
+        let scheme = source.get_scheme();
+        for change in changes {
+            match change {
+                RowChange::Inserted(position, row) => {
+                    let at = positions.partition_point(|&other| other < position);
+                    positions[at..].iter_mut().for_each(|other| *other += 1);
+                    if self.view.accepts(scheme, &row)? {
+                        positions.insert(at, position);
+                        insert_row_at(&self.table, at, self.view.project(scheme, &row));
+                    }
+                },
+                RowChange::Replaced(position, row) => match (positions.binary_search(&position), self.view.accepts(scheme, &row)?) {
+                    (Ok(at), true) => self.table.replace_row(at, self.view.project(scheme, &row)),
+                    (Ok(at), false) => {
+                        positions.remove(at);
+                        self.table.erase(at as u64)?;
+                    },
+                    (Err(at), true) => {
+                        positions.insert(at, position);
+                        insert_row_at(&self.table, at, self.view.project(scheme, &row));
+                    },
+                    (Err(_), false) => (),
+                },
+                RowChange::Removed(position) => {
+                    if let Ok(at) = positions.binary_search(&position) {
+                        positions.remove(at);
+                        self.table.erase(at as u64)?;
+                    }
+                    positions.iter_mut().filter(|other| **other > position).for_each(|other| *other -= 1);
+                },
+            }
+        }
+        self.source_versions = vec![(source.get_name().to_string(), source.get_version())];
+        Ok(true)
+    }
+}
+
+// puts the row at `position` of a table with no indexes, such as the one of a materialized view
+fn insert_row_at(table: &Table, position: usize, row: Row<dyn CellValue>) {
+    if position == table.get_rows().len() {
+        return table.add_row(row);
+    }
+    let mut rows = table.get_rows().clone();
+    rows.insert(position, Rc::new(row));
+    table.set_rows(rows);
+}
+
+fn evaluate_filter(filter: &Expression, scheme: &Scheme<dyn CellValue>, row: &Row<dyn CellValue>) -> Result<bool, String> {
+    let values = row.get_values();
+    let lookup = |column: &str| scheme.get_column_index(column)
+        .and_then(|index| values.get(index))
+        .map(|value| Datum::from_value(&value.get_value()));
+    filter.evaluate(&lookup).map(|value| value.is_true())
+}
+
+// NULL goes after every value, values that can't be compared keep their order
+fn compare_cells(lhs: &ValueType, rhs: &ValueType) -> Ordering {
+    match (lhs, rhs) {
+        (ValueType::Null(_), ValueType::Null(_)) => Ordering::Equal,
+        (ValueType::Null(_), _) => Ordering::Greater,
+        (_, ValueType::Null(_)) => Ordering::Less,
+        (lhs, rhs) => lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal),
+    }
+}
+
+// parses `age desc;name` into (column, descending) pairs
+pub fn parse_order_by(raw_value: &str) -> Result<Vec<(String, bool)>, String> {
+    raw_value.split_terminator(';')
+        .map(|item| {
+            let words = item.split_whitespace().collect::<Vec<&str>>();
+            match words.as_slice() {
+                [column] => Ok((column.to_string(), false)),
+                [column, direction] if direction.eq_ignore_ascii_case("asc") => Ok((column.to_string(), false)),
+                [column, direction] if direction.eq_ignore_ascii_case("desc") => Ok((column.to_string(), true)),
+                _ => Err(format!("expected `column [asc|desc]`, got {}", item.trim())),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index::IndexKind;
+    use crate::row::Row;
+    use crate::scheme::{ColumnDefinition, Scheme};
+    use crate::table::Table;
+    use crate::types::{CellValue, ValueType, SUPPORTED_TYPES};
+    use crate::view::{parse_order_by, MaterializedView, RefreshMode, View, ViewSource};
+
+    fn people() -> Table {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("age", "IntValue"), SUPPORTED_TYPES.get("IntValue").unwrap().clone())
+            .build();
+        let table = Table::new("people".to_string(), scheme);
+        for (name, age) in [("Illia", "21"), ("Taras", "NULL"), ("Ivan", "40"), ("Lesya", "21")] {
+            let scheme = table.get_scheme();
+            let age = match age {
+                "NULL" => scheme.generate_null_value(1).unwrap(),
+                age => scheme.generate_value(1, age.to_string()).unwrap(),
+            };
+            table.add_row(Row::new(vec![scheme.generate_value(0, name.to_string()).unwrap(), age]));
+        }
+        table
+    }
+
+    fn names(table: &Table, index: usize) -> Vec<String> {
+        table.get_rows()
+            .iter()
+            .map(|row| match row.get_values()[index].get_value() {
+                ValueType::Str(value) => value.get_value().to_string(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_view() {
+        let table = people();
+        let view = View::new("adults".to_string(), ViewSource::Table("people".to_string()))
+            .with_filter(Some("age >= 18 and name != 'Ivan'"))
+            .unwrap()
+            .with_order_by(parse_order_by("age desc;name").unwrap());
+        let result = view.apply(&table).unwrap();
+        assert_eq!(result.get_name(), "adults");
+        assert_eq!(names(&result, 0), ["Illia", "Lesya"]);
+
+        let view = View::new("by_age".to_string(), ViewSource::Table("people".to_string()))
+            .with_columns(vec!["name".to_string()])
+            .with_order_by(parse_order_by("age;name desc").unwrap());
+        let result = view.apply(&table).unwrap();
+        assert_eq!(result.get_columns(), ["name"]);
+        assert_eq!(names(&result, 0), ["Lesya", "Illia", "Ivan", "Taras"]);
+
+        let scheme = table.get_scheme().clone();
+        table.add_row(Row::new(vec![scheme.generate_value(0, "Marko".to_string()).unwrap(), scheme.generate_value(1, "30".to_string()).unwrap()]));
+        assert_eq!(names(&view.apply(&table).unwrap(), 0), ["Lesya", "Illia", "Marko", "Ivan", "Taras"]);
+    }
+
+    #[test]
+    fn test_filter_by_intervals() {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("ages", "IntIntervalValue"), SUPPORTED_TYPES.get("IntIntervalValue").unwrap().clone())
+            .build();
+        let table = Table::new("groups".to_string(), scheme);
+        for (name, ages) in [("kids", "[6, 12]"), ("teens", "[13, 17]"), ("adults", "[18, 99]")] {
+            let scheme = table.get_scheme();
+            table.add_row(Row::new(vec![scheme.generate_value(0, name.to_string()).unwrap(), scheme.generate_value(1, ages.to_string()).unwrap()]));
+        }
+        let filtered = |filter: &str| {
+            let view = View::new("v".to_string(), ViewSource::Table("groups".to_string()))
+                .with_filter(Some(filter))
+                .unwrap();
+            names(&view.apply(&table).unwrap(), 0)
+        };
+        assert_eq!(filtered("contains(ages, 15)"), ["teens"]);
+        assert_eq!(filtered("overlaps(ages, '[10, 14]')"), ["kids", "teens"]);
+        assert_eq!(filtered("not contains(ages, '[18, 20]')"), ["kids", "teens"]);
+    }
+
+    #[test]
+    fn test_filter_by_small_reals() {
+        let column = |name: &str, declaration: &str| ColumnDefinition::parse(name, declaration).unwrap();
+        let scheme = Scheme::<dyn CellValue>::builder()
+            .with_column_definition(column("name", "StringValue"), SUPPORTED_TYPES.get("StringValue").unwrap().clone())
+            .with_column_definition(column("price", "RealValue"), SUPPORTED_TYPES.get("RealValue").unwrap().clone())
+            .build();
+        let table = Table::new("prices".to_string(), scheme);
+        for (name, price) in [("dust", "0.000001"), ("grain", "0.001"), ("stone", "10")] {
+            let scheme = table.get_scheme();
+            table.add_row(Row::new(vec![scheme.generate_value(0, name.to_string()).unwrap(), scheme.generate_value(1, price.to_string()).unwrap()]));
+        }
+        let view = View::new("cheap".to_string(), ViewSource::Table("prices".to_string()))
+            .with_filter(Some("price < 0.00001 or price >= 10"))
+            .unwrap();
+        assert_eq!(view.filter.as_deref(), Some("price < 0.00001 or price >= 10"));
+        // the filter as it's stored reads back into the same view
+        let reopened = View::new("cheap".to_string(), ViewSource::Table("prices".to_string()))
+            .with_filter(view.filter.as_deref())
+            .unwrap();
+        assert_eq!(reopened.filter, view.filter);
+        assert!(reopened.check(&table.get_columns()).is_ok());
+        assert_eq!(names(&reopened.apply(&table).unwrap(), 0), ["dust", "stone"]);
+    }
+
+    #[test]
+    fn test_check_view() {
+        let table = people();
+        let source = ViewSource::Table("people".to_string());
+        assert!(View::new("v".to_string(), source.clone()).with_filter(Some("age >")).is_err());
+        assert!(View::new("v".to_string(), source.clone()).with_columns(vec!["email".to_string()]).apply(&table).is_err());
+        assert!(View::new("v".to_string(), source.clone()).with_columns(vec!["age".to_string(), "age".to_string()]).apply(&table).is_err());
+        assert!(View::new("v".to_string(), source.clone()).with_order_by(vec![("height".to_string(), false)]).apply(&table).is_err());
+        assert!(View::new("v".to_string(), source.clone()).with_filter(Some("name + 1")).unwrap().apply(&table).is_err());
+        assert!(parse_order_by("age up").is_err());
+
+        let mut view = View::new("v".to_string(), source)
+            .with_columns(vec!["name".to_string()])
+            .with_filter(Some("age > 20"))
+            .unwrap();
+        view.rename_columns(&|column| (column == "age").then(|| "years".to_string()));
+        view.rename_table("people", "persons");
+        assert_eq!(view.filter.as_deref(), Some("years > 20"));
+        assert_eq!(view.source, ViewSource::Table("persons".to_string()));
+        assert_eq!(view.get_used_columns(), ["name", "years"]);
+    }
+    #[test]
+    fn test_materialized_view_follows_its_source() {
+        let table = people();
+        let view = View::new("adults".to_string(), ViewSource::Table("people".to_string()))
+            .with_columns(vec!["name".to_string()])
+            .with_filter(Some("age >= 18"))
+            .unwrap();
+        let (rows, positions) = view.apply_with_positions(&table).unwrap();
+        let mut materialized_view = MaterializedView::new(view.clone(), RefreshMode::Incremental, rows.clone());
+        materialized_view.set_table(rows, Some(positions), vec![("people".to_string(), table.get_version())]);
+        let scheme = table.get_scheme().clone();
+        let row = |name: &str, age: &str| Row::new(vec![
+            scheme.generate_value(0, name.to_string()).unwrap(),
+            scheme.generate_value(1, age.to_string()).unwrap(),
+        ]);
+        table.add_row(row("Taras", "16"));
+        table.add_row(row("Oksana", "30"));
+        table.replace_row(0, row("Illia", "10"));
+        table.replace_row(1, row("Taras", "50"));
+        table.replace_row(2, row("Ivan", "41"));
+        table.erase(3).unwrap();
+        table.pop();
+        assert!(materialized_view.catch_up(&table).unwrap());
+        assert_eq!(names(materialized_view.get_table(), 0), names(&view.apply(&table).unwrap(), 0));
+        assert_eq!(names(materialized_view.get_table(), 0), ["Taras", "Ivan"]);
+        assert_eq!(materialized_view.get_source_versions(), [("people".to_string(), table.get_version())]);
+
+        // replacing all the rows can't be replayed
+        let rows = table.get_rows().clone();
+        table.set_rows(rows);
+        assert!(!materialized_view.catch_up(&table).unwrap());
+        assert!(table.get_changes_since(0).is_none());
+
+        let sorted = view.with_order_by(vec![("name".to_string(), false)]);
+        let mut materialized_view = MaterializedView::new(sorted.clone(), RefreshMode::Incremental, sorted.apply(&table).unwrap());
+        materialized_view.set_table(sorted.apply(&table).unwrap(), None, vec![("people".to_string(), table.get_version())]);
+        table.add_row(row("Marko", "20"));
+        assert!(!materialized_view.catch_up(&table).unwrap());
+
+        assert_eq!(RefreshMode::parse("on_demand").unwrap(), RefreshMode::OnDemand);
+        assert_eq!(RefreshMode::parse(&RefreshMode::Incremental.to_string()).unwrap(), RefreshMode::Incremental);
+        assert!(RefreshMode::parse("hourly").is_err());
+    }
+    #[test]
+    fn test_indexes_give_the_same_rows() {
+        let table = people();
+        let views = [
+            ("age = 21", "name"),
+            ("21 <= age and name != 'Lesya'", "age desc"),
+            ("age < 40", "age"),
+            ("age > 21 or name = 'Taras'", "age;name"),
+            ("'Ivan' = name", "name desc"),
+        ];
+        let evaluate = |table: &Table| views.iter()
+            .map(|(filter, order)| {
+                let view = View::new("view".to_string(), ViewSource::Table("people".to_string()))
+                    .with_filter(Some(filter))
+                    .unwrap()
+                    .with_order_by(parse_order_by(order).unwrap());
+                names(&view.apply(table).unwrap(), 0)
+            })
+            .collect::<Vec<Vec<String>>>();
+        let scanned = evaluate(&table);
+        table.add_index("by_age", vec!["age".to_string()], IndexKind::BTree).unwrap();
+        table.add_index("by_age_and_name", vec!["age".to_string(), "name".to_string()], IndexKind::BTree).unwrap();
+        table.add_index("by_name", vec!["name".to_string()], IndexKind::Hash).unwrap();
+        assert_eq!(evaluate(&table), scanned);
+        assert_eq!(scanned[0], ["Illia", "Lesya"]);
+        assert_eq!(scanned[1], ["Ivan", "Illia"]);
+    }
+}
\ No newline at end of file
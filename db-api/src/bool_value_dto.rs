@@ -0,0 +1,45 @@
+use ion_rs;
+use core::types::bool_value::BoolValue;
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoolValueDTO {
+    pub value: BoolValue,
+}
+
+impl BoolValueDTO {
+    pub fn new(value: BoolValue) -> BoolValueDTO {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("value");
+        writer.write_bool(self.value.get_value()).unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let value = binary_user_reader.read_bool()?;
+        Ok(BoolValueDTO::new(BoolValue::new(value)))
+    }
+}
\ No newline at end of file
@@ -4,6 +4,8 @@ use core::types::ValueBuilder;
 
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct CharValueDTO {
@@ -24,7 +26,7 @@ impl CharValueDTO {
         writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
 
         writer.set_field_name("value");
-        writer.write_string(&self.value.get_value().to_string()).unwrap();
+        writer.write_string(self.value.get_value().to_string()).unwrap();
 
         writer.step_out().unwrap();
         writer.flush().unwrap();
@@ -32,17 +34,20 @@ impl CharValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let ans = binding.text();
         let value = CharValue::builder()
             .with_raw_value(ans.to_owned())
             .build()
-            .unwrap();
-        CharValueDTO::new(value)
+            .map_err(decoding_error_raw)?;
+        Ok(CharValueDTO::new(value))
     }
 }
\ No newline at end of file
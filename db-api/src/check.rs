@@ -0,0 +1,237 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use crate::db::{DatabaseDTO, TablePages};
+use crate::format::read_version;
+use crate::pager::{check_records, checksum, Pager};
+use crate::row_dto::RowDTO;
+use crate::table::TableDTO;
+
+// what a scan found in a table, `rows` are the ones that can be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCheck {
+    pub name: String,
+    pub rows: usize,
+    pub corrupt_rows: Vec<usize>,
+    pub problems: Vec<String>,
+    // false when the definition can't be read, so the rows can't be either
+    pub readable: bool,
+}
+
+impl TableCheck {
+    pub fn is_corrupt(&self) -> bool {
+        !self.problems.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckReport {
+    pub tables: Vec<TableCheck>,
+    // the file the readable tables were written to
+    pub salvaged_to: Option<String>,
+}
+
+impl CheckReport {
+    pub fn is_corrupt(&self) -> bool {
+        self.tables.iter().any(|table| table.is_corrupt())
+    }
+    pub fn get_corrupt_tables(&self) -> Vec<&TableCheck> {
+        self.tables.iter().filter(|table| table.is_corrupt()).collect()
+    }
+}
+
+// a line per table, e.g. "orders: 2 rows, corrupt: row 1 doesn't match its checksum"
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self.tables.iter()
+            .map(|table| match table.is_corrupt() {
+                false => format!("{}: {} rows, ok", table.name, table.rows),
+                true => format!("{}: {} rows, corrupt: {}", table.name, table.rows, table.problems.join("; ")),
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))?;
+        if let Some(path) = self.salvaged_to.as_ref() {
+            write!(f, "\nthe readable tables are written to {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+// reads every table of the file, which has to be of the current version, and reports what can't be read,
+// the file is never changed
+pub fn check(path: &str) -> Result<CheckReport, String> {
+    Ok(scan(path)?.0)
+}
+
+// writes the tables that can be read, with the rows that can, into a new file at `dir`/`name`,
+// the views and the foreign keys using the tables left behind are left behind as well
+pub fn salvage(path: &str, dir: &str, name: &str) -> Result<CheckReport, String> {
+    let target = format!("{}/{}", dir, name);
+    if fs::metadata(&target).is_ok() {
+        return Err(format!("{} already exists", target));
+    }
+    let (mut report, mut database) = scan(path)?;
+    database.name = name.to_string();
+    database.location = dir.to_string();
+    let tables = database.tables.iter().map(|table| table.name.clone()).collect::<Vec<String>>();
+    let has_tables = |used: Vec<&str>| used.iter().all(|table| tables.iter().any(|name| name == table));
+    database.foreign_keys.retain(|foreign_key| has_tables(vec![foreign_key.value.get_table(), foreign_key.value.get_referenced_table()]));
+    database.views.retain(|view| has_tables(view.value.get_source().get_tables()));
+    database.materialized_views.retain(|view| has_tables(view.view.value.get_source().get_tables()));
+
+    let mut pager = Pager::create(&target)?;
+    database.write_pages(&mut pager)?;
+    report.salvaged_to = Some(target);
+    Ok(report)
+}
+
+fn scan(path: &str) -> Result<(CheckReport, DatabaseDTO), String> {
+    let version = read_version(path)?;
+    let mut pager = Pager::open(path)
+        .map_err(|err| format!("{} (version {}) can't be checked until it's opened and migrated: {}", path, version, err))?;
+    // without the catalog there is no telling where the tables are
+    let (mut database, directory) = DatabaseDTO::read_catalog(&mut pager)?;
+    let mut tables = Vec::with_capacity(directory.len());
+    for pages in directory.iter() {
+        let (table_check, table) = scan_table(&mut pager, pages);
+        if let Some(table) = table {
+            database.tables.push(table);
+        }
+        tables.push(table_check);
+    }
+    Ok((CheckReport { tables, salvaged_to: None }, database))
+}
+
+fn scan_table(pager: &mut Pager, pages: &TablePages) -> (TableCheck, Option<TableDTO>) {
+    let mut table_check = TableCheck {
+        name: pages.name.clone(),
+        rows: 0,
+        corrupt_rows: Vec::default(),
+        problems: Vec::default(),
+        readable: false,
+    };
+    let definition = match pager.read_chain(pages.definition_page) {
+        Ok(definition) if checksum(&definition) == pages.checksum => TableDTO::try_decode(definition)
+            .map_err(|err| format!("the definition can't be decoded: {}", err)),
+        Ok(_) => Err("the definition doesn't match its checksum".to_string()),
+        Err(err) => Err(format!("the definition can't be read: {}", err)),
+    };
+    let mut table = match definition {
+        Ok(table) => table,
+        Err(problem) => {
+            table_check.problems.push(problem);
+            return (table_check, None);
+        },
+    };
+    table_check.readable = true;
+    let records = match pager.read_chain_pages(pages.rows_page) {
+        Ok(data) => check_records(&data),
+        Err(err) => {
+            table_check.problems.push(format!("the rows can't be read: {}", err));
+            return (table_check, Some(table));
+        },
+    };
+    for (position, record) in records.iter().enumerate() {
+        // a record can match its checksum and still not be a row, when an older build wrote it wrong
+        let row = record.clone().and_then(|record| RowDTO::try_decode(record)
+            .and_then(|row| row.try_into_row().map(|_| row))
+            .map_err(|err| format!("can't be decoded: {}", err)));
+        match row {
+            Ok(row) => table.rows.push(row),
+            Err(err) => {
+                table_check.corrupt_rows.push(position);
+                table_check.problems.push(format!("row {} {}", position, err));
+            },
+        }
+    }
+    table_check.rows = table.rows.len();
+    if records.len() != pages.rows as usize {
+        table_check.problems.push(format!("{} of {} rows are found", records.len(), pages.rows));
+    }
+    (table_check, Some(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::io::{Seek, SeekFrom, Write};
+    use core::types::int_value::IntValue;
+    use crate::db::DatabaseDTO;
+    use crate::envelope::Envelope;
+    use crate::int_value_dto::IntValueDTO;
+    use crate::pager::{Pager, PAGE_HEADER_SIZE, PAGE_SIZE};
+    use crate::row_dto::RowDTO;
+    use crate::scheme_dto::SchemeDTO;
+    use crate::table::TableDTO;
+    use super::{check, salvage};
+
+    fn corrupt_byte(path: &str, offset: u64) {
+        let mut file = OpenOptions::new().read(true).write(true).open(path).unwrap();
+        let mut byte = [0];
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        std::io::Read::read_exact(&mut file, &mut byte).unwrap();
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        file.write_all(&[byte[0] ^ 0xFF]).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_tables_are_reported_and_the_rest_salvaged() {
+        let dir = std::env::temp_dir().to_str().unwrap().to_string();
+        let path = format!("{}/test_check.db", dir);
+        std::fs::copy(concat!(env!("CARGO_MANIFEST_DIR"), "/test_resources/format_v5.db"), &path).unwrap();
+        assert!(!check(&path).unwrap().is_corrupt());
+
+        let mut pager = Pager::open(&path).unwrap();
+        let (_, directory) = DatabaseDTO::read_catalog(&mut pager).unwrap();
+        drop(pager);
+        let orders = directory.iter().find(|pages| pages.name == "orders").unwrap();
+        let users = directory.iter().find(|pages| pages.name == "users").unwrap();
+        // the last byte of the first row of orders and a byte of the definition of users
+        let row_length = {
+            let mut pager = Pager::open(&path).unwrap();
+            let data = pager.read_chain(orders.rows_page).unwrap();
+            u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as u64
+        };
+        corrupt_byte(&path, orders.rows_page as u64 * PAGE_SIZE as u64 + PAGE_HEADER_SIZE as u64 + 8 + row_length - 1);
+        corrupt_byte(&path, users.definition_page as u64 * PAGE_SIZE as u64 + PAGE_HEADER_SIZE as u64 + 10);
+
+        let report = check(&path).unwrap();
+        assert_eq!(
+            report.to_string(),
+            "orders: 2 rows, corrupt: row 0 doesn't match its checksum\n\
+             users: 0 rows, corrupt: the definition doesn't match its checksum"
+        );
+        assert_eq!(report.get_corrupt_tables()[0].corrupt_rows, [0]);
+        assert!(DatabaseDTO::read_pages(&mut Pager::open(&path).unwrap()).is_err());
+
+        let salvaged_path = format!("{}/test_check_salvaged.db", dir);
+        let _ = std::fs::remove_file(&salvaged_path);
+        let report = salvage(&path, &dir, "test_check_salvaged.db").unwrap();
+        assert_eq!(report.salvaged_to.as_deref(), Some(salvaged_path.as_str()));
+        assert!(salvage(&path, &dir, "test_check_salvaged.db").is_err());
+
+        let salvaged = DatabaseDTO::read_pages(&mut Pager::open(&salvaged_path).unwrap()).unwrap();
+        assert_eq!(salvaged.tables.iter().map(|table| (table.name.as_str(), table.rows.len())).collect::<Vec<(&str, usize)>>(), [("orders", 2)]);
+        // the foreign key to users is gone with it
+        assert!(salvaged.foreign_keys.is_empty());
+        assert_eq!(salvaged.views.len(), 1);
+        assert!(!check(&salvaged_path).unwrap().is_corrupt());
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(salvaged_path).unwrap();
+    }
+
+    #[test]
+    fn test_rows_that_cant_be_decoded_are_reported() {
+        let path = std::env::temp_dir().join("test_check_undecodable.db").to_str().unwrap().to_string();
+        let row = |data: &[u8]| RowDTO::new(vec![Envelope::new("IntValue", data)]);
+        let rows = vec![row(&IntValueDTO::new(IntValue::new(1)).encode()), row(b"not an int"), RowDTO::new(Vec::default())];
+        let table = TableDTO::new("numbers".to_string(), SchemeDTO::new(vec!["IntValue".to_string()], vec!["number".to_string()]), rows);
+        DatabaseDTO::new("test".to_string(), "".to_string(), vec![table]).write_pages(&mut Pager::create(&path).unwrap()).unwrap();
+
+        let report = check(&path).unwrap();
+        assert_eq!(report.get_corrupt_tables()[0].corrupt_rows, [1]);
+        assert!(report.to_string().starts_with("numbers: 2 rows, corrupt: row 1 can't be decoded"));
+        let err = DatabaseDTO::read_pages(&mut Pager::open(&path).unwrap()).unwrap_err();
+        assert!(err.starts_with("Table numbers is corrupt: row 1 can't be read"), "{}", err);
+        std::fs::remove_file(path).unwrap();
+    }
+}
\ No newline at end of file
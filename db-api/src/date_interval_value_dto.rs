@@ -0,0 +1,71 @@
+use chrono::DateTime;
+use ion_rs;
+use core::types::date_interval_value::DateIntervalValue;
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DateIntervalValueDTO {
+    pub value: DateIntervalValue,
+}
+
+impl DateIntervalValueDTO {
+    pub fn new(value: DateIntervalValue) -> DateIntervalValueDTO {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("lower");
+        writer.write_string(self.value.get_lower().to_rfc3339()).unwrap();
+
+        writer.set_field_name("upper");
+        writer.write_string(self.value.get_upper().to_rfc3339()).unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
+        let lower = DateTime::parse_from_rfc3339(binding.text()).map_err(|err| decoding_error_raw(err.to_string()))?.into();
+
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
+        let upper = DateTime::parse_from_rfc3339(binding.text()).map_err(|err| decoding_error_raw(err.to_string()))?.into();
+        Ok(DateIntervalValueDTO::new(DateIntervalValue::new(lower, upper)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use core::types::date_interval_value::DateIntervalValue;
+    use super::DateIntervalValueDTO;
+
+    #[test]
+    fn endec_date_interval_value() {
+        let value = DateIntervalValue::new(
+            Utc.with_ymd_and_hms(2004, 12, 5, 21, 16, 24).unwrap(),
+            Utc.with_ymd_and_hms(2013, 9, 18, 7, 49, 7).unwrap(),
+        );
+        let decoded = DateIntervalValueDTO::decode(DateIntervalValueDTO::new(value.clone()).encode());
+        assert_eq!(decoded.value, value);
+    }
+}
\ No newline at end of file
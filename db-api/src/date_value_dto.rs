@@ -3,6 +3,8 @@ use ion_rs;
 use core::types::date_value::DateValue;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DateValueDTO {
@@ -23,7 +25,7 @@ impl DateValueDTO {
         writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
 
         writer.set_field_name("value");
-        writer.write_string(&self.value.get_value().to_string()).unwrap();
+        writer.write_string(self.value.get_value().to_string()).unwrap();
 
         writer.step_out().unwrap();
         writer.flush().unwrap();
@@ -31,14 +33,17 @@ impl DateValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let ans = binding.text();
-        let value = DateValue::new(NaiveDateTime::parse_from_str(ans, "%b %d, %Y %H:%M:%S.%f %Z").unwrap().and_utc());
-        DateValueDTO::new(value)
+        let value = DateValue::new(NaiveDateTime::parse_from_str(ans, "%b %d, %Y %H:%M:%S.%f %Z").map_err(|err| decoding_error_raw(err.to_string()))?.and_utc());
+        Ok(DateValueDTO::new(value))
     }
 }
\ No newline at end of file
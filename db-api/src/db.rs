@@ -1,45 +1,123 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use ion_rs;
 use ion_rs::IonWriter;
 use ion_rs::element::reader::ElementReader;
 use ion_rs::IonReader;
-use core::db::Database;
+use ion_rs::IonResult;
+use core::db::{Database, TableLoader};
+use ion_rs::StreamItem;
+use ion_rs::element::Element;
+use ion_rs::types::IntAccess;
 use core::table::Table;
+use core::view::MaterializedView;
+use crate::foreign_key_dto::ForeignKeyDTO;
+use crate::materialized_view_dto::MaterializedViewDTO;
+use crate::pager::{checksum, pack_records, unpack_records, PageId, Pager};
+use crate::row_dto::RowDTO;
 use crate::table::TableDTO;
+use crate::view_dto::ViewDTO;
+use crate::Expected;
 
 
+// where a table is in a paged file, the chain of its definition and the one of its rows,
+// with what they are checked against when read
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TablePages {
+    pub name: String,
+    pub definition_page: PageId,
+    pub rows_page: PageId,
+    // of the definition, every row has its own
+    pub checksum: u32,
+    pub rows: u32,
+    // of the table, listed here so the materialized views can be checked without reading it
+    pub version: u64,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct DatabaseDTO {
     pub name: String,
     pub location: String,
     pub tables: Vec<TableDTO>,
+    pub foreign_keys: Vec<ForeignKeyDTO>,
+    pub views: Vec<ViewDTO>,
+    pub materialized_views: Vec<MaterializedViewDTO>,
+    // the tables of a paged file that were never loaded or are as they were read, `write_pages` leaves
+    // their pages as they are
+    pub unloaded_tables: Vec<String>,
 }
 
-impl From<DatabaseDTO> for Database {
-    fn from(value: DatabaseDTO) -> Self {
-        let mut tables = HashMap::with_capacity(value.tables.len());
-        value.tables.iter().for_each(|table| {
-            tables.insert(table.name.clone(), Table::from(table.clone()));
-        });
-        let db = Database::new(value.name, value.location);
-        db.set_tables(tables);
-        db
+// reads the tables of a paged file the first time the database needs them
+#[derive(Debug)]
+pub struct PagedTableLoader {
+    pager: Rc<RefCell<Pager>>,
+    directory: Vec<TablePages>,
+}
+
+impl TableLoader for PagedTableLoader {
+    fn load_table(&self, table_name: &str) -> Result<Table, String> {
+        match self.directory.iter().find(|pages| pages.name == table_name) {
+            Some(pages) => {
+                let table = Table::try_from(DatabaseDTO::read_table(&mut self.pager.borrow_mut(), pages)?)?;
+                table.set_stored(true);
+                Ok(table)
+            },
+            None => Err(format!("There is no table with name {} in the file", table_name)),
+        }
+    }
+    fn get_version(&self, table_name: &str) -> Option<u64> {
+        self.directory.iter().find(|pages| pages.name == table_name).map(|pages| pages.version)
+    }
+}
+
+impl TryFrom<DatabaseDTO> for Database {
+    type Error = String;
+
+    fn try_from(value: DatabaseDTO) -> Result<Self, Self::Error> {
+        let db = value.build_database()?;
+        db.check_materialized_views();
+        Ok(db)
     }
 }
 
 impl From<Database> for DatabaseDTO {
     fn from(value: Database) -> Self {
-        let tables: Vec<TableDTO> = value.tables
+        // the tables that are as stored aren't encoded again
+        let (stored, changed): (Vec<Table>, Vec<Table>) = value.tables
             .take()
             .into_values()
+            .partition(|table| table.is_stored());
+        let tables: Vec<TableDTO> = changed.into_iter()
             .map(|table| {
                 TableDTO::from(table)
             })
             .collect();
+        let mut unloaded_tables = value.unloaded_tables.take();
+        unloaded_tables.extend(stored.into_iter().map(|table| table.name));
+        let foreign_keys = value.foreign_keys
+            .take()
+            .into_iter()
+            .map(ForeignKeyDTO::new)
+            .collect();
+        let views = value.views
+            .take()
+            .into_iter()
+            .map(ViewDTO::new)
+            .collect();
+        let materialized_views = value.materialized_views
+            .take()
+            .into_iter()
+            .map(MaterializedViewDTO::from)
+            .collect();
         Self {
             name: value.name,
             location: value.location,
             tables,
+            foreign_keys,
+            views,
+            materialized_views,
+            unloaded_tables,
         }
     }
 }
@@ -50,9 +128,80 @@ impl DatabaseDTO {
             name,
             location,
             tables,
+            foreign_keys: Vec::default(),
+            views: Vec::default(),
+            materialized_views: Vec::default(),
+            unloaded_tables: Vec::default(),
         }
     }
+    pub fn with_foreign_keys(mut self, foreign_keys: Vec<ForeignKeyDTO>) -> Self {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+    pub fn with_views(mut self, views: Vec<ViewDTO>) -> Self {
+        self.views = views;
+        self
+    }
+    pub fn with_materialized_views(mut self, materialized_views: Vec<MaterializedViewDTO>) -> Self {
+        self.materialized_views = materialized_views;
+        self
+    }
+    // the database as it's stored, without checking its materialized views against its tables
+    fn build_database(self) -> Result<Database, String> {
+        let mut tables = HashMap::with_capacity(self.tables.len());
+        for table in self.tables {
+            tables.insert(table.name.clone(), Table::try_from(table)?);
+        }
+        let materialized_views = self.materialized_views
+            .into_iter()
+            .map(MaterializedView::try_from)
+            .collect::<Result<Vec<MaterializedView>, String>>()?;
+        let db = Database::new(self.name, self.location);
+        db.set_tables(tables);
+        db.set_foreign_keys(self.foreign_keys.into_iter().map(|foreign_key| foreign_key.value).collect());
+        db.set_views(self.views.into_iter().map(|view| view.value).collect());
+        db.set_materialized_views(materialized_views);
+        Ok(db)
+    }
+    // only the catalog is read, the tables are read when they are needed
+    pub fn read_lazily(pager: Rc<RefCell<Pager>>) -> Result<Database, String> {
+        let (database, directory) = Self::read_catalog(&mut pager.borrow_mut())?;
+        let table_names = directory.iter().map(|pages| pages.name.clone()).collect();
+        let db = database.build_database()?;
+        db.set_unloaded_tables(table_names, Box::new(PagedTableLoader { pager, directory }));
+        db.check_materialized_views();
+        Ok(db)
+    }
     pub fn encode(&self) -> Vec<u8> {
+        self.encode_with_tables(|writer| {
+            for table in self.tables.iter() {
+                let data = table.encode();
+                writer.write_blob(data.as_slice()).unwrap();
+            }
+        })
+    }
+    // the same struct, but the tables are only listed with their pages
+    fn encode_catalog(&self, directory: &[TablePages]) -> Vec<u8> {
+        self.encode_with_tables(|writer| {
+            for pages in directory.iter() {
+                writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+                writer.set_field_name("name");
+                writer.write_string(&pages.name).unwrap();
+                writer.set_field_name("definition_page");
+                writer.write_i64(pages.definition_page as i64).unwrap();
+                writer.set_field_name("rows_page");
+                writer.write_i64(pages.rows_page as i64).unwrap();
+                writer.set_field_name("checksum");
+                writer.write_i64(pages.checksum as i64).unwrap();
+                writer.set_field_name("rows");
+                writer.write_i64(pages.rows as i64).unwrap();
+                writer.set_field_name("version");
+                writer.write_i64(pages.version as i64).unwrap();
+                writer.step_out().unwrap();
+            }
+        })
+    }
+    pub(crate) fn encode_with_tables(&self, write_tables: impl Fn(&mut ion_rs::BinaryWriter<Vec<u8>>)) -> Vec<u8> {
         let buffer: Vec<u8> = Vec::new();
 
         let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
@@ -68,9 +217,27 @@ impl DatabaseDTO {
 
         writer.set_field_name("tables");
         writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
-        for table in self.tables.iter() {
-            let data = table.encode();
-            writer.write_blob(data.as_slice()).unwrap();
+        write_tables(&mut writer);
+        writer.step_out().unwrap();
+
+        writer.set_field_name("foreign_keys");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for foreign_key in self.foreign_keys.iter() {
+            writer.write_blob(foreign_key.encode().as_slice()).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("views");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for view in self.views.iter() {
+            writer.write_blob(view.encode().as_slice()).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("materialized_views");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for materialized_view in self.materialized_views.iter() {
+            writer.write_blob(materialized_view.encode().as_slice()).unwrap();
         }
         writer.step_out().unwrap();
 
@@ -80,32 +247,265 @@ impl DatabaseDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let (mut database, elements) = Self::decode_with_tables(data)?;
+        database.tables = elements.into_iter()
+            .map(|element| TableDTO::try_decode(element.as_blob().expected("a blob")?.to_vec()))
+            .collect::<IonResult<Vec<TableDTO>>>()?;
+        Ok(database)
+    }
+    // the database without its tables and the elements of the list of tables
+    fn decode_with_tables(data: Vec<u8>) -> IonResult<(Self, Vec<Element>)> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let name = binding.text();
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let location = binding.text();
 
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-        let elements = binary_user_reader.read_all_elements().unwrap();
-        let mut tables = Vec::<TableDTO>::with_capacity(elements.capacity());
-        for element in elements {
-            let data = element.as_blob().unwrap();
-            tables.push(TableDTO::decode(data.to_vec()));
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let elements = binary_user_reader.read_all_elements()?;
+        binary_user_reader.step_out()?;
+
+        // databases saved before foreign keys existed end with the tables
+        let mut foreign_keys = Vec::<ForeignKeyDTO>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let data = element.as_blob().expected("a blob")?;
+                foreign_keys.push(ForeignKeyDTO::try_decode(data.to_vec())?);
+            }
+            binary_user_reader.step_out()?;
         }
-        // binary_user_reader.step_out().unwrap();
 
-        Self {
+        // as well as the ones saved before views
+        let mut views = Vec::<ViewDTO>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let data = element.as_blob().expected("a blob")?;
+                views.push(ViewDTO::try_decode(data.to_vec())?);
+            }
+            binary_user_reader.step_out()?;
+        }
+
+        // and before materialized views
+        let mut materialized_views = Vec::<MaterializedViewDTO>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let data = element.as_blob().expected("a blob")?;
+                materialized_views.push(MaterializedViewDTO::try_decode(data.to_vec())?);
+            }
+            binary_user_reader.step_out()?;
+        }
+
+        let database = Self {
             name: name.to_owned(),
             location: location.to_owned(),
-            tables,
+            tables: Vec::default(),
+            foreign_keys,
+            views,
+            materialized_views,
+            unloaded_tables: Vec::default(),
+        };
+        Ok((database, elements))
+    }
+
+    // writes the tables over the pages they had, so only the pages that changed are written
+    pub fn write_pages(&self, pager: &mut Pager) -> Result<(), String> {
+        let previous = match pager.get_root_page() {
+            0 => Vec::default(),
+            _ => Self::read_catalog(pager)?.1,
+        };
+        // the pages of the tables that were dropped since can take the new ones
+        let (previous, dropped): (Vec<TablePages>, Vec<TablePages>) = previous.into_iter()
+            .partition(|pages| self.tables.iter().any(|table| table.name == pages.name) || self.unloaded_tables.contains(&pages.name));
+        for pages in dropped {
+            pager.free_chain(pages.definition_page)?;
+            pager.free_chain(pages.rows_page)?;
+        }
+        // the ones that were never loaded keep their pages
+        let (mut directory, mut previous): (Vec<TablePages>, Vec<TablePages>) = previous.into_iter()
+            .partition(|pages| self.unloaded_tables.contains(&pages.name));
+        for table in self.tables.iter() {
+            let pages = previous.iter()
+                .position(|pages| pages.name == table.name)
+                .map(|position| previous.remove(position));
+            let definition = TableDTO {
+                name: table.name.clone(),
+                scheme: table.scheme.clone(),
+                rows: Vec::default(),
+                sequences: table.sequences.clone(),
+                indexes: table.indexes.clone(),
+                version: table.version,
+            };
+            let definition = definition.encode();
+            let definition_page = pager.write_chain(pages.as_ref().map(|pages| pages.definition_page), &definition)?;
+            let rows = table.rows.iter().map(|row| row.encode()).collect::<Vec<Vec<u8>>>();
+            let rows_page = pager.write_chain_pages(pages.as_ref().map(|pages| pages.rows_page), pack_records(&rows))?;
+            directory.push(TablePages {
+                name: table.name.clone(),
+                definition_page,
+                rows_page,
+                checksum: checksum(&definition),
+                rows: rows.len() as u32,
+                version: table.version,
+            });
+        }
+        directory.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        let root_page = match pager.get_root_page() {
+            0 => None,
+            page => Some(page),
+        };
+        let catalog = self.encode_catalog(&directory);
+        let root_page = pager.write_chain(root_page, &catalog)?;
+        pager.set_root_page(root_page);
+        pager.set_root_checksum(checksum(&catalog));
+        pager.flush()
+    }
+    pub fn read_pages(pager: &mut Pager) -> Result<Self, String> {
+        let (mut database, directory) = Self::read_catalog(pager)?;
+        for pages in directory.iter() {
+            database.tables.push(Self::read_table(pager, pages)?);
+        }
+        Ok(database)
+    }
+    // the database without its tables and where the tables are
+    pub fn read_catalog(pager: &mut Pager) -> Result<(Self, Vec<TablePages>), String> {
+        if pager.get_root_page() == 0 {
+            return Err("The file has no catalog".to_string());
+        }
+        let catalog = pager.read_chain(pager.get_root_page())?;
+        if checksum(&catalog) != pager.get_root_checksum() {
+            return Err("The catalog doesn't match its checksum".to_string());
         }
+        Self::parse_catalog(catalog)
+    }
+    // the catalogs of the files before checksums have none of them, nor the counts of rows or versions
+    pub(crate) fn parse_catalog(catalog: Vec<u8>) -> Result<(Self, Vec<TablePages>), String> {
+        let (database, elements) = Self::decode_with_tables(catalog)
+            .map_err(|err| format!("The catalog can't be read: {}", err))?;
+        let mut directory = Vec::<TablePages>::with_capacity(elements.len());
+        for element in elements {
+            let pages = element.as_struct().ok_or("The catalog lists a table that isn't a struct")?;
+            let get_number = |field: &str| pages.get(field)
+                .and_then(|number| number.as_i64())
+                .map(|number| number as u32)
+                .ok_or(format!("A table in the catalog has no {}", field));
+            directory.push(TablePages {
+                name: pages.get("name")
+                    .and_then(|name| name.as_string())
+                    .ok_or("A table in the catalog has no name")?
+                    .to_string(),
+                definition_page: get_number("definition_page")?,
+                rows_page: get_number("rows_page")?,
+                checksum: get_number("checksum").unwrap_or_default(),
+                rows: get_number("rows").unwrap_or_default(),
+                version: pages.get("version").and_then(|version| version.as_i64()).unwrap_or_default() as u64,
+            });
+        }
+        Ok((database, directory))
+    }
+    // fails on the first thing that doesn't match its checksum, see `crate::check` for the rest
+    pub fn read_table(pager: &mut Pager, pages: &TablePages) -> Result<TableDTO, String> {
+        let mut table = Self::read_definition(pager, pages)?;
+        let rows = unpack_records(&pager.read_chain(pages.rows_page)?)
+            .map_err(|err| format!("Table {} is corrupt: {}", pages.name, err))?;
+        if rows.len() != pages.rows as usize {
+            return Err(format!("Table {} is corrupt: {} of {} rows are found", pages.name, rows.len(), pages.rows));
+        }
+        // a row matching its checksum may still hold cells that can't be decoded
+        for (position, row) in rows.into_iter().enumerate() {
+            let row = RowDTO::try_decode(row)
+                .and_then(|row| row.try_into_row().map(|_| row))
+                .map_err(|err| format!("Table {} is corrupt: row {} can't be read: {}", pages.name, position, err))?;
+            table.rows.push(row);
+        }
+        Ok(table)
+    }
+    // the table without its rows
+    pub fn read_definition(pager: &mut Pager, pages: &TablePages) -> Result<TableDTO, String> {
+        let definition = pager.read_chain(pages.definition_page)?;
+        if checksum(&definition) != pages.checksum {
+            return Err(format!("Table {} is corrupt: its definition doesn't match its checksum", pages.name));
+        }
+        TableDTO::try_decode(definition)
+            .map_err(|err| format!("Table {} is corrupt: its definition can't be read: {}", pages.name, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use core::row::Row;
+    use core::types::CellValue;
+    use core::types::int_value::IntValue;
+    use crate::pager::Pager;
+    use crate::row_dto::RowDTO;
+    use crate::scheme_dto::SchemeDTO;
+    use crate::table::TableDTO;
+    use super::DatabaseDTO;
+
+    fn numbers(name: &str, count: i64) -> TableDTO {
+        let rows = (0..count)
+            .map(|number| RowDTO::from(Rc::new(Row::new(vec![Rc::new(IntValue::new(number)) as Rc<dyn CellValue>]))))
+            .collect();
+        TableDTO::new(name.to_string(), SchemeDTO::new(vec!["IntValue".to_string()], vec!["number".to_string()]), rows)
+    }
+
+    #[test]
+    fn test_tables_are_read_when_needed() {
+        let path = std::env::temp_dir().join("test_lazy_tables.db").to_str().unwrap().to_string();
+        let mut pager = Pager::create(&path).unwrap();
+        let database = DatabaseDTO::new("lazy".to_string(), "/tmp".to_string(), vec![numbers("small", 3), numbers("big", 5000)]);
+        database.write_pages(&mut pager).unwrap();
+        let page_count = pager.get_page_count() as usize;
+        drop(pager);
+
+        let pager = Rc::new(RefCell::new(Pager::open(&path).unwrap()));
+        let db = DatabaseDTO::read_lazily(pager.clone()).unwrap();
+        assert!(db.tables.borrow().is_empty());
+        let mut names = db.get_table_names();
+        names.sort();
+        assert_eq!(names, ["big", "small"]);
+        assert_eq!(db.get_table("small").unwrap().get_rows().len(), 3);
+        assert_eq!(*db.unloaded_tables.borrow(), ["big"]);
+        // the catalog and the pages of the small table
+        let (read, _) = pager.borrow().get_io_counts();
+        assert!(read < page_count / 2);
+
+        // the table that was never loaded and the one that is as it was read keep their pages as they are
+        let (_, written) = pager.borrow().get_io_counts();
+        DatabaseDTO::from(db).write_pages(&mut pager.borrow_mut()).unwrap();
+        assert_eq!(pager.borrow().get_io_counts().1, written);
+        drop(pager);
+
+        // past the limit the tables that are as stored are read again when they are needed
+        let pager = Rc::new(RefCell::new(Pager::open(&path).unwrap()));
+        let db = DatabaseDTO::read_lazily(pager.clone()).unwrap();
+        db.set_loaded_rows_limit(1000);
+        db.get_table("small").unwrap().add_row(Row::new(vec![Rc::new(IntValue::new(3)) as Rc<dyn CellValue>]));
+        assert_eq!(db.get_table("big").unwrap().get_rows().len(), 5000);
+        assert_eq!(db.get_table("small").unwrap().get_rows().len(), 4);
+        assert_eq!(*db.unloaded_tables.borrow(), ["big"]);
+        assert_eq!(db.get_table("big").unwrap().get_rows()[4999].get_values()[0].get_value().to_raw_value(), Some("4999".to_string()));
+        DatabaseDTO::from(db).write_pages(&mut pager.borrow_mut()).unwrap();
+        drop(pager);
+
+        let mut pager = Pager::open(&path).unwrap();
+        let mut tables = DatabaseDTO::read_pages(&mut pager).unwrap().tables;
+        tables.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        assert_eq!(tables, [numbers("big", 5000), numbers("small", 4).with_version(1)]);
+        std::fs::remove_file(path).unwrap();
     }
 }
\ No newline at end of file
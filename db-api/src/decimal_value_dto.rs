@@ -0,0 +1,141 @@
+use ion_rs;
+use ion_rs::Decimal;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use ion_rs::IonType;
+use core::types::decimal_value::{DecimalValue, MAX_PRECISION};
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DecimalValueDTO {
+    pub value: DecimalValue,
+}
+
+impl DecimalValueDTO {
+    pub fn new(value: DecimalValue) -> DecimalValueDTO {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("value");
+        let decimal = Decimal::new(self.value.get_units(), -(self.value.get_scale() as i64));
+        writer.write_decimal(&decimal).unwrap();
+
+        writer.set_field_name("precision");
+        writer.write_i64(self.value.get_precision() as i64).unwrap();
+
+        writer.set_field_name("currency");
+        match self.value.get_currency() {
+            Some(currency) => writer.write_string(currency).unwrap(),
+            None => writer.write_null(IonType::String).unwrap(),
+        }
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let decimal = binary_user_reader.read_decimal()?;
+        let (units, scale) = Self::decimal_to_parts(decimal).map_err(decoding_error_raw)?;
+
+        binary_user_reader.next()?;
+        let precision = binary_user_reader.read_i64()?;
+        let precision = u32::try_from(precision).ok()
+            .filter(|precision| (1..=MAX_PRECISION).contains(precision) && *precision >= scale)
+            .ok_or_else(|| decoding_error_raw(format!("invalid precision {} of a decimal at scale {}", precision, scale)))?;
+
+        binary_user_reader.next()?;
+        let currency = if binary_user_reader.is_null() {
+            None
+        } else {
+            Some(binary_user_reader.read_string()?.text().to_owned())
+        };
+
+        Ok(DecimalValueDTO::new(
+            DecimalValue::new(units, scale)
+                .with_precision(precision)
+                .with_currency(currency)
+        ))
+    }
+
+    // the coefficient of the decimal and its exponent turned into the units at a non negative scale,
+    // `1.2345678d7` has the exponent 1 and is read as 12345678 units at scale 0
+    fn decimal_to_parts(decimal: Decimal) -> Result<(i128, u32), String> {
+        let text = decimal.to_string();
+        let (coefficient, scale) = BigDecimal::try_from(decimal)
+            .map_err(|err| format!("couldn't read the decimal {}: {}", text, err))?
+            .into_bigint_and_exponent();
+        let coefficient = coefficient.to_i128()
+            .ok_or_else(|| format!("{} is out of the decimal range", text))?;
+        if scale >= 0 {
+            let scale = u32::try_from(scale).ok()
+                .filter(|scale| *scale <= MAX_PRECISION)
+                .ok_or_else(|| format!("the scale of {} is bigger than {}", text, MAX_PRECISION))?;
+            return Ok((coefficient, scale));
+        }
+        let units = u32::try_from(scale.unsigned_abs()).ok()
+            .and_then(|shift| 10i128.checked_pow(shift))
+            .and_then(|multiplier| coefficient.checked_mul(multiplier))
+            .ok_or_else(|| format!("{} is out of the decimal range", text))?;
+        Ok((units, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ion_rs::Decimal;
+    use core::types::decimal_value::DecimalValue;
+    use super::DecimalValueDTO;
+
+    #[test]
+    fn endec_decimal_value() {
+        for (units, scale) in [(1250, 2), (-5, 1), (12345678, 0), (7, 9), (0, 2), (-120000, 2)] {
+            let value = DecimalValue::new(units, scale).with_currency(Some("USD".to_string()));
+            let decoded = DecimalValueDTO::decode(DecimalValueDTO::new(value.clone()).encode()).value;
+            assert_eq!(decoded.get_units(), units);
+            assert_eq!(decoded.get_scale(), scale);
+            assert_eq!(decoded.get_currency(), Some("USD"));
+        }
+        let value = DecimalValue::new(1, 0).with_precision(4);
+        let decoded = DecimalValueDTO::decode(DecimalValueDTO::new(value).encode()).value;
+        assert_eq!(decoded.get_precision(), 4);
+        assert_eq!(decoded.get_currency(), None);
+        for precision in [0, 39, 1] {
+            let value = DecimalValue::new(125, 2).with_precision(precision);
+            assert!(DecimalValueDTO::try_decode(DecimalValueDTO::new(value).encode()).is_err());
+        }
+    }
+
+    #[test]
+    fn decimal_parts() {
+        for (decimal, expected) in [
+            (Decimal::new(1250, -2), Ok((1250, 2))),
+            (Decimal::new(-5, 0), Ok((-5, 0))),
+            (Decimal::new(12345678, 0), Ok((12345678, 0))),
+            // the exponent is above zero, the scale below the number of fractional digits written
+            (Decimal::new(12, 3), Ok((12000, 0))),
+            (Decimal::new(1, -12), Ok((1, 12))),
+        ] {
+            assert_eq!(DecimalValueDTO::decimal_to_parts(decimal), expected);
+        }
+        assert!(DecimalValueDTO::decimal_to_parts(Decimal::new(1, -39)).is_err());
+        assert!(DecimalValueDTO::decimal_to_parts(Decimal::new(1, 40)).is_err());
+    }
+}
\ No newline at end of file
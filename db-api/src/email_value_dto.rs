@@ -4,6 +4,8 @@ use core::types::email_value::EmailValue;
 
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct EmailValueDTO {
@@ -32,16 +34,19 @@ impl EmailValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let ans = binary_user_reader.read_string().unwrap().to_string();
+        binary_user_reader.next()?;
+        let ans = binary_user_reader.read_string()?.to_string();
         let value = EmailValue::builder()
             .with_raw_value(ans)
             .build()
-            .unwrap();
-        EmailValueDTO::new(value)
+            .map_err(decoding_error_raw)?;
+        Ok(EmailValueDTO::new(value))
     }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ use ion_rs;
 use ion_rs::element::writer::TextKind;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
 
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -51,24 +52,24 @@ impl crate::Encoder for Envelope {
 }
 
 impl crate::Decoder for Envelope {
-    fn decode(data: &[u8]) -> Self {
+    fn try_decode(data: &[u8]) -> IonResult<Self> {
 
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let envelope_type = binding.text();
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_blob().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_blob()?;
         let data = binding.as_slice();
 
-        Envelope::new(
+        Ok(Envelope::new(
             envelope_type,
             data,
-        )
+        ))
     }
 }
 
@@ -0,0 +1,108 @@
+use ion_rs;
+use ion_rs::IonWriter;
+use ion_rs::element::reader::ElementReader;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
+use crate::Expected;
+use core::foreign_key::{ForeignKey, OnDelete};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForeignKeyDTO {
+    pub value: ForeignKey,
+}
+
+impl ForeignKeyDTO {
+    pub fn new(value: ForeignKey) -> Self {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("table");
+        writer.write_string(self.value.get_table()).unwrap();
+
+        writer.set_field_name("columns");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column in self.value.get_columns().iter() {
+            writer.write_string(column).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("referenced_table");
+        writer.write_string(self.value.get_referenced_table()).unwrap();
+
+        writer.set_field_name("referenced_columns");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column in self.value.get_referenced_columns().iter() {
+            writer.write_string(column).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("on_delete");
+        writer.write_string(self.value.get_on_delete().to_string()).unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let table = binary_user_reader.read_string()?.text().to_string();
+
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let columns = binary_user_reader.read_all_elements()?
+            .iter()
+            .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+            .collect::<IonResult<Vec<String>>>()?;
+        binary_user_reader.step_out()?;
+
+        binary_user_reader.next()?;
+        let referenced_table = binary_user_reader.read_string()?.text().to_string();
+
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let referenced_columns = binary_user_reader.read_all_elements()?
+            .iter()
+            .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+            .collect::<IonResult<Vec<String>>>()?;
+        binary_user_reader.step_out()?;
+
+        binary_user_reader.next()?;
+        let on_delete = OnDelete::parse(binary_user_reader.read_string()?.text()).map_err(decoding_error_raw)?;
+
+        Ok(Self::new(ForeignKey::new(table, columns, referenced_table, referenced_columns, on_delete)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::foreign_key::{ForeignKey, OnDelete};
+    use super::ForeignKeyDTO;
+
+    #[test]
+    fn endec_foreign_key() {
+        let foreign_key = ForeignKeyDTO::new(ForeignKey::new(
+            "orders".to_string(),
+            vec!["user_id".to_string(), "user_name".to_string()],
+            "users".to_string(),
+            vec!["id".to_string(), "name".to_string()],
+            OnDelete::SetNull,
+        ));
+        assert_eq!(foreign_key, ForeignKeyDTO::decode(foreign_key.encode()));
+    }
+}
\ No newline at end of file
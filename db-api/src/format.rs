@@ -0,0 +1,290 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use ion_rs::IonWriter;
+use crate::db::DatabaseDTO;
+use crate::pager::{Pager, PAGE_SIZE};
+use crate::row_dto::RowDTO;
+use crate::table::TableDTO;
+
+// the versions of the database file:
+// 1 - a single ion struct with no header at all
+// 2 - pages, the first one starting with `PAGED_MAGIC` and no version
+// 3 - pages, the first one starting with `MAGIC` and the version
+// 4 - checksums of the catalog, the definitions of the tables and the rows
+// 5 - checksums of the headers of the pages and of the header of the file
+pub const FORMAT_VERSION: u16 = 5;
+pub const MAGIC: &[u8; 8] = b"CRATEDB\0";
+const PAGED_MAGIC: &[u8; 8] = b"CRATEPAG";
+// what every binary ion stream starts with
+const ION_VERSION_MARKER: &[u8; 4] = &[0xE0, 0x01, 0x00, 0xEA];
+
+pub fn get_version(header: &[u8]) -> Result<u16, String> {
+    if header.starts_with(MAGIC) && header.len() >= MAGIC.len() + 2 {
+        Ok(u16::from_be_bytes([header[MAGIC.len()], header[MAGIC.len() + 1]]))
+    } else if header.starts_with(PAGED_MAGIC) {
+        Ok(2)
+    } else if header.starts_with(ION_VERSION_MARKER) {
+        Ok(1)
+    } else {
+        Err("The file is not a database".to_string())
+    }
+}
+
+pub fn read_version(path: &str) -> Result<u16, String> {
+    let mut header = Vec::with_capacity(MAGIC.len() + 2);
+    fs::File::open(path)
+        .and_then(|file| file.take(MAGIC.len() as u64 + 2).read_to_end(&mut header))
+        .map_err(|err| format!("couldn't read the file {}: {}", path, err))?;
+    get_version(&header)
+}
+
+// upgrades a file from version `from` to version `to`
+pub struct Migration {
+    pub from: u16,
+    pub to: u16,
+    pub description: &'static str,
+    apply: fn(&str) -> Result<(), String>,
+}
+
+// every migration upgrades the file a version at a time
+pub const MIGRATIONS: [Migration; 4] = [
+    Migration {
+        from: 1,
+        to: 2,
+        description: "split the ion struct into pages",
+        apply: page_ion_file,
+    },
+    Migration {
+        from: 2,
+        to: 3,
+        description: "add the format version to the header",
+        apply: add_version_to_header,
+    },
+    Migration {
+        from: 3,
+        to: 4,
+        description: "add checksums",
+        apply: add_checksums,
+    },
+    Migration {
+        from: 4,
+        to: 5,
+        description: "add checksums to the page headers",
+        apply: add_page_checksums,
+    },
+];
+
+// brings the file to `FORMAT_VERSION` and returns the descriptions of the migrations applied
+pub fn migrate(path: &str) -> Result<Vec<String>, String> {
+    let mut applied = Vec::default();
+    let mut version = read_version(path)?;
+    while version < FORMAT_VERSION {
+        let migration = match MIGRATIONS.iter().find(|migration| migration.from == version) {
+            Some(migration) => migration,
+            None => return Err(format!("There is no migration from version {} of the file", version)),
+        };
+        (migration.apply)(path).map_err(|err| format!("couldn't upgrade {} from version {}: {}", path, version, err))?;
+        applied.push(format!("{} -> {}: {}", migration.from, migration.to, migration.description));
+        version = read_version(path)?;
+        if version != migration.to {
+            return Err(format!("{} has version {} after the migration to {}", path, version, migration.to));
+        }
+    }
+    if version > FORMAT_VERSION {
+        return Err(format!("{} has version {}, newer than {} this build reads", path, version, FORMAT_VERSION));
+    }
+    Ok(applied)
+}
+
+// written next to the file and moved over it, so a failure leaves the old one as it was. The pages
+// are the ones of version 2: the tables listed in the catalog by the chains of their definitions and
+// of their rows, every row prefixed with its length, and a header with no version
+fn page_ion_file(path: &str) -> Result<(), String> {
+    let data = fs::read(path).map_err(|err| err.to_string())?;
+    let database = DatabaseDTO::try_decode(data).map_err(|err| format!("the file can't be read: {}", err))?;
+    let migrated_path = format!("{}.migrating", path);
+    // the pages of version 2 are the ones of version 3, only the header is different
+    let mut pager = Pager::create_version(&migrated_path, 2)?;
+    let mut directory = Vec::with_capacity(database.tables.len());
+    for table in database.tables.iter() {
+        let definition = TableDTO { rows: Vec::default(), ..table.clone() }.encode();
+        let mut rows = Vec::default();
+        for row in table.rows.iter() {
+            let record = row.encode();
+            rows.extend_from_slice(&(record.len() as u32).to_be_bytes());
+            rows.extend_from_slice(&record);
+        }
+        directory.push((table.name.clone(), pager.write_chain(None, &definition)?, pager.write_chain(None, &rows)?));
+    }
+    let catalog = database.encode_with_tables(|writer| {
+        for (name, definition_page, rows_page) in directory.iter() {
+            writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+            writer.set_field_name("name");
+            writer.write_string(name).unwrap();
+            writer.set_field_name("definition_page");
+            writer.write_i64(*definition_page as i64).unwrap();
+            writer.set_field_name("rows_page");
+            writer.write_i64(*rows_page as i64).unwrap();
+            writer.step_out().unwrap();
+        }
+    });
+    let root_page = pager.write_chain(None, &catalog)?;
+    pager.flush()?;
+    let page_count = pager.get_page_count();
+    drop(pager);
+    // magic, page count, first free page and the root page, a new file has no free pages
+    let mut header = PAGED_MAGIC.to_vec();
+    header.extend_from_slice(&page_count.to_be_bytes());
+    header.extend_from_slice(&0u32.to_be_bytes());
+    header.extend_from_slice(&root_page.to_be_bytes());
+    header.resize(PAGE_SIZE, 0);
+    write_header(&migrated_path, &header)?;
+    fs::rename(&migrated_path, path).map_err(|err| err.to_string())
+}
+
+// the pages stay where they are, the counts in the header move to make room for the version. The
+// header is changed in a copy moved over the file, so a failure can't leave it half written
+fn add_version_to_header(path: &str) -> Result<(), String> {
+    let mut old = [0; 20];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut old))
+        .map_err(|err| err.to_string())?;
+    let mut header = MAGIC.to_vec();
+    header.extend_from_slice(&3u16.to_be_bytes());
+    header.resize(12, 0);
+    header.extend_from_slice(&old[8..20]);
+    header.resize(PAGE_SIZE, 0);
+    let migrated_path = format!("{}.migrating", path);
+    fs::copy(path, &migrated_path).map_err(|err| err.to_string())?;
+    write_header(&migrated_path, &header)?;
+    fs::rename(&migrated_path, path).map_err(|err| err.to_string())
+}
+
+fn write_header(path: &str, header: &[u8]) -> Result<(), String> {
+    let mut file = OpenOptions::new().write(true).open(path).map_err(|err| err.to_string())?;
+    file.seek(SeekFrom::Start(0))
+        .and_then(|_| file.write_all(header))
+        .and_then(|_| file.sync_all())
+        .map_err(|err| err.to_string())
+}
+
+// the records of version 3 had no checksums, so the tables are written again
+fn add_checksums(path: &str) -> Result<(), String> {
+    let mut pager = Pager::open_version(path, 3)?;
+    let catalog = pager.read_chain(pager.get_root_page())?;
+    let (mut database, directory) = DatabaseDTO::parse_catalog(catalog)?;
+    for pages in directory.iter() {
+        let mut table = TableDTO::try_decode(pager.read_chain(pages.definition_page)?)
+            .map_err(|err| format!("table {} can't be read: {}", pages.name, err))?;
+        table.rows = unpack_unchecked_records(&pager.read_chain(pages.rows_page)?)?
+            .into_iter()
+            .map(RowDTO::try_decode)
+            .collect::<ion_rs::IonResult<Vec<RowDTO>>>()
+            .map_err(|err| format!("a row of table {} can't be read: {}", pages.name, err))?;
+        database.tables.push(table);
+    }
+    drop(pager);
+    write_pages_version(path, &database, 4)
+}
+
+// the pages of version 4 have no room for the checksums of their headers, so the tables are written again
+fn add_page_checksums(path: &str) -> Result<(), String> {
+    let database = DatabaseDTO::read_pages(&mut Pager::open_version(path, 4)?)?;
+    write_pages_version(path, &database, 5)
+}
+
+fn write_pages_version(path: &str, database: &DatabaseDTO, version: u16) -> Result<(), String> {
+    let migrated_path = format!("{}.migrating", path);
+    let mut pager = Pager::create_version(&migrated_path, version)?;
+    database.write_pages(&mut pager)?;
+    drop(pager);
+    fs::rename(&migrated_path, path).map_err(|err| err.to_string())
+}
+
+fn unpack_unchecked_records(data: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut records = Vec::default();
+    let mut position = 0;
+    while position < data.len() {
+        if position + 4 > data.len() {
+            return Err("A record is cut short".to_string());
+        }
+        let length = u32::from_be_bytes([data[position], data[position + 1], data[position + 2], data[position + 3]]) as usize;
+        position += 4;
+        if length > data.len() - position {
+            return Err("A record is cut short".to_string());
+        }
+        records.push(data[position..position + length].to_vec());
+        position += length;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::DatabaseDTO;
+    use crate::pager::Pager;
+    use super::{migrate, read_version, FORMAT_VERSION};
+
+    // a copy of the fixture in the temp dir, the fixtures themselves stay as they are
+    fn copy_fixture(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("test_{}", name)).to_str().unwrap().to_string();
+        std::fs::copy(format!("{}/test_resources/{}", env!("CARGO_MANIFEST_DIR"), name), &path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_fixtures_of_every_version_are_migrated() {
+        let mut migrated = Vec::default();
+        for (version, name, applied) in [
+            (1, "format_v1.db", vec!["1 -> 2: split the ion struct into pages", "2 -> 3: add the format version to the header", "3 -> 4: add checksums", "4 -> 5: add checksums to the page headers"]),
+            (2, "format_v2.db", vec!["2 -> 3: add the format version to the header", "3 -> 4: add checksums", "4 -> 5: add checksums to the page headers"]),
+            (3, "format_v3.db", vec!["3 -> 4: add checksums", "4 -> 5: add checksums to the page headers"]),
+            (4, "format_v4.db", vec!["4 -> 5: add checksums to the page headers"]),
+            (5, "format_v5.db", vec![]),
+        ] {
+            let path = copy_fixture(name);
+            assert_eq!(read_version(&path).unwrap(), version);
+            assert_eq!(migrate(&path).unwrap(), applied);
+            assert_eq!(read_version(&path).unwrap(), FORMAT_VERSION);
+
+            let mut database = DatabaseDTO::read_pages(&mut Pager::open(&path).unwrap()).unwrap();
+            database.tables.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+            migrated.push(database);
+            std::fs::remove_file(path).unwrap();
+        }
+        assert_eq!(migrated[0], migrated[1]);
+        assert_eq!(migrated[1], migrated[2]);
+        assert_eq!(migrated[2], migrated[3]);
+        assert_eq!(migrated[3], migrated[4]);
+
+        let database = &migrated[0];
+        assert_eq!(database.tables.iter().map(|table| (table.name.as_str(), table.rows.len())).collect::<Vec<(&str, usize)>>(), [("orders", 3), ("users", 2)]);
+        assert_eq!(database.tables[0].indexes.len(), 1);
+        assert_eq!(database.foreign_keys.len(), 1);
+        assert_eq!(database.views.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_and_newer_files_are_rejected() {
+        let path = std::env::temp_dir().join("test_unknown_format.db").to_str().unwrap().to_string();
+        std::fs::write(&path, b"hello").unwrap();
+        assert!(migrate(&path).is_err());
+        let mut header = super::MAGIC.to_vec();
+        header.extend_from_slice(&(FORMAT_VERSION + 1).to_be_bytes());
+        std::fs::write(&path, header).unwrap();
+        assert!(migrate(&path).unwrap_err().contains("newer"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_a_malformed_file_fails_to_migrate() {
+        let path = std::env::temp_dir().join("test_malformed_format.db").to_str().unwrap().to_string();
+        let mut data = super::ION_VERSION_MARKER.to_vec();
+        data.extend_from_slice(b"not a database");
+        std::fs::write(&path, &data).unwrap();
+        assert!(migrate(&path).unwrap_err().contains("from version 1"));
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        std::fs::remove_file(path).unwrap();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use ion_rs;
+use core::types::int_interval_value::IntIntervalValue;
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IntIntervalValueDTO {
+    pub value: IntIntervalValue,
+}
+
+impl IntIntervalValueDTO {
+    pub fn new(value: IntIntervalValue) -> IntIntervalValueDTO {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("lower");
+        writer.write_i64(self.value.get_lower()).unwrap();
+
+        writer.set_field_name("upper");
+        writer.write_i64(self.value.get_upper()).unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let lower = binary_user_reader.read_i64()?;
+
+        binary_user_reader.next()?;
+        let upper = binary_user_reader.read_i64()?;
+        Ok(IntIntervalValueDTO::new(IntIntervalValue::new(lower, upper)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::types::int_interval_value::IntIntervalValue;
+    use super::IntIntervalValueDTO;
+
+    #[test]
+    fn endec_int_interval_value() {
+        for (lower, upper) in [(1, 5), (-7, -7), (i64::MIN, i64::MAX)] {
+            let value = IntIntervalValue::new(lower, upper);
+            let decoded = IntIntervalValueDTO::decode(IntIntervalValueDTO::new(value.clone()).encode());
+            assert_eq!(decoded.value, value);
+        }
+    }
+}
\ No newline at end of file
@@ -2,6 +2,7 @@ use ion_rs;
 use core::types::int_value::IntValue;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct IntValueDTO {
@@ -30,12 +31,15 @@ impl IntValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-
-        binary_user_reader.next().unwrap();
-        let value = binary_user_reader.read_i64().unwrap();
-        IntValueDTO::new(IntValue::new(value))
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let value = binary_user_reader.read_i64()?;
+        Ok(IntValueDTO::new(IntValue::new(value)))
     }
 }
\ No newline at end of file
@@ -1,13 +1,31 @@
-pub trait Decoder {
-    fn decode(data: &[u8]) -> Self;
+pub trait Decoder: Sized {
+    fn try_decode(data: &[u8]) -> ion_rs::IonResult<Self>;
+    fn decode(data: &[u8]) -> Self {
+        Self::try_decode(data).unwrap()
+    }
 }
 pub trait Encoder {
     fn encode(&self) -> Vec<u8>;
 }
+// an ion value a decoder reads that isn't there or isn't of the type it has in the format
+pub(crate) trait Expected<T> {
+    fn expected(self, what: &str) -> ion_rs::IonResult<T>;
+}
+impl<T> Expected<T> for Option<T> {
+    fn expected(self, what: &str) -> ion_rs::IonResult<T> {
+        self.ok_or_else(|| ion_rs::result::decoding_error_raw(format!("expected {}", what)))
+    }
+}
 pub mod db;
 pub mod row_dto;
 pub mod table;
 pub mod envelope;
+pub mod foreign_key_dto;
+pub mod view_dto;
+pub mod materialized_view_dto;
+pub mod pager;
+pub mod format;
+pub mod check;
 
 pub mod int_value_dto;
 pub mod real_value_dto;
@@ -16,4 +34,11 @@ pub mod string_value_dto;
 pub mod picture_value_dto;
 pub mod date_value_dto;
 pub mod email_value_dto;
+pub mod bool_value_dto;
+pub mod decimal_value_dto;
+pub mod int_interval_value_dto;
+pub mod real_interval_value_dto;
+pub mod date_interval_value_dto;
+pub mod enum_value_dto;
+pub mod null_value_dto;
 pub mod scheme_dto;
\ No newline at end of file
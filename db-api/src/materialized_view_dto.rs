@@ -0,0 +1,205 @@
+use ion_rs;
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+use crate::Expected;
+use ion_rs::StreamItem;
+use ion_rs::element::reader::ElementReader;
+use ion_rs::types::IntAccess;
+use core::table::Table;
+use core::view::{MaterializedView, RefreshMode};
+use crate::table::TableDTO;
+use crate::view_dto::ViewDTO;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MaterializedViewDTO {
+    pub view: ViewDTO,
+    pub refresh: RefreshMode,
+    pub stale: bool,
+    pub table: TableDTO,
+    pub source_versions: Vec<(String, u64)>,
+    pub positions: Option<Vec<usize>>,
+}
+
+impl TryFrom<MaterializedViewDTO> for MaterializedView {
+    type Error = String;
+
+    fn try_from(value: MaterializedViewDTO) -> Result<Self, Self::Error> {
+        let mut materialized_view = MaterializedView::new(value.view.value, value.refresh, Table::try_from(value.table)?);
+        materialized_view.stale = value.stale;
+        materialized_view.source_versions = value.source_versions;
+        materialized_view.positions = value.positions;
+        Ok(materialized_view)
+    }
+}
+
+impl From<MaterializedView> for MaterializedViewDTO {
+    fn from(value: MaterializedView) -> Self {
+        Self {
+            view: ViewDTO::new(value.view),
+            refresh: value.refresh,
+            stale: value.stale,
+            table: TableDTO::from(value.table),
+            source_versions: value.source_versions,
+            positions: value.positions,
+        }
+    }
+}
+
+impl MaterializedViewDTO {
+    pub fn new(view: ViewDTO, refresh: RefreshMode, table: TableDTO) -> Self {
+        Self {
+            view,
+            refresh,
+            stale: false,
+            table,
+            source_versions: Vec::default(),
+            positions: None,
+        }
+    }
+    pub fn with_stale(mut self, stale: bool) -> Self {
+        self.stale = stale;
+        self
+    }
+    pub fn with_source_versions(mut self, source_versions: Vec<(String, u64)>) -> Self {
+        self.source_versions = source_versions;
+        self
+    }
+    pub fn with_positions(mut self, positions: Option<Vec<usize>>) -> Self {
+        self.positions = positions;
+        self
+    }
+    // the definition and the rows are stored as the blobs of a view and a table
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("view");
+        writer.write_blob(self.view.encode()).unwrap();
+
+        writer.set_field_name("refresh");
+        writer.write_string(self.refresh.to_string()).unwrap();
+
+        writer.set_field_name("stale");
+        writer.write_bool(self.stale).unwrap();
+
+        writer.set_field_name("table");
+        writer.write_blob(self.table.encode()).unwrap();
+
+        writer.set_field_name("source_versions");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for (table, version) in self.source_versions.iter() {
+            writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+            writer.set_field_name("table");
+            writer.write_string(table).unwrap();
+            writer.set_field_name("version");
+            writer.write_i64(*version as i64).unwrap();
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("positions");
+        match &self.positions {
+            Some(positions) => {
+                writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+                for position in positions.iter() {
+                    writer.write_i64(*position as i64).unwrap();
+                }
+                writer.step_out().unwrap();
+            },
+            None => writer.write_null(ion_rs::IonType::List).unwrap(),
+        }
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let view = ViewDTO::try_decode(binary_user_reader.read_blob()?.as_slice().to_vec())?;
+
+        binary_user_reader.next()?;
+        let refresh = RefreshMode::parse(binary_user_reader.read_string()?.text()).unwrap_or_default();
+
+        binary_user_reader.next()?;
+        let stale = binary_user_reader.read_bool()?;
+
+        binary_user_reader.next()?;
+        let table = TableDTO::try_decode(binary_user_reader.read_blob()?.as_slice().to_vec())?;
+
+        // views saved before the versions of their sources were kept end with the table
+        let mut source_versions = Vec::<(String, u64)>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let source = element.as_struct().expected("a struct")?;
+                let table = source.get("table").expected("the field table")?.as_string().expected("a string")?.to_string();
+                let version = source.get("version").expected("the field version")?.as_i64().expected("an int")? as u64;
+                source_versions.push((table, version));
+            }
+            binary_user_reader.step_out()?;
+        }
+
+        let mut positions = None;
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            if !binary_user_reader.is_null() {
+                binary_user_reader.step_in()?;
+                positions = Some(binary_user_reader.read_all_elements()?
+                    .into_iter()
+                    .map(|position| Ok(position.as_i64().expected("an int")? as usize))
+                    .collect::<IonResult<Vec<usize>>>()?);
+                binary_user_reader.step_out()?;
+            }
+        }
+
+        Ok(Self::new(view, refresh, table)
+            .with_stale(stale)
+            .with_source_versions(source_versions)
+            .with_positions(positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::view::{RefreshMode, View, ViewSource};
+    use crate::scheme_dto::SchemeDTO;
+    use crate::table::TableDTO;
+    use crate::view_dto::ViewDTO;
+    use super::MaterializedViewDTO;
+
+    #[test]
+    fn endec_materialized_view() {
+        let view = ViewDTO::new(
+            View::new("adults".to_string(), ViewSource::Table("people".to_string()))
+                .with_columns(vec!["name".to_string(), "age".to_string()])
+                .with_filter(Some("age >= 18"))
+                .unwrap()
+        );
+        let table = TableDTO::new(
+            "adults".to_string(),
+            SchemeDTO::new(
+                vec!["StringValue".to_string(), "IntValue".to_string()],
+                vec!["name".to_string(), "age".to_string()],
+            ),
+            Vec::default(),
+        );
+        let materialized_view = MaterializedViewDTO::new(view, RefreshMode::Incremental, table.with_version(7)).with_stale(true);
+        assert_eq!(materialized_view, MaterializedViewDTO::decode(materialized_view.encode()));
+        let materialized_view = materialized_view
+            .with_source_versions(vec![("people".to_string(), 12)])
+            .with_positions(Some(vec![0, 2, 5]));
+        assert_eq!(materialized_view, MaterializedViewDTO::decode(materialized_view.encode()));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,752 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use crate::format::{get_version, FORMAT_VERSION, MAGIC};
+
+pub type PageId = u32;
+
+pub const PAGE_SIZE: usize = 4096;
+// a page starts with the next page of its chain (0 ends it), the count of used bytes and the checksum
+// of both, the files before version 5 have no checksum
+pub const PAGE_HEADER_SIZE: usize = 10;
+const UNCHECKED_PAGE_HEADER_SIZE: usize = 6;
+// where the checksum of the fields of the header page is, see `Pager::write_header`
+const HEADER_CHECKSUM_OFFSET: usize = 28;
+pub const PAGE_DATA_SIZE: usize = PAGE_SIZE - PAGE_HEADER_SIZE;
+pub const DEFAULT_POOL_CAPACITY: usize = 256;
+
+#[derive(Debug)]
+struct PooledPage {
+    data: Vec<u8>,
+    dirty: bool,
+}
+
+// keeps at most `capacity` pages in memory, dirty ones are written back when evicted
+#[derive(Debug)]
+pub struct BufferPool {
+    capacity: usize,
+    pages: HashMap<PageId, PooledPage>,
+    // least recently used first
+    recent: VecDeque<PageId>,
+}
+
+impl BufferPool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            pages: HashMap::default(),
+            recent: VecDeque::default(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+    fn touch(&mut self, id: PageId) {
+        self.recent.retain(|recent| *recent != id);
+        self.recent.push_back(id);
+    }
+    fn get(&mut self, id: PageId) -> Option<&PooledPage> {
+        if !self.pages.contains_key(&id) {
+            return None;
+        }
+        self.touch(id);
+        self.pages.get(&id)
+    }
+    // returns the page it evicted to make room, if that one was dirty
+    fn put(&mut self, id: PageId, data: Vec<u8>, dirty: bool) -> Option<(PageId, Vec<u8>)> {
+        self.touch(id);
+        let dirty = dirty || self.pages.get(&id).is_some_and(|page| page.dirty);
+        self.pages.insert(id, PooledPage { data, dirty });
+        if self.pages.len() <= self.capacity {
+            return None;
+        }
+        let evicted = self.recent.pop_front().unwrap();
+        let page = self.pages.remove(&evicted).unwrap();
+        if page.dirty {
+            Some((evicted, page.data))
+        } else {
+            None
+        }
+    }
+    fn take_dirty(&mut self) -> Vec<(PageId, Vec<u8>)> {
+        let mut dirty = self.pages.iter_mut()
+            .filter(|(_, page)| page.dirty)
+            .map(|(id, page)| {
+                page.dirty = false;
+                (*id, page.data.clone())
+            })
+            .collect::<Vec<(PageId, Vec<u8>)>>();
+        dirty.sort_by_key(|(id, _)| *id);
+        dirty
+    }
+}
+
+// a file of fixed size pages read through a buffer pool,
+// the pages that were changed are written on `flush` and nothing else is.
+// Before a page of the file is written over, what it held is appended to the journal next to the file,
+// see `journal_path`; `flush` removes the journal once the file is whole again, and opening a file
+// with a journal left by a save that didn't finish puts the pages back as they were before it
+#[derive(Debug)]
+pub struct Pager {
+    file: File,
+    path: String,
+    // of the format the pages are read and written in
+    version: u16,
+    journal: Option<File>,
+    // the pages whose content before the save is in the journal
+    journaled: HashSet<PageId>,
+    // the pages the file had after the last flush, the later ones need no journal
+    committed_page_count: u32,
+    pool: BufferPool,
+    page_count: u32,
+    // the head of the chain of free pages
+    free_page: PageId,
+    // the chain the caller keeps its own catalog in, 0 if there is none yet
+    root_page: PageId,
+    root_checksum: u32,
+    header_dirty: bool,
+    pages_read: usize,
+    pages_written: usize,
+}
+
+impl Pager {
+    // truncates whatever is in the file
+    pub fn create(path: &str) -> Result<Self, String> {
+        Self::create_version(path, FORMAT_VERSION)
+    }
+    // migrations write the files of the versions they upgrade to
+    pub(crate) fn create_version(path: &str, version: u16) -> Result<Self, String> {
+        // a journal of the file that was there would be played over the new one
+        remove_journal(path)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|err| format!("couldn't create the file {}: {}", path, err))?;
+        let mut pager = Self::new(file, path, version);
+        pager.header_dirty = true;
+        pager.flush()?;
+        Ok(pager)
+    }
+    pub fn open(path: &str) -> Result<Self, String> {
+        Self::open_version(path, FORMAT_VERSION)
+    }
+    // migrations open the files of the versions they upgrade, which have the same pages
+    pub(crate) fn open_version(path: &str, expected_version: u16) -> Result<Self, String> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|err| format!("couldn't open the file {}: {}", path, err))?;
+        roll_back(&mut file, path)?;
+        let mut pager = Self::new(file, path, expected_version);
+        // the first page of the file is its header, see `Pager::write_header`
+        let header = pager.read_from_file(0)?;
+        match get_version(&header) {
+            Ok(version) if version == expected_version => (),
+            Ok(version) => return Err(format!("{} has version {} of the format, it has to be migrated to {}", path, version, FORMAT_VERSION)),
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        }
+        if expected_version >= 5 && checksum(&header[..HEADER_CHECKSUM_OFFSET]) != read_u32(&header, HEADER_CHECKSUM_OFFSET) {
+            return Err(format!("The header of {} doesn't match its checksum", path));
+        }
+        pager.page_count = read_u32(&header, 12);
+        pager.free_page = read_u32(&header, 16);
+        pager.root_page = read_u32(&header, 20);
+        pager.root_checksum = read_u32(&header, 24);
+        pager.committed_page_count = pager.page_count;
+        Ok(pager)
+    }
+    fn new(file: File, path: &str, version: u16) -> Self {
+        Self {
+            file,
+            path: path.to_string(),
+            version,
+            journal: None,
+            journaled: HashSet::default(),
+            committed_page_count: 0,
+            pool: BufferPool::new(DEFAULT_POOL_CAPACITY),
+            page_count: 1,
+            free_page: 0,
+            root_page: 0,
+            root_checksum: 0,
+            header_dirty: false,
+            pages_read: 0,
+            pages_written: 0,
+        }
+    }
+    pub fn with_pool_capacity(mut self, capacity: usize) -> Self {
+        self.pool = BufferPool::new(capacity);
+        self
+    }
+
+    pub fn get_page_count(&self) -> u32 {
+        self.page_count
+    }
+    pub fn get_root_page(&self) -> PageId {
+        self.root_page
+    }
+    pub fn set_root_page(&mut self, page: PageId) {
+        if self.root_page != page {
+            self.root_page = page;
+            self.header_dirty = true;
+        }
+    }
+    pub fn get_root_checksum(&self) -> u32 {
+        self.root_checksum
+    }
+    pub fn set_root_checksum(&mut self, root_checksum: u32) {
+        if self.root_checksum != root_checksum {
+            self.root_checksum = root_checksum;
+            self.header_dirty = true;
+        }
+    }
+    // (read, written) pages since the pager was opened
+    pub fn get_io_counts(&self) -> (usize, usize) {
+        (self.pages_read, self.pages_written)
+    }
+    pub fn get_pool(&self) -> &BufferPool {
+        &self.pool
+    }
+
+    pub fn read_page(&mut self, id: PageId) -> Result<Vec<u8>, String> {
+        if id == 0 || id >= self.page_count {
+            return Err(format!("There is no page {} in the file", id));
+        }
+        if let Some(page) = self.pool.get(id) {
+            return Ok(page.data.clone());
+        }
+        let data = self.read_from_file(id)?;
+        if let Some((evicted, data)) = self.pool.put(id, data.clone(), false) {
+            self.write_to_file(evicted, &data)?;
+        }
+        Ok(data)
+    }
+    // the page becomes dirty only if its content changes
+    pub fn write_page(&mut self, id: PageId, mut data: Vec<u8>) -> Result<(), String> {
+        if data.len() > PAGE_SIZE {
+            return Err(format!("{} bytes don't fit in a page", data.len()));
+        }
+        data.resize(PAGE_SIZE, 0);
+        if self.read_page(id)? == data {
+            return Ok(());
+        }
+        if let Some((evicted, data)) = self.pool.put(id, data, true) {
+            self.write_to_file(evicted, &data)?;
+        }
+        Ok(())
+    }
+    pub fn allocate_page(&mut self) -> Result<PageId, String> {
+        self.header_dirty = true;
+        if self.free_page != 0 {
+            let id = self.free_page;
+            let page = self.read_page(id)?;
+            self.free_page = self.read_page_header(id, &page)?.0;
+            return Ok(id);
+        }
+        let id = self.page_count;
+        self.page_count += 1;
+        // a new page is all zeroes, whether or not it ever reached the file
+        if let Some((evicted, data)) = self.pool.put(id, vec![0; PAGE_SIZE], true) {
+            self.write_to_file(evicted, &data)?;
+        }
+        Ok(id)
+    }
+    pub fn free_chain(&mut self, first: PageId) -> Result<(), String> {
+        for id in self.get_chain(first)? {
+            let page = self.make_page_header(self.free_page, 0);
+            self.write_page(id, page)?;
+            self.free_page = id;
+            self.header_dirty = true;
+        }
+        Ok(())
+    }
+
+    pub fn get_chain(&mut self, first: PageId) -> Result<Vec<PageId>, String> {
+        let mut chain = Vec::default();
+        let mut id = first;
+        while id != 0 {
+            if chain.contains(&id) {
+                return Err(format!("The chain of pages from {} loops at {}", first, id));
+            }
+            chain.push(id);
+            let page = self.read_page(id)?;
+            id = self.read_page_header(id, &page)?.0;
+        }
+        Ok(chain)
+    }
+    // the next page and the count of used bytes
+    fn read_page_header(&self, id: PageId, page: &[u8]) -> Result<(PageId, usize), String> {
+        if self.version >= 5 && checksum(&page[..UNCHECKED_PAGE_HEADER_SIZE]) != read_u32(page, UNCHECKED_PAGE_HEADER_SIZE) {
+            return Err(format!("The header of page {} doesn't match its checksum", id));
+        }
+        let used = u16::from_be_bytes([page[4], page[5]]) as usize;
+        if used > PAGE_SIZE - self.get_page_header_size() {
+            return Err(format!("Page {} claims {} used bytes", id, used));
+        }
+        Ok((read_u32(page, 0), used))
+    }
+    fn make_page_header(&self, next: PageId, used: usize) -> Vec<u8> {
+        let mut header = Vec::with_capacity(PAGE_SIZE);
+        header.extend_from_slice(&next.to_be_bytes());
+        header.extend_from_slice(&(used as u16).to_be_bytes());
+        if self.version >= 5 {
+            header.extend_from_slice(&checksum(&header).to_be_bytes());
+        }
+        header
+    }
+    fn get_page_header_size(&self) -> usize {
+        match self.version {
+            version if version >= 5 => PAGE_HEADER_SIZE,
+            _ => UNCHECKED_PAGE_HEADER_SIZE,
+        }
+    }
+    // the used bytes of every page of the chain
+    pub fn read_chain_pages(&mut self, first: PageId) -> Result<Vec<Vec<u8>>, String> {
+        let mut pages = Vec::default();
+        let header_size = self.get_page_header_size();
+        for id in self.get_chain(first)? {
+            let page = self.read_page(id)?;
+            let (_, used) = self.read_page_header(id, &page)?;
+            pages.push(page[header_size..header_size + used].to_vec());
+        }
+        Ok(pages)
+    }
+    pub fn read_chain(&mut self, first: PageId) -> Result<Vec<u8>, String> {
+        Ok(self.read_chain_pages(first)?.concat())
+    }
+    // writes the pages over the chain starting at `first`, allocating and freeing pages as needed,
+    // and returns the first page of the chain
+    pub fn write_chain_pages(&mut self, first: Option<PageId>, pages: Vec<Vec<u8>>) -> Result<PageId, String> {
+        let mut chain = match first {
+            Some(first) if first != 0 => self.get_chain(first)?,
+            _ => Vec::default(),
+        };
+        // even an empty chain takes a page so it has somewhere to start
+        let needed = pages.len().max(1);
+        if chain.len() > needed {
+            let tail = chain.split_off(needed);
+            self.free_chain(tail[0])?;
+        }
+        while chain.len() < needed {
+            chain.push(self.allocate_page()?);
+        }
+        for (position, id) in chain.iter().enumerate() {
+            let data = pages.get(position).cloned().unwrap_or_default();
+            if data.len() > PAGE_DATA_SIZE {
+                return Err(format!("{} bytes don't fit in a page", data.len()));
+            }
+            let next = chain.get(position + 1).copied().unwrap_or(0);
+            let mut page = self.make_page_header(next, data.len());
+            page.extend_from_slice(&data);
+            self.write_page(*id, page)?;
+        }
+        Ok(chain[0])
+    }
+    pub fn write_chain(&mut self, first: Option<PageId>, data: &[u8]) -> Result<PageId, String> {
+        let pages = data.chunks(PAGE_DATA_SIZE).map(|chunk| chunk.to_vec()).collect();
+        self.write_chain_pages(first, pages)
+    }
+
+    // the pages are journaled together, then written, and the journal goes once they are on the disk
+    pub fn flush(&mut self) -> Result<(), String> {
+        let dirty = self.pool.take_dirty();
+        let mut written = dirty.iter().map(|(id, _)| *id).collect::<Vec<PageId>>();
+        if self.header_dirty {
+            written.push(0);
+        }
+        self.journal_pages(&written)?;
+        for (id, data) in dirty {
+            self.write_to_file(id, &data)?;
+        }
+        if self.header_dirty {
+            self.write_header()?;
+            self.header_dirty = false;
+        }
+        self.file.sync_all().map_err(|err| format!("couldn't flush the file: {}", err))?;
+        self.committed_page_count = self.page_count;
+        self.journaled.clear();
+        if self.journal.take().is_some() {
+            remove_journal(&self.path)?;
+        }
+        Ok(())
+    }
+    // appends what the pages of the last flush held to the journal, the header first, and syncs it
+    fn journal_pages(&mut self, ids: &[PageId]) -> Result<(), String> {
+        let mut ids = ids.iter()
+            .copied()
+            .filter(|id| *id < self.committed_page_count && !self.journaled.contains(id))
+            .collect::<Vec<PageId>>();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        if self.journal.is_none() {
+            let journal = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(journal_path(&self.path))
+                .map_err(|err| format!("couldn't create the journal of {}: {}", self.path, err))?;
+            self.journal = Some(journal);
+            if !ids.contains(&0) {
+                ids.insert(0, 0);
+            }
+        }
+        ids.sort();
+        let mut entries = Vec::with_capacity(ids.len() * JOURNAL_ENTRY_SIZE);
+        for id in ids {
+            let data = read_file_page(&mut self.file, id)?;
+            entries.extend_from_slice(&id.to_be_bytes());
+            entries.extend_from_slice(&checksum(&data).to_be_bytes());
+            entries.extend_from_slice(&data);
+            self.journaled.insert(id);
+        }
+        let journal = self.journal.as_mut().unwrap();
+        journal.write_all(&entries)
+            .and_then(|_| journal.sync_data())
+            .map_err(|err| format!("couldn't write the journal of {}: {}", self.path, err))
+    }
+    // magic, version, page count, first free page, the root page and its checksum, then the checksum
+    // of all of them
+    fn write_header(&mut self) -> Result<(), String> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&self.version.to_be_bytes());
+        header.resize(12, 0);
+        header.extend_from_slice(&self.page_count.to_be_bytes());
+        header.extend_from_slice(&self.free_page.to_be_bytes());
+        header.extend_from_slice(&self.root_page.to_be_bytes());
+        header.extend_from_slice(&self.root_checksum.to_be_bytes());
+        if self.version >= 5 {
+            header.extend_from_slice(&checksum(&header).to_be_bytes());
+        }
+        header.resize(PAGE_SIZE, 0);
+        self.write_to_file(0, &header)
+    }
+
+    fn read_from_file(&mut self, id: PageId) -> Result<Vec<u8>, String> {
+        let data = read_file_page(&mut self.file, id)?;
+        self.pages_read += 1;
+        Ok(data)
+    }
+    // a page of the last flush is journaled before it's written over, see `flush`
+    fn write_to_file(&mut self, id: PageId, data: &[u8]) -> Result<(), String> {
+        self.journal_pages(&[id])?;
+        self.file.seek(SeekFrom::Start(id as u64 * PAGE_SIZE as u64))
+            .and_then(|_| self.file.write_all(data))
+            .map_err(|err| format!("couldn't write page {}: {}", id, err))?;
+        self.pages_written += 1;
+        Ok(())
+    }
+}
+
+fn read_file_page(file: &mut File, id: PageId) -> Result<Vec<u8>, String> {
+    let mut data = vec![0; PAGE_SIZE];
+    file.seek(SeekFrom::Start(id as u64 * PAGE_SIZE as u64))
+        .map_err(|err| format!("couldn't read page {}: {}", id, err))?;
+    // the last pages may never have been written, they read as zeroes
+    let mut read = 0;
+    while read < PAGE_SIZE {
+        match file.read(&mut data[read..]) {
+            Ok(0) => break,
+            Ok(count) => read += count,
+            Err(err) => return Err(format!("couldn't read page {}: {}", id, err)),
+        }
+    }
+    Ok(data)
+}
+
+// the page and its checksum before the content of the page
+const JOURNAL_ENTRY_SIZE: usize = 8 + PAGE_SIZE;
+
+pub fn journal_path(path: &str) -> String {
+    format!("{}.journal", path)
+}
+
+fn remove_journal(path: &str) -> Result<(), String> {
+    match std::fs::remove_file(journal_path(path)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(format!("couldn't remove the journal of {}: {}", path, err)),
+        _ => Ok(()),
+    }
+}
+
+// puts back the pages a save that didn't finish wrote over, and cuts the ones it added. An entry that
+// doesn't match its checksum was being journaled when the save stopped, so its page wasn't written yet;
+// a journal without the header wasn't started with anything written to the file
+fn roll_back(file: &mut File, path: &str) -> Result<(), String> {
+    let journal = match std::fs::read(journal_path(path)) {
+        Ok(journal) => journal,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(format!("couldn't read the journal of {}: {}", path, err)),
+    };
+    let entries = journal.chunks_exact(JOURNAL_ENTRY_SIZE)
+        .map(|entry| (read_u32(entry, 0), read_u32(entry, 4), &entry[8..]))
+        .take_while(|(_, expected, data)| checksum(data) == *expected)
+        .collect::<Vec<(PageId, u32, &[u8])>>();
+    if let Some((0, _, header)) = entries.first() {
+        for (id, _, data) in entries.iter() {
+            file.seek(SeekFrom::Start(*id as u64 * PAGE_SIZE as u64))
+                .and_then(|_| file.write_all(data))
+                .map_err(|err| format!("couldn't roll {} back: {}", path, err))?;
+        }
+        let length = read_u32(header, 12) as u64 * PAGE_SIZE as u64;
+        let current = file.metadata().map_err(|err| format!("couldn't roll {} back: {}", path, err))?.len();
+        if current > length {
+            file.set_len(length).map_err(|err| format!("couldn't roll {} back: {}", path, err))?;
+        }
+        file.sync_all().map_err(|err| format!("couldn't roll {} back: {}", path, err))?;
+        log::warn!("{} is rolled back to how it was before a save that didn't finish", path);
+    }
+    remove_journal(path)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+// CRC-32, the one zip and png use
+pub fn checksum(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+const CRC_TABLE: [u32; 256] = make_crc_table();
+
+const fn make_crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB88320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+}
+
+// packs records prefixed with their length and checksum into pages, a record that doesn't fit
+// in what is left of a page starts the next one, so changing a record rarely moves the ones in other pages
+pub fn pack_records(records: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut pages = Vec::<Vec<u8>>::default();
+    let mut current = Vec::<u8>::default();
+    for record in records {
+        let mut bytes = (record.len() as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&checksum(record).to_be_bytes());
+        bytes.extend_from_slice(record);
+        if !current.is_empty() && current.len() + bytes.len() > PAGE_DATA_SIZE && bytes.len() <= PAGE_DATA_SIZE {
+            pages.push(std::mem::take(&mut current));
+        }
+        // the ones bigger than a page go on through as many pages as they need
+        for byte in bytes {
+            if current.len() == PAGE_DATA_SIZE {
+                pages.push(std::mem::take(&mut current));
+            }
+            current.push(byte);
+        }
+    }
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+pub fn unpack_records(data: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    scan_records(data, &[])
+        .into_iter()
+        .enumerate()
+        .map(|(position, record)| record.map_err(|err| format!("record {} {}", position, err)))
+        .collect()
+}
+
+// every record of the pages of a chain that can be told apart, the ones whose checksum doesn't match
+// are errors. A length that points past the data loses the records up to the next page, where
+// `pack_records` starts a record unless the one before it goes on through
+pub fn check_records(pages: &[Vec<u8>]) -> Vec<Result<Vec<u8>, String>> {
+    let mut page_starts = Vec::with_capacity(pages.len());
+    let mut start = 0;
+    for page in pages.iter() {
+        page_starts.push(start);
+        start += page.len();
+    }
+    scan_records(&pages.concat(), &page_starts)
+}
+
+fn scan_records(data: &[u8], page_starts: &[usize]) -> Vec<Result<Vec<u8>, String>> {
+    let mut records = Vec::default();
+    let mut position = 0;
+    while position < data.len() {
+        let whole = position + 8 <= data.len() && read_u32(data, position) as usize <= data.len() - position - 8;
+        if !whole {
+            records.push(Err("is cut short".to_string()));
+            match page_starts.iter().find(|start| **start > position) {
+                Some(start) => {
+                    position = *start;
+                    continue;
+                },
+                None => break,
+            }
+        }
+        let length = read_u32(data, position) as usize;
+        let expected = read_u32(data, position + 4);
+        position += 8;
+        let record = &data[position..position + length];
+        if checksum(record) == expected {
+            records.push(Ok(record.to_vec()));
+        } else {
+            records.push(Err("doesn't match its checksum".to_string()));
+        }
+        position += length;
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum, check_records, journal_path, pack_records, unpack_records, Pager, PAGE_DATA_SIZE, PAGE_SIZE};
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir().join(name).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_chains_reuse_pages_and_write_only_dirty_ones() {
+        let path = temp_path("test_pager_chains");
+        let mut pager = Pager::create(&path).unwrap();
+        let records = (0..1000).map(|i| format!("record {}", i).into_bytes()).collect::<Vec<Vec<u8>>>();
+        let first = pager.write_chain_pages(None, pack_records(&records)).unwrap();
+        let short = pager.write_chain(None, b"catalog").unwrap();
+        pager.set_root_page(short);
+        pager.flush().unwrap();
+        let page_count = pager.get_page_count();
+        drop(pager);
+
+        let mut pager = Pager::open(&path).unwrap().with_pool_capacity(2);
+        assert_eq!(pager.read_chain(pager.get_root_page()).unwrap(), b"catalog");
+        assert_eq!(unpack_records(&pager.read_chain(first).unwrap()).unwrap(), records);
+        assert!(pager.get_pool().len() <= 2);
+
+        // changing the last record leaves the other pages as they were
+        let mut changed = records.clone();
+        changed[999] = b"the last one".to_vec();
+        let (_, written) = pager.get_io_counts();
+        assert_eq!(pager.write_chain_pages(Some(first), pack_records(&changed)).unwrap(), first);
+        pager.flush().unwrap();
+        assert_eq!(pager.get_io_counts().1 - written, 1);
+        assert_eq!(pager.get_page_count(), page_count);
+
+        // the pages a chain no longer needs are reused by the next one
+        pager.write_chain(Some(first), b"short").unwrap();
+        let reused = pager.write_chain(None, &vec![1; PAGE_DATA_SIZE * 2]).unwrap();
+        assert!(reused < page_count);
+        assert_eq!(pager.get_page_count(), page_count);
+        pager.flush().unwrap();
+        drop(pager);
+
+        let mut pager = Pager::open(&path).unwrap();
+        assert_eq!(pager.read_chain(first).unwrap(), b"short");
+        assert_eq!(pager.read_chain(reused).unwrap(), vec![1; PAGE_DATA_SIZE * 2]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_a_save_that_stops_is_rolled_back() {
+        let path = temp_path("test_pager_journal");
+        let mut pager = Pager::create(&path).unwrap();
+        let records = (0..1000).map(|i| format!("record {}", i).into_bytes()).collect::<Vec<Vec<u8>>>();
+        let first = pager.write_chain_pages(None, pack_records(&records)).unwrap();
+        pager.set_root_page(first);
+        pager.flush().unwrap();
+        assert!(!std::path::Path::new(&journal_path(&path)).exists());
+        let length = std::fs::metadata(&path).unwrap().len();
+        drop(pager);
+
+        // the pages the pool has no room for are written before the save ends
+        let mut pager = Pager::open(&path).unwrap().with_pool_capacity(2);
+        let changed = (0..2000).map(|i| format!("changed {}", i).into_bytes()).collect::<Vec<Vec<u8>>>();
+        pager.write_chain_pages(Some(first), pack_records(&changed)).unwrap();
+        assert!(pager.get_io_counts().1 > 0);
+        assert!(std::path::Path::new(&journal_path(&path)).exists());
+        drop(pager);
+
+        let mut pager = Pager::open(&path).unwrap();
+        assert!(!std::path::Path::new(&journal_path(&path)).exists());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), length);
+        assert_eq!(unpack_records(&pager.read_chain(pager.get_root_page()).unwrap()).unwrap(), records);
+
+        // a journal cut short before the header is whole is of a save that wrote nothing yet
+        std::fs::write(journal_path(&path), [0; 100]).unwrap();
+        drop(pager);
+        let mut pager = Pager::open(&path).unwrap();
+        assert_eq!(unpack_records(&pager.read_chain(first).unwrap()).unwrap(), records);
+        assert!(!std::path::Path::new(&journal_path(&path)).exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_records_bigger_than_a_page() {
+        let records = vec![b"small".to_vec(), vec![7; PAGE_DATA_SIZE * 3], b"small again".to_vec()];
+        let pages = pack_records(&records);
+        assert_eq!(pages.len(), 4);
+        assert_eq!(unpack_records(&pages.concat()).unwrap(), records);
+        assert!(unpack_records(&pages.concat()[..100]).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_records_are_found() {
+        assert_eq!(checksum(b"123456789"), 0xCBF43926);
+        let records = vec![b"first".to_vec(), b"second".to_vec(), b"third".to_vec()];
+        let mut pages = pack_records(&records);
+        // a byte of "second"
+        pages[0][4 + 4 + 5 + 4 + 4 + 2] ^= 1;
+        let checked = check_records(&pages);
+        assert_eq!(checked.len(), 3);
+        assert_eq!(checked[0], Ok(b"first".to_vec()));
+        assert!(checked[1].is_err());
+        assert_eq!(checked[2], Ok(b"third".to_vec()));
+        assert_eq!(unpack_records(&pages.concat()).unwrap_err(), "record 1 doesn't match its checksum");
+
+        // a length pointing past the end loses the records up to the next page
+        let records = (0..3).map(|i| vec![i; PAGE_DATA_SIZE / 2]).collect::<Vec<Vec<u8>>>();
+        let mut pages = pack_records(&records);
+        assert_eq!(pages.len(), 3);
+        pages[1][0] = 0xFF;
+        assert_eq!(check_records(&pages), vec![Ok(records[0].clone()), Err("is cut short".to_string()), Ok(records[2].clone())]);
+        assert!(unpack_records(&pages.concat()).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_headers_are_found() {
+        let path = temp_path("test_pager_headers");
+        let mut pager = Pager::create(&path).unwrap();
+        let first = pager.write_chain(None, &vec![1; PAGE_DATA_SIZE * 3]).unwrap();
+        pager.set_root_page(first);
+        pager.flush().unwrap();
+        drop(pager);
+
+        // a flipped pointer of the first page would have cut the chain short
+        let mut data = std::fs::read(&path).unwrap();
+        data[first as usize * PAGE_SIZE + 3] ^= 1;
+        std::fs::write(&path, &data).unwrap();
+        let mut pager = Pager::open(&path).unwrap();
+        assert_eq!(pager.read_chain(first).unwrap_err(), format!("The header of page {} doesn't match its checksum", first));
+        drop(pager);
+
+        // and one of the root page in the header would have pointed the catalog elsewhere
+        data[first as usize * PAGE_SIZE + 3] ^= 1;
+        data[23] ^= 1;
+        std::fs::write(&path, &data).unwrap();
+        assert!(Pager::open(&path).unwrap_err().contains("The header of"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
\ No newline at end of file
@@ -2,6 +2,8 @@ use ion_rs;
 use core::types::picture_value::PictureValue;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct PictureValueDTO {
@@ -29,13 +31,16 @@ impl PictureValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-
-        binary_user_reader.next().unwrap();
-        let value = binary_user_reader.read_blob().unwrap().to_owned();
-        let dynamic_image = image::load_from_memory(value.as_slice()).unwrap();
-        PictureValueDTO::new(PictureValue::new(dynamic_image))
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let value = binary_user_reader.read_blob()?.to_owned();
+        let dynamic_image = image::load_from_memory(value.as_slice()).map_err(|err| decoding_error_raw(err.to_string()))?;
+        Ok(PictureValueDTO::new(PictureValue::new(dynamic_image)))
     }
 }
\ No newline at end of file
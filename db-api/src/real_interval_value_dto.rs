@@ -0,0 +1,65 @@
+use ion_rs;
+use core::types::real_interval_value::RealIntervalValue;
+use ion_rs::IonWriter;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RealIntervalValueDTO {
+    pub value: RealIntervalValue,
+}
+
+impl RealIntervalValueDTO {
+    pub fn new(value: RealIntervalValue) -> RealIntervalValueDTO {
+        Self { value }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("lower");
+        writer.write_f64(self.value.get_lower()).unwrap();
+
+        writer.set_field_name("upper");
+        writer.write_f64(self.value.get_upper()).unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let lower = binary_user_reader.read_f64()?;
+
+        binary_user_reader.next()?;
+        let upper = binary_user_reader.read_f64()?;
+        Ok(RealIntervalValueDTO::new(RealIntervalValue::new(lower, upper)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::types::real_interval_value::RealIntervalValue;
+    use super::RealIntervalValueDTO;
+
+    #[test]
+    fn endec_real_interval_value() {
+        for (lower, upper) in [(0.5, 2.5), (-1e-5, 1e300), (3.0, 3.0)] {
+            let value = RealIntervalValue::new(lower, upper);
+            let decoded = RealIntervalValueDTO::decode(RealIntervalValueDTO::new(value.clone()).encode());
+            assert_eq!(decoded.value, value);
+        }
+    }
+}
\ No newline at end of file
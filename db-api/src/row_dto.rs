@@ -3,6 +3,8 @@ use ion_rs;
 use ion_rs::IonWriter;
 use ion_rs::element::reader::ElementReader;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use crate::Expected;
 use core::row::Row;
 use core::types::CellValue;
 
@@ -12,9 +14,21 @@ use core::types::char_value::CharValue;
 use core::types::date_value::DateValue;
 use core::types::picture_value::PictureValue;
 use core::types::real_value::RealValue;
+use core::types::bool_value::BoolValue;
+use core::types::decimal_value::DecimalValue;
+use core::types::int_interval_value::IntIntervalValue;
+use core::types::real_interval_value::RealIntervalValue;
+use core::types::date_interval_value::DateIntervalValue;
+use core::types::enum_value::EnumValue;
+use core::types::null_value::NullValue;
 
+use crate::bool_value_dto::BoolValueDTO;
 use crate::char_value_dto::CharValueDTO;
 use crate::date_value_dto::DateValueDTO;
+use crate::decimal_value_dto::DecimalValueDTO;
+use crate::date_interval_value_dto::DateIntervalValueDTO;
+use crate::int_interval_value_dto::IntIntervalValueDTO;
+use crate::real_interval_value_dto::RealIntervalValueDTO;
 
 use crate::Encoder;
 use crate::Decoder;
@@ -24,6 +38,8 @@ use crate::int_value_dto::IntValueDTO;
 use crate::picture_value_dto::PictureValueDTO;
 use crate::real_value_dto::RealValueDTO;
 use crate::string_value_dto::StringValueDTO;
+use crate::enum_value_dto::EnumValueDTO;
+use crate::null_value_dto::NullValueDTO;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct RowDTO {
@@ -64,33 +80,45 @@ impl From<Rc<Row<dyn CellValue>>> for RowDTO {
                     let ty = v.get_type();
                     Envelope::new(ty.as_str(), EmailValueDTO::new(v).encode().as_slice())
                 }
+                ValueType::Bool(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), BoolValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::Decimal(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), DecimalValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::IntInterval(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), IntIntervalValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::RealInterval(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), RealIntervalValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::DateInterval(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), DateIntervalValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::Enum(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), EnumValueDTO::new(v).encode().as_slice())
+                }
+                ValueType::Null(v) => {
+                    let ty = v.get_type();
+                    Envelope::new(ty.as_str(), NullValueDTO::new(v).encode().as_slice())
+                }
             };
             values.push(wrapper);
         });
         Self { values }
     }
 }
-impl From<RowDTO> for Row<dyn CellValue> {
-    fn from(value: RowDTO) -> Self {
-        let mut row_values = Vec::with_capacity(value.values.len());
-        value.values.iter().for_each(|wrapper| {
-            let ty = wrapper.get_type();
-            let value: Rc<dyn CellValue> = if ty == IntValue::get_type_name() {
-                Rc::new(IntValueDTO::decode(wrapper.get_data().to_vec()).value)
-            } else if ty == CharValue::get_type_name() {
-                Rc::new(CharValueDTO::decode(wrapper.get_data().to_vec()).value)
-            } else if ty == DateValue::get_type_name() {
-                Rc::new(DateValueDTO::decode(wrapper.get_data().to_vec()).value)
-            } else if ty == PictureValue::get_type_name() {
-                Rc::new(PictureValueDTO::decode(wrapper.get_data().to_vec()).value)
-            } else if ty == RealValue::get_type_name() {
-                Rc::new(RealValueDTO::decode(wrapper.get_data().to_vec()).value)
-            } else {
-                Rc::new(StringValueDTO::decode(wrapper.get_data().to_vec()).value)
-            };
-            row_values.push(value);
-        });
-        Row::<dyn CellValue>::new(row_values)
+impl TryFrom<RowDTO> for Row<dyn CellValue> {
+    type Error = ion_rs::IonError;
+
+    fn try_from(value: RowDTO) -> IonResult<Self> {
+        value.try_into_row()
     }
 }
 
@@ -121,22 +149,61 @@ impl RowDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    // the values are only read here, so a row can be decoded and still fail to become a row
+    pub fn try_into_row(&self) -> IonResult<Row<dyn CellValue>> {
+        let mut row_values = Vec::with_capacity(self.values.len());
+        for wrapper in self.values.iter() {
+            let ty = wrapper.get_type();
+            let value: Rc<dyn CellValue> = if ty == IntValue::get_type_name() {
+                Rc::new(IntValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == CharValue::get_type_name() {
+                Rc::new(CharValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == DateValue::get_type_name() {
+                Rc::new(DateValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == PictureValue::get_type_name() {
+                Rc::new(PictureValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == RealValue::get_type_name() {
+                Rc::new(RealValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == BoolValue::get_type_name() {
+                Rc::new(BoolValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == DecimalValue::get_type_name() {
+                Rc::new(DecimalValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == IntIntervalValue::get_type_name() {
+                Rc::new(IntIntervalValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == RealIntervalValue::get_type_name() {
+                Rc::new(RealIntervalValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == DateIntervalValue::get_type_name() {
+                Rc::new(DateIntervalValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == EnumValue::get_type_name() {
+                Rc::new(EnumValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else if ty == NullValue::get_type_name() {
+                Rc::new(NullValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            } else {
+                Rc::new(StringValueDTO::try_decode(wrapper.get_data().to_vec())?.value)
+            };
+            row_values.push(value);
+        }
+        Ok(Row::<dyn CellValue>::new(row_values))
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-        let elements = binary_user_reader.read_all_elements().unwrap();
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let elements = binary_user_reader.read_all_elements()?;
         let mut values = Vec::<Envelope>::with_capacity(elements.capacity());
         for element in elements {
-            let data = element.as_blob().unwrap();
-            values.push(Envelope::decode(data));
+            let data = element.as_blob().expected("a blob")?;
+            values.push(Envelope::try_decode(data)?);
         }
-        binary_user_reader.step_out().unwrap();
+        binary_user_reader.step_out()?;
 
-        binary_user_reader.step_out().unwrap();
+        binary_user_reader.step_out()?;
 
-        Self { values }
+        Ok(Self { values })
     }
 }
\ No newline at end of file
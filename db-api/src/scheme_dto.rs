@@ -2,7 +2,11 @@ use ion_rs;
 use ion_rs::element::reader::ElementReader;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
-use core::scheme::Scheme;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
+use crate::Expected;
+use ion_rs::StreamItem;
+use core::scheme::{Constraint, Scheme};
 use core::types::CellValue;
 use core::types::SUPPORTED_TYPES;
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -10,15 +14,27 @@ use core::types::SUPPORTED_TYPES;
 pub struct SchemeDTO {
     types: Vec<String>,
     columns: Vec<String>,
+    parameters: Vec<Vec<String>>,
+    constraints: Vec<Vec<Constraint>>,
+    primary_key: Vec<String>,
+    unique: Vec<Vec<String>>,
 }
 
-impl From<SchemeDTO> for Scheme<dyn CellValue> {
-    fn from(value: SchemeDTO) -> Self {
+impl TryFrom<SchemeDTO> for Scheme<dyn CellValue> {
+    type Error = String;
+
+    fn try_from(value: SchemeDTO) -> Result<Self, Self::Error> {
         let mut value_generators = Vec::with_capacity(value.types.len());
-        value.types.iter().for_each(|ty| {
-            value_generators.push(SUPPORTED_TYPES.get(ty.as_str()).unwrap().clone());
-        });
-        Scheme::new(value.types, value.columns, value_generators)
+        for ty in value.types.iter() {
+            let generator = SUPPORTED_TYPES.get(ty.as_str())
+                .ok_or_else(|| format!("No such supported data type: {}", ty))?;
+            value_generators.push(generator.clone());
+        }
+        Ok(Scheme::new(value.types, value.columns, value_generators)
+            .with_parameters(value.parameters)
+            .with_constraints(value.constraints)
+            .with_primary_key(value.primary_key)
+            .with_unique(value.unique))
     }
 }
 
@@ -27,17 +43,40 @@ impl From<Scheme<dyn CellValue>> for SchemeDTO {
         Self {
             types: value.get_types(),
             columns: value.get_columns(),
+            parameters: value.get_parameters(),
+            constraints: value.get_constraints(),
+            primary_key: value.get_primary_key().to_vec(),
+            unique: value.get_unique().to_vec(),
         }
     }
 }
 
 impl SchemeDTO {
     pub fn new(types: Vec<String>, columns: Vec<String>) -> Self {
+        let parameters = vec![Vec::default(); types.len()];
+        let constraints = vec![Vec::default(); types.len()];
         Self {
             types,
-            columns
+            columns,
+            parameters,
+            constraints,
+            primary_key: Vec::default(),
+            unique: Vec::default(),
         }
     }
+    pub fn with_parameters(mut self, parameters: Vec<Vec<String>>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+    pub fn with_constraints(mut self, constraints: Vec<Vec<Constraint>>) -> Self {
+        self.constraints = constraints;
+        self
+    }
+    pub fn with_keys(mut self, primary_key: Vec<String>, unique: Vec<Vec<String>>) -> Self {
+        self.primary_key = primary_key;
+        self.unique = unique;
+        self
+    }
     pub fn encode(&self) -> Vec<u8> {
         let buffer: Vec<u8> = Vec::new();
 
@@ -61,41 +100,214 @@ impl SchemeDTO {
         }
         writer.step_out().unwrap();
 
+        writer.set_field_name("parameters");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column_parameters in self.parameters.iter() {
+            writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+            for parameter in column_parameters.iter() {
+                writer.write_string(parameter).unwrap();
+            }
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        // every constraint is a list of its kind followed by the arguments
+        writer.set_field_name("constraints");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column_constraints in self.constraints.iter() {
+            writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+            for constraint in column_constraints.iter() {
+                writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+                writer.write_string(constraint.get_kind()).unwrap();
+                for argument in constraint.get_arguments().iter() {
+                    writer.write_string(argument).unwrap();
+                }
+                writer.step_out().unwrap();
+            }
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("primary_key");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column in self.primary_key.iter() {
+            writer.write_string(column).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("unique");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for columns in self.unique.iter() {
+            writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+            for column in columns.iter() {
+                writer.write_string(column).unwrap();
+            }
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
         writer.step_out().unwrap();
         writer.flush().unwrap();
 
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-        let elements = binary_user_reader.read_all_elements().unwrap();
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let elements = binary_user_reader.read_all_elements()?;
         let mut types = Vec::<String>::with_capacity(elements.capacity());
         for element in elements {
-            let ty = element.as_string().unwrap().to_string();
+            let ty = element.as_string().expected("a string")?.to_string();
             types.push(ty);
         }
-        binary_user_reader.step_out().unwrap();
+        binary_user_reader.step_out()?;
 
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-        let elements = binary_user_reader.read_all_elements().unwrap();
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let elements = binary_user_reader.read_all_elements()?;
         let mut columns = Vec::<String>::with_capacity(elements.capacity());
         for element in elements {
-            let column = element.as_string().unwrap().to_string();
+            let column = element.as_string().expected("a string")?.to_string();
             columns.push(column);
         }
-        binary_user_reader.step_out().unwrap();
+        binary_user_reader.step_out()?;
 
-        binary_user_reader.step_out().unwrap();
+        // schemes written before type parameters existed end right after the columns
+        let mut parameters = vec![Vec::<String>::default(); types.len()];
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            let elements = binary_user_reader.read_all_elements()?;
+            parameters = Vec::with_capacity(elements.capacity());
+            for element in elements {
+                let column_parameters = element.as_sequence().expected("a list")?
+                    .elements()
+                    .map(|parameter| Ok(parameter.as_string().expected("a string")?.to_string()))
+                    .collect::<IonResult<Vec<String>>>()?;
+                parameters.push(column_parameters);
+            }
+            binary_user_reader.step_out()?;
+        }
 
-        Self {
+        // as well as the ones written before constraints existed end after the parameters
+        let mut constraints = vec![Vec::<Constraint>::default(); types.len()];
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            let elements = binary_user_reader.read_all_elements()?;
+            constraints = Vec::with_capacity(elements.capacity());
+            for element in elements {
+                let column_constraints = element.as_sequence().expected("a list")?
+                    .elements()
+                    .map(|constraint| {
+                        let parts = constraint.as_sequence().expected("a list")?
+                            .elements()
+                            .map(|part| Ok(part.as_string().expected("a string")?.to_string()))
+                            .collect::<IonResult<Vec<String>>>()?;
+                        let (name, arguments) = parts.split_first().expected("the name of a constraint")?;
+                        Constraint::from_parts(name, arguments).map_err(decoding_error_raw)
+                    })
+                    .collect::<IonResult<Vec<Constraint>>>()?;
+                constraints.push(column_constraints);
+            }
+            binary_user_reader.step_out()?;
+        }
+
+        // keys came after constraints, older schemes have none
+        let mut primary_key = Vec::<String>::default();
+        let mut unique = Vec::<Vec<String>>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            primary_key = binary_user_reader.read_all_elements()?
+                .iter()
+                .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+                .collect::<IonResult<Vec<String>>>()?;
+            binary_user_reader.step_out()?;
+
+            binary_user_reader.next()?;
+            binary_user_reader.step_in()?;
+            unique = binary_user_reader.read_all_elements()?
+                .iter()
+                .map(|columns| columns.as_sequence().expected("a list")?
+                    .elements()
+                    .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+                    .collect())
+                .collect::<IonResult<Vec<Vec<String>>>>()?;
+            binary_user_reader.step_out()?;
+        }
+
+        binary_user_reader.step_out()?;
+
+        Ok(Self {
             types,
             columns,
-        }
+            parameters,
+            constraints,
+            primary_key,
+            unique,
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use ion_rs::IonWriter;
+    use core::scheme::{Constraint, Pattern, Scheme};
+    use core::types::CellValue;
+    use super::SchemeDTO;
+
+    #[test]
+    fn endec_scheme_with_parameters() {
+        let scheme = SchemeDTO::new(
+            vec!["IntValue".to_string(), "EnumValue".to_string()],
+            vec!["id".to_string(), "color".to_string()],
+        ).with_parameters(vec![vec![], vec!["red".to_string(), "green".to_string()]]);
+        assert_eq!(scheme, SchemeDTO::decode(scheme.encode()));
+    }
+
+    #[test]
+    fn endec_scheme_with_constraints() {
+        let scheme = SchemeDTO::new(
+            vec!["IntValue".to_string(), "StringValue".to_string()],
+            vec!["age".to_string(), "name".to_string()],
+        ).with_parameters(vec![vec![], vec!["64".to_string()]])
+            .with_constraints(vec![
+                vec![
+                    Constraint::Range { min: Some("0".to_string()), max: None },
+                    Constraint::NotNull,
+                    Constraint::Default("18".to_string()),
+                ],
+                vec![Constraint::Regex(Pattern::new("[A-Z][a-z]+").unwrap())],
+            ])
+            .with_keys(vec!["name".to_string()], vec![vec!["name".to_string(), "age".to_string()]]);
+        assert_eq!(scheme, SchemeDTO::decode(scheme.encode()));
+    }
+
+    #[test]
+    fn decode_scheme_without_parameters() {
+        let mut writer = ion_rs::BinaryWriterBuilder::new().build(Vec::<u8>::new()).unwrap();
+        writer.step_in(ion_rs::IonType::Struct).unwrap();
+        writer.set_field_name("types");
+        writer.step_in(ion_rs::IonType::List).unwrap();
+        writer.write_string("IntValue").unwrap();
+        writer.step_out().unwrap();
+        writer.set_field_name("columns");
+        writer.step_in(ion_rs::IonType::List).unwrap();
+        writer.write_string("id").unwrap();
+        writer.step_out().unwrap();
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        let scheme = SchemeDTO::decode(writer.output().clone());
+        assert_eq!(scheme, SchemeDTO::new(vec!["IntValue".to_string()], vec!["id".to_string()]));
+    }
+    #[test]
+    fn scheme_with_unknown_type() {
+        let scheme = SchemeDTO::new(vec!["IntValue".to_string(), "MoneyValue".to_string()], vec!["id".to_string(), "price".to_string()]);
+        assert_eq!(Scheme::<dyn CellValue>::try_from(scheme).err(), Some("No such supported data type: MoneyValue".to_string()));
     }
 }
\ No newline at end of file
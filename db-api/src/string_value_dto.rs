@@ -2,6 +2,7 @@ use ion_rs;
 use core::types::string_value::StringValue;
 use ion_rs::IonWriter;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct StringValueDTO {
@@ -29,13 +30,16 @@ impl StringValueDTO {
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let value = binding.text();
-        StringValueDTO::new(StringValue::new(value.to_owned()))
+        Ok(StringValueDTO::new(StringValue::new(value.to_owned())))
     }
 }
\ No newline at end of file
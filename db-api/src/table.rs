@@ -1,9 +1,15 @@
 
+use std::collections::HashMap;
 use std::rc::Rc;
 use ion_rs;
+use ion_rs::StreamItem;
+use ion_rs::types::IntAccess;
 use ion_rs::IonWriter;
 use ion_rs::element::reader::ElementReader;
 use ion_rs::IonReader;
+use ion_rs::IonResult;
+use crate::Expected;
+use core::index::IndexKind;
 use core::table::Table;
 use core::scheme::Scheme;
 use core::types::CellValue;
@@ -17,35 +23,68 @@ pub struct TableDTO {
     pub name: String,
     pub scheme: SchemeDTO,
     pub rows: Vec<RowDTO>,
+    // (column, last issued value) of the auto_increment columns, sorted by column
+    pub sequences: Vec<(String, i64)>,
+    // (name, kind, columns) of the indexes, which are built again over the rows on load
+    pub indexes: Vec<(String, IndexKind, Vec<String>)>,
+    // see `Table::get_version`
+    pub version: u64,
 }
 
-impl From<TableDTO> for Table {
-    fn from(value: TableDTO) -> Self {
-        let schema: Scheme<dyn CellValue> = value.scheme.into();
+impl TryFrom<TableDTO> for Table {
+    type Error = String;
+
+    fn try_from(value: TableDTO) -> Result<Self, Self::Error> {
+        let schema = Scheme::<dyn CellValue>::try_from(value.scheme)
+            .map_err(|err| format!("Table {} is corrupt: {}", value.name, err))?;
         let table = Table::new(value.name, schema);
         let mut rows: Vec<Rc<Row<dyn CellValue>>> = Vec::with_capacity(value.rows.len());
-        value.rows.into_iter().for_each(|row| {
-            let new_row = Row::<dyn CellValue>::from(row);
+        for (position, row) in value.rows.into_iter().enumerate() {
+            // computed cells aren't stored, see `From<Table>`
+            let stored_values = row.try_into_row()
+                .map_err(|err| format!("Table {} is corrupt: row {} can't be read: {}", table.get_name(), position, err))?
+                .get_values()
+                .to_vec();
+            let new_row = Row::new(table.get_scheme().complete_values(stored_values));
             rows.push(Rc::new(new_row));
-        });
+        }
+        table.set_sequences(value.sequences.into_iter().collect());
         table.set_rows(rows);
-        table
+        table.set_version(value.version);
+        for (name, kind, columns) in value.indexes {
+            if let Err(err) = table.add_index(&name, columns, kind) {
+                log::error!("index {} of table {} is skipped: {}", name, table.get_name(), err);
+            }
+        }
+        Ok(table)
     }
 }
 
 impl From<Table> for TableDTO {
     fn from(value: Table) -> Self {
         let core_rows = value.rows.take();
+        let mut sequences = value.sequences.take().into_iter().collect::<Vec<(String, i64)>>();
+        sequences.sort();
+        let indexes = value.indexes
+            .take()
+            .into_iter()
+            .map(|index| (index.get_name().to_string(), index.get_kind(), index.get_columns().to_vec()))
+            .collect();
+        let version = value.get_version();
         let name = value.name;
-        let scheme: SchemeDTO = value.scheme.into();
         let mut rows = Vec::<RowDTO>::with_capacity(core_rows.len());
         for row in core_rows.into_iter() {
-            rows.push(RowDTO::from(row));
+            let stored_values = value.scheme.get_stored_values(row.get_values());
+            rows.push(RowDTO::from(Rc::new(Row::new(stored_values))));
         }
+        let scheme: SchemeDTO = value.scheme.into();
         Self {
             name,
             scheme,
             rows,
+            sequences,
+            indexes,
+            version,
         }
     }
 }
@@ -55,9 +94,25 @@ impl TableDTO {
         Self {
             name,
             scheme,
-            rows
+            rows,
+            sequences: Vec::default(),
+            indexes: Vec::default(),
+            version: 0,
         }
     }
+    pub fn with_sequences(mut self, sequences: HashMap<String, i64>) -> Self {
+        self.sequences = sequences.into_iter().collect();
+        self.sequences.sort();
+        self
+    }
+    pub fn with_indexes(mut self, indexes: Vec<(String, IndexKind, Vec<String>)>) -> Self {
+        self.indexes = indexes;
+        self
+    }
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
     pub fn encode(&self) -> Vec<u8> {
         let buffer: Vec<u8> = Vec::new();
 
@@ -70,7 +125,7 @@ impl TableDTO {
         writer.write_string(&self.name).unwrap();
 
         writer.set_field_name("scheme");
-        writer.write_blob(&self.scheme.encode()).unwrap();
+        writer.write_blob(self.scheme.encode()).unwrap();
 
         writer.set_field_name("rows");
         writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
@@ -80,38 +135,117 @@ impl TableDTO {
         }
         writer.step_out().unwrap();
 
+        writer.set_field_name("sequences");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for (column, last_issued) in self.sequences.iter() {
+            writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+            writer.set_field_name("column");
+            writer.write_string(column).unwrap();
+            writer.set_field_name("last_issued");
+            writer.write_i64(*last_issued).unwrap();
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("indexes");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for (name, kind, columns) in self.indexes.iter() {
+            writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+            writer.set_field_name("name");
+            writer.write_string(name).unwrap();
+            writer.set_field_name("kind");
+            writer.write_string(kind.to_string()).unwrap();
+            writer.set_field_name("columns");
+            writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+            for column in columns.iter() {
+                writer.write_string(column).unwrap();
+            }
+            writer.step_out().unwrap();
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("version");
+        writer.write_i64(self.version as i64).unwrap();
+
         writer.step_out().unwrap();
         writer.flush().unwrap();
 
         writer.output().as_slice().into()
     }
     pub fn decode(data: Vec<u8>) -> Self {
-        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data).unwrap();
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
 
-        binary_user_reader.next().unwrap();
-        let binding = binary_user_reader.read_string().unwrap();
+        binary_user_reader.next()?;
+        let binding = binary_user_reader.read_string()?;
         let name = binding.text();
 
-        binary_user_reader.next().unwrap();
-        let scheme = SchemeDTO::decode(binary_user_reader.read_blob().unwrap().as_slice().to_vec());
+        binary_user_reader.next()?;
+        let scheme = SchemeDTO::try_decode(binary_user_reader.read_blob()?.as_slice().to_vec())?;
 
-        binary_user_reader.next().unwrap();
-        binary_user_reader.step_in().unwrap();
-        let elements = binary_user_reader.read_all_elements().unwrap();
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let elements = binary_user_reader.read_all_elements()?;
         let mut rows = Vec::<RowDTO>::with_capacity(elements.capacity());
         for element in elements {
-            let data = element.as_blob().unwrap();
-            rows.push(RowDTO::decode(data.to_vec()));
+            let data = element.as_blob().expected("a blob")?;
+            rows.push(RowDTO::try_decode(data.to_vec())?);
         }
 
-        binary_user_reader.step_out().unwrap();
+        binary_user_reader.step_out()?;
+
+        // tables saved before sequences existed end with the rows
+        let mut sequences = Vec::<(String, i64)>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let sequence = element.as_struct().expected("a struct")?;
+                let column = sequence.get("column").expected("the field column")?.as_string().expected("a string")?.to_string();
+                let last_issued = sequence.get("last_issued").expected("the field last_issued")?.as_i64().expected("an int")?;
+                sequences.push((column, last_issued));
+            }
+            binary_user_reader.step_out()?;
+        }
 
-        Self {
+        // as well as the ones saved before indexes
+        let mut indexes = Vec::<(String, IndexKind, Vec<String>)>::default();
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            binary_user_reader.step_in()?;
+            for element in binary_user_reader.read_all_elements()? {
+                let index = element.as_struct().expected("a struct")?;
+                let name = index.get("name").expected("the field name")?.as_string().expected("a string")?.to_string();
+                // indexes saved before hash ones existed were all B-trees
+                let kind = index.get("kind")
+                    .and_then(|kind| kind.as_string())
+                    .and_then(|kind| IndexKind::parse(kind).ok())
+                    .unwrap_or_default();
+                let columns = index.get("columns").expected("the field columns")?.as_sequence().expected("a list")?
+                    .elements()
+                    .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+                    .collect::<IonResult<Vec<String>>>()?;
+                indexes.push((name, kind, columns));
+            }
+            binary_user_reader.step_out()?;
+        }
+
+        // and before versions
+        let mut version = 0;
+        if let StreamItem::Value(_) = binary_user_reader.next()? {
+            version = binary_user_reader.read_i64()? as u64;
+        }
+
+        Ok(Self {
             name: name.to_owned(),
             scheme,
             rows,
-        }
+            sequences,
+            indexes,
+            version,
+        })
     }
 }
\ No newline at end of file
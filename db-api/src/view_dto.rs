@@ -0,0 +1,155 @@
+use ion_rs;
+use ion_rs::IonWriter;
+use ion_rs::element::reader::ElementReader;
+use ion_rs::IonReader;
+use ion_rs::IonResult;
+use ion_rs::result::decoding_error_raw;
+use crate::Expected;
+use core::view::{View, ViewSource};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ViewDTO {
+    pub value: View,
+}
+
+impl ViewDTO {
+    pub fn new(value: View) -> Self {
+        Self { value }
+    }
+    // the source is stored as its tables, one for a table and two for a join, and the join column,
+    // empty for a table; an absent filter is an empty string
+    pub fn encode(&self) -> Vec<u8> {
+        let buffer: Vec<u8> = Vec::new();
+
+        let binary_writer_builder = ion_rs::BinaryWriterBuilder::new();
+        let mut writer = binary_writer_builder.build(buffer.clone()).unwrap();
+
+        writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+
+        writer.set_field_name("name");
+        writer.write_string(self.value.get_name()).unwrap();
+
+        writer.set_field_name("tables");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for table in self.value.get_source().get_tables() {
+            writer.write_string(table).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("join_column");
+        match self.value.get_source() {
+            ViewSource::Join { column, .. } => writer.write_string(column).unwrap(),
+            ViewSource::Table(_) => writer.write_string("").unwrap(),
+        }
+
+        writer.set_field_name("columns");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for column in self.value.columns.iter() {
+            writer.write_string(column).unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.set_field_name("filter");
+        writer.write_string(self.value.filter.clone().unwrap_or_default()).unwrap();
+
+        writer.set_field_name("order_by");
+        writer.step_in(ion_rs::IonType::List).expect("Error while entering an ion list");
+        for (column, descending) in self.value.order_by.iter() {
+            writer.step_in(ion_rs::IonType::Struct).expect("Error while creating an ion struct");
+            writer.set_field_name("column");
+            writer.write_string(column).unwrap();
+            writer.set_field_name("descending");
+            writer.write_bool(*descending).unwrap();
+            writer.step_out().unwrap();
+        }
+        writer.step_out().unwrap();
+
+        writer.step_out().unwrap();
+        writer.flush().unwrap();
+
+        writer.output().as_slice().into()
+    }
+    pub fn decode(data: Vec<u8>) -> Self {
+        Self::try_decode(data).unwrap()
+    }
+    pub fn try_decode(data: Vec<u8>) -> IonResult<Self> {
+        let mut binary_user_reader = ion_rs::ReaderBuilder::new().build(data)?;
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+
+        binary_user_reader.next()?;
+        let name = binary_user_reader.read_string()?.text().to_string();
+
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let tables = binary_user_reader.read_all_elements()?
+            .iter()
+            .map(|table| Ok(table.as_string().expected("a string")?.to_string()))
+            .collect::<IonResult<Vec<String>>>()?;
+        binary_user_reader.step_out()?;
+
+        binary_user_reader.next()?;
+        let join_column = binary_user_reader.read_string()?.text().to_string();
+
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let columns = binary_user_reader.read_all_elements()?
+            .iter()
+            .map(|column| Ok(column.as_string().expected("a string")?.to_string()))
+            .collect::<IonResult<Vec<String>>>()?;
+        binary_user_reader.step_out()?;
+
+        binary_user_reader.next()?;
+        let filter = binary_user_reader.read_string()?.text().to_string();
+
+        binary_user_reader.next()?;
+        binary_user_reader.step_in()?;
+        let mut order_by = Vec::default();
+        for element in binary_user_reader.read_all_elements()? {
+            let item = element.as_struct().expected("a struct")?;
+            let column = item.get("column").expected("the field column")?.as_string().expected("a string")?.to_string();
+            let descending = item.get("descending").expected("the field descending")?.as_bool().expected("a bool")?;
+            order_by.push((column, descending));
+        }
+        binary_user_reader.step_out()?;
+
+        let source = match tables.as_slice() {
+            [left_table, right_table] => ViewSource::Join {
+                left_table: left_table.clone(),
+                right_table: right_table.clone(),
+                column: join_column,
+            },
+            [table] => ViewSource::Table(table.clone()),
+            _ => return Err(decoding_error_raw(format!("a view has {} source tables", tables.len()))),
+        };
+        let mut view = View::new(name, source)
+            .with_columns(columns)
+            .with_order_by(order_by);
+        view.filter = Some(filter).filter(|filter| !filter.is_empty());
+        Ok(Self::new(view))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::view::{View, ViewSource};
+    use super::ViewDTO;
+
+    #[test]
+    fn endec_view() {
+        let view = ViewDTO::new(
+            View::new("adults".to_string(), ViewSource::Table("people".to_string()))
+                .with_columns(vec!["name".to_string(), "age".to_string()])
+                .with_filter(Some("age >= 18"))
+                .unwrap()
+                .with_order_by(vec![("age".to_string(), true), ("name".to_string(), false)])
+        );
+        assert_eq!(view, ViewDTO::decode(view.encode()));
+
+        let view = ViewDTO::new(View::new(
+            "orders_of_users".to_string(),
+            ViewSource::Join { left_table: "users".to_string(), right_table: "orders".to_string(), column: "user_id".to_string() },
+        ));
+        assert_eq!(view, ViewDTO::decode(view.encode()));
+    }
+}
\ No newline at end of file
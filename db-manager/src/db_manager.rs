@@ -1,19 +1,28 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
-use std::fs::File;
-use std::io::Write;
 use std::ops::DerefMut;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use core::conversion::{ConversionReport, OnConversionFailure};
 use core::db::Database;
+use core::foreign_key::{ForeignKey, OnDelete};
+use core::index::{IndexKind, IndexSummary};
+use core::planner::{plan_join, plan_view, Operation, PlanNode};
+use core::stats::TableProfile;
 use core::types::CellValue;
-use core::scheme::Scheme;
+use core::types::null_value::NullValue;
+use core::scheme::{ColumnDefinition, Scheme};
 use core::row::Row;
 use core::types::SUPPORTED_TYPES;
+use core::row_literal::{parse_row_literal, LiteralValue};
 use core::table::Table;
+use core::view::{parse_order_by, MaterializedView, RefreshMode, View, ViewSource};
 use db_api::db::DatabaseDTO;
+use db_api::check::{check, salvage, CheckReport};
+use db_api::format::migrate;
+use db_api::pager::Pager;
 
 // Can operate with one db-manager at the time
 #[derive(Debug)]
@@ -21,6 +30,8 @@ pub struct DatabaseManager {
     #[allow(clippy::type_complexity)]
     supported_types: HashMap<String, Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>>,
     database: RefCell<Option<Database>>,
+    // the file of the active database, None until a file of the old format is saved
+    pager: RefCell<Option<Rc<RefCell<Pager>>>>,
 }
 
 impl Default for DatabaseManager {
@@ -35,6 +46,7 @@ impl DatabaseManager {
         Self {
             supported_types: SUPPORTED_TYPES.clone(),
             database: RefCell::new(None),
+            pager: RefCell::new(None),
         }
     }
     pub fn create_db(&self, name: &str, location: &str) -> Result<(), String> {
@@ -46,10 +58,12 @@ impl DatabaseManager {
             }
         }
         // create a file for database
-        match File::create(format!("{}/{}", location, name)) {
-            Ok(_) => (),
+        let mut pager = match Pager::create(&format!("{}/{}", location, name)) {
+            Ok(pager) => pager,
             Err(err) => return Err(format!("couldn't create a file: {err}"))
-        }
+        };
+        DatabaseDTO::new(name.to_string(), location.to_string(), Vec::default()).write_pages(&mut pager)?;
+        *self.pager.borrow_mut() = Some(Rc::new(RefCell::new(pager)));
         // build db-manager using Database::builder()
         let database = Database::builder()
             .with_location(location)
@@ -73,21 +87,37 @@ impl DatabaseManager {
             },
             Err(err) => return Err(format!("couldn't read the file {}: {}", location, err))
         };
-        // read file location/table using amazon ion
-        let database = match fs::read(location) {
-            Ok(database) => database,
+        // files written by older versions are upgraded first
+        match migrate(location) {
+            Ok(applied) => applied.iter().for_each(|migration| log::info!("{} is migrated: {}", location, migration)),
             Err(err) => {
                 let err_string = format!("The error is occurred while trying to read tables: {}", err);
                 log::error!("{}", err_string.as_str());
                 return Err(err_string);
             }
-        };
-        let db_dto = DatabaseDTO::decode(database);
-        self.database.borrow_mut().replace(Database::from(db_dto));
+        }
+        // only the catalog is read here, a table is read the first time it's needed
+        let pager = Rc::new(RefCell::new(Pager::open(location)?));
+        let database = DatabaseDTO::read_lazily(pager.clone())?;
+        *self.pager.borrow_mut() = Some(pager);
+        self.database.borrow_mut().replace(database);
+        // views refreshed on change catch up with a file written before they could, if they still can
+        let stale_views = self.database.borrow().as_ref().unwrap().get_materialized_views()
+            .iter()
+            .filter(|view| view.is_stale() && view.get_refresh() == RefreshMode::Incremental)
+            .map(|view| view.get_name().to_string())
+            .collect::<Vec<String>>();
+        for view_name in stale_views {
+            let _ = self.refresh_materialized_view(&view_name);
+        }
         Ok(())
     }
     
     pub fn create_table(&self, table_name: &str, columns: Vec<&str>, data_types: Vec<&str>) -> Result<(), String> {
+        self.create_table_with_keys(table_name, columns, data_types, Vec::default(), Vec::default())
+    }
+    // `primary_key` lists the key columns, `unique` - column sets that can't repeat
+    pub fn create_table_with_keys(&self, table_name: &str, columns: Vec<&str>, data_types: Vec<&str>, primary_key: Vec<&str>, unique: Vec<Vec<&str>>) -> Result<(), String> {
         // 1) check if the table already exists
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
@@ -95,42 +125,40 @@ impl DatabaseManager {
         if columns.len() != data_types.len() {
             return Err("Different number of columns and data types".to_string());
         }
-        #[allow(clippy::type_complexity)]
-        let mut value_generators: Vec<Arc<fn(String) -> Result<Rc<dyn CellValue>, String>>> = Vec::with_capacity(data_types.len());
-        let mut new_columns = Vec::with_capacity(columns.len());
-        let mut types = Vec::with_capacity(data_types.len());
+        let mut scheme = Scheme::builder();
         for (data_type, column_name) in data_types.iter().zip(columns) {
-            match self.supported_types.get(*data_type) {
-                Some(value_generator) => value_generators.push(value_generator.clone()),
-                None => return Err(format!("No such supported data type: {}", data_type))
-            }
-            new_columns.push(column_name.to_string());
-            types.push(data_type.to_string());
+            let definition = ColumnDefinition::parse(column_name, data_type)?;
+            let value_generator = match self.supported_types.get(&definition.ty) {
+                Some(value_generator) => value_generator.clone(),
+                None => return Err(format!("No such supported data type: {}", definition.ty))
+            };
+            scheme = scheme.with_column_definition(definition, value_generator);
         }
-        let scheme = Scheme::new(types, new_columns, value_generators);
-        let table = match Table::builder()
+        let scheme = scheme.build()
+            .with_primary_key(primary_key.iter().map(|column| column.to_string()).collect())
+            .with_unique(unique.iter().map(|columns| columns.iter().map(|column| column.to_string()).collect()).collect());
+        let table = Table::builder()
             .with_name(table_name.to_string())
             .with_scheme(scheme)
-            .build() {
-            Ok(table) => table,
-            Err(err) => return Err(err)
-        };
+            .build()?;
         let mut db = self.database.borrow_mut();
         let unwrapped_db = db.as_mut().unwrap();
-        unwrapped_db.get_tables_mut().insert(table_name.to_string(), table);
+        if unwrapped_db.has_view(table_name) {
+            return Err(format!("view {} already exists", table_name));
+        }
+        unwrapped_db.add_table(table);
         Ok(())
     }
     pub fn delete_table(&self, table_name: &str) -> Result<(), String> {
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
         }
-        let mut db = self.database.borrow_mut();
-        let db_unwrapped = db.as_mut().unwrap();
-        let mut tables = db_unwrapped.get_tables_mut();
-        match tables.deref_mut().remove(table_name) {
-            Some(_) => Ok(()),
-            None => Err(format!("There is no table with name {}", table_name))
-        }
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        let views = db_unwrapped.get_materialized_views_depending_on(table_name);
+        db_unwrapped.delete_table(table_name)?;
+        self.refresh_views(views);
+        Ok(())
     }
     pub fn add_row(&self, table_name: &str, raw_values: &str) -> Result<(), String>{
         if self.database.borrow().is_none() {
@@ -138,31 +166,145 @@ impl DatabaseManager {
         }
         let db = self.database.borrow();
         let db_unwrapped = db.as_ref().unwrap();
-        let res = match db_unwrapped.get_tables_mut().get_mut(table_name) {
-            Some(table) => {
-                let scheme = table.get_scheme();
-                let split_values = raw_values
-                    .split(';')
-                    .map(|value| value.trim().to_string());
-
-                let mut row_values = Vec::default();
-                for (generator, raw_value) in scheme.get_validators().iter().zip(split_values) {
-                    match generator(raw_value) {
-                        Ok(value) => row_values.push(value),
-                        Err(err) => {
-                            return Err(err);
-                        }
-                    }
+        // the tables its foreign keys refer to are loaded with it, as checking the row reads them
+        let res = {
+            let tables = db_unwrapped.get_table_with_referenced(table_name)?;
+            let table = &tables[table_name];
+            let new_row = Self::build_row(table, raw_values, None)?;
+            db_unwrapped.check_references(table_name, &new_row)?;
+            log::debug!("Added row into table {} with values {:?}", table_name, new_row);
+            table.insert_row(new_row)
+        };
+        if res.is_ok() {
+            self.refresh_views(db_unwrapped.get_materialized_views_depending_on(table_name));
+        }
+        res
+    }
+    pub fn update_row(&self, table_name: &str, index: u64, raw_values: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        let res = {
+            let tables = db_unwrapped.get_table_with_references(table_name)?;
+            let table = &tables[table_name];
+            let base_row = match table.get_rows().get(index as usize) {
+                Some(row) => row.clone(),
+                None => return Err(format!("There is no row {} in table {}", index, table_name)),
+            };
+            let new_row = Self::build_row(table, raw_values, Some(&base_row))?;
+            db_unwrapped.check_update_references(table_name, index as usize, &new_row)?;
+            log::debug!("Updated row {} of table {} with values {:?}", index, table_name, new_row);
+            table.update_row(index, new_row)
+        };
+        if res.is_ok() {
+            self.refresh_views(db_unwrapped.get_materialized_views_depending_on(table_name));
+        }
+        res
+    }
+    // adds a row for every line of `text` holding a row literal, as `add_row` does, skipping blank
+    // lines; either all of the rows are added or none of them. Returns how many rows were added
+    pub fn import_rows(&self, table_name: &str, text: &str) -> Result<usize, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        let res = {
+            let tables = db_unwrapped.get_table_with_referenced(table_name)?;
+            let table = &tables[table_name];
+            let mut added = 0;
+            let mut res = Ok(0);
+            for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                let inserted = Self::build_row(table, line, None).and_then(|new_row| {
+                    db_unwrapped.check_references(table_name, &new_row)?;
+                    table.insert_row(new_row)
+                });
+                if let Err(err) = inserted {
+                    res = Err(format!("line {}: {}", number + 1, err));
+                    break;
                 }
-                let new_row = Row::new(row_values);
-                log::debug!("Added row into table {} with values {:?}", table_name, new_row);
-                table.add_row(new_row);
-                Ok(())
-            },
-            None => Err(format!("There is no table with name {}", table_name))
+                added += 1;
+                res = Ok(added);
+            }
+            if res.is_err() {
+                (0..added).for_each(|_| table.pop());
+            }
+            res
         };
+        match &res {
+            Ok(added) => {
+                log::debug!("Imported {} rows into table {}", added, table_name);
+                self.refresh_views(db_unwrapped.get_materialized_views_depending_on(table_name));
+            },
+            Err(err_string) => log::error!("{}", err_string.as_str()),
+        }
         res
     }
+    // parses a row literal (see `core::row_literal`) into a row of the table. Values are either given
+    // for every stored column in order, or by name (`name=Bob;email=b@x.io`); empty unquoted cells and
+    // the columns left out take their defaults, the next value of their sequence, or the values of
+    // `base_row` when it's an update. Computed columns can't be given, they are computed at the end
+    fn build_row(table: &Table, raw_values: &str, base_row: Option<&Row<dyn CellValue>>) -> Result<Row<dyn CellValue>, String> {
+        let scheme = table.get_scheme();
+        let columns = scheme.get_columns();
+        let stored_columns = (0..columns.len())
+            .filter(|&index| !scheme.is_computed_column(index))
+            .collect::<Vec<usize>>();
+        let fields = parse_row_literal(raw_values)?;
+
+        let mut cells: Vec<LiteralValue> = vec![LiteralValue::Omitted; columns.len()];
+        let named_count = fields.iter().filter(|field| field.column.is_some()).count();
+        if named_count == fields.len() {
+            let mut given = vec![false; columns.len()];
+            for field in fields {
+                let column = field.column.unwrap();
+                let index = match scheme.get_column_index(&column) {
+                    Some(index) => index,
+                    None => return Err(format!("There is no column {} in table {}", column, table.get_name())),
+                };
+                if scheme.is_computed_column(index) {
+                    return Err(format!("column {} is computed and can't be given a value", column));
+                }
+                if given[index] {
+                    return Err(format!("column {} is given more than once", column));
+                }
+                given[index] = true;
+                cells[index] = field.value;
+            }
+        } else if named_count == 0 {
+            if fields.len() != stored_columns.len() {
+                return Err(format!(
+                    "table {} has {} columns to fill, but {} values were given; name the columns to omit some of them",
+                    table.get_name(),
+                    stored_columns.len(),
+                    fields.len()
+                ));
+            }
+            for (index, field) in stored_columns.into_iter().zip(fields) {
+                cells[index] = field.value;
+            }
+        } else {
+            return Err("either name every value as column=value or none of them; quote values containing =".to_string());
+        }
+
+        let mut row_values = Vec::with_capacity(cells.len());
+        for (index, cell) in cells.into_iter().enumerate() {
+            let value = match cell {
+                LiteralValue::Omitted if scheme.is_computed_column(index) => Ok(Rc::new(NullValue::new()) as Rc<dyn CellValue>),
+                LiteralValue::Omitted => match base_row {
+                    Some(base_row) => Ok(base_row.get_values()[index].clone()),
+                    None => table.generate_missing_value(index),
+                },
+                LiteralValue::Null => scheme.generate_null_value(index),
+                LiteralValue::Value(raw_value) => scheme.generate_literal_value(index, raw_value),
+            };
+            row_values.push(value?);
+        }
+        scheme.compute_values(&mut row_values)?;
+        Ok(Row::new(row_values))
+    }
 
     pub fn add_row_to_the_table(table: &Table, raw_values: Vec<String>){
         let scheme = table.get_scheme();
@@ -184,41 +326,132 @@ impl DatabaseManager {
         }
         let db = self.database.borrow();
         let db_unwrapped = db.as_ref().unwrap();
-        let res = match db_unwrapped.get_tables_mut().get_mut(table_name) {
-            Some(table) => table.erase(index),
-            None => {
-                let err_string = format!("There is no table {} in {}", table_name, db_unwrapped.get_name());
-                log::error!("{}", err_string.as_str());
-                Err(err_string)
-            }
-        };
+        let res = db_unwrapped.delete_row(table_name, index as usize);
+        match &res {
+            Ok(()) => self.refresh_views(db_unwrapped.get_materialized_views_depending_on(table_name)),
+            Err(err_string) => log::error!("{}", err_string.as_str()),
+        }
         res
     }
+    // builds the index `index_name` over `columns` of the table, `kind` being btree, hash or fulltext; lookups,
+    // and for a B-tree sorts and ranges as well, use it from now on
+    pub fn create_index(&self, table_name: &str, index_name: &str, columns: Vec<&str>, kind: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let kind = IndexKind::parse(kind)?;
+        let columns = columns.iter().map(|column| column.trim().to_string()).collect();
+        self.database.borrow().as_ref().unwrap().get_table(table_name)?.add_index(index_name.trim(), columns, kind)
+    }
+    pub fn drop_index(&self, table_name: &str, index_name: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        self.database.borrow().as_ref().unwrap().get_table(table_name)?.drop_index(index_name.trim())
+    }
+    pub fn get_indexes(&self, table_name: &str) -> Result<Vec<IndexSummary>, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        let table = db.as_ref().unwrap().get_table(table_name)?;
+        let summaries = table.get_indexes().iter().map(|index| index.get_summary()).collect();
+        Ok(summaries)
+    }
+    // (table, estimated bytes its indexes take) for the tables having any, by name
+    pub fn get_indexes_memory_sizes(&self) -> Result<Vec<(String, usize)>, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        // an index of a table that isn't loaded takes no memory
+        let mut sizes = self.database.borrow().as_ref().unwrap().get_loaded_tables()
+            .values()
+            .filter(|table| !table.get_indexes().is_empty())
+            .map(|table| (table.get_name().to_string(), table.get_indexes_memory_size()))
+            .collect::<Vec<(String, usize)>>();
+        sizes.sort();
+        Ok(sizes)
+    }
+    // profile of the table, which the planner uses until the table changes, or of the rows of a view
+    pub fn get_stats(&self, name: &str) -> Result<TableProfile, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        if self.database.borrow().as_ref().unwrap().has_table(name) {
+            return Ok(self.database.borrow().as_ref().unwrap().get_table(name)?.update_profile());
+        }
+        match self.get_view(name) {
+            Ok(view) => Ok(TableProfile::collect(&view)),
+            Err(_) => Err(format!("There is no table or view with name {}", name)),
+        }
+    }
+    // writes the profile of the table or view to `path` as CSV, a line per column
+    pub fn export_stats(&self, name: &str, path: &str) -> Result<(), String> {
+        let profile = self.get_stats(name)?;
+        fs::write(path, profile.to_csv()).map_err(|err| format!("Can't write the statistics to {}: {}", path, err))
+    }
+    // rows of the table that its full-text indexes find for the query, the best matches first, in a
+    // table of the same columns but with no keys
+    pub fn search(&self, table_name: &str, query: &str) -> Result<Table, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let table = self.get_table(table_name)?;
+        let found = table.search(query)?;
+        let scheme = table.get_scheme();
+        let result_scheme = Scheme::new(scheme.get_types(), scheme.get_columns(), scheme.get_validators().to_vec());
+        let result = Table::builder()
+            .with_name("search_result".to_string())
+            .with_scheme(result_scheme)
+            .build()?;
+        let rows = table.get_rows();
+        for (position, _) in found {
+            result.add_row(Row::new(rows[position].get_values().to_vec()));
+        }
+        Ok(result)
+    }
+    // `table(columns)` starts referencing the key `referenced_table(referenced_columns)`
+    pub fn add_foreign_key(&self, table_name: &str, columns: Vec<&str>, referenced_table_name: &str, referenced_columns: Vec<&str>, on_delete: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let foreign_key = ForeignKey::new(
+            table_name.to_string(),
+            columns.iter().map(|column| column.trim().to_string()).collect(),
+            referenced_table_name.to_string(),
+            referenced_columns.iter().map(|column| column.trim().to_string()).collect(),
+            OnDelete::parse(on_delete)?,
+        );
+        let db = self.database.borrow();
+        db.as_ref().unwrap().add_foreign_key(foreign_key)
+    }
+    pub fn get_foreign_keys(&self) -> Result<Vec<ForeignKey>, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        Ok(self.database.borrow().as_ref().unwrap().get_foreign_keys().clone())
+    }
     pub fn close_db(&self, save: bool) -> Result<(), String> {
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
         }
         let db = self.database.take().unwrap();
-        let res = if save {
-            let db_dto: DatabaseDTO = db.into();
-            let location = &format!("{}/{}", db_dto.location, db_dto.name);
-            let fd = fs::OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(location);
-            let mut file = match fd {
-                Ok(file) => file,
-                Err(err) => return Err(format!("couldn't open the file to save {}: {}", location, err))
-            };
-            match file.write_all(db_dto.encode().as_slice()) {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("couldn't write to the file to save {}: {}", location, err)),
-            }
-        } else {
-            Ok(())
-        };
-        res
+        let pager = self.pager.take();
+        if !save {
+            return Ok(());
+        }
+        let db_dto: DatabaseDTO = db.into();
+        let location = &format!("{}/{}", db_dto.location, db_dto.name);
+        if let Some(pager) = pager {
+            let result = db_dto.write_pages(&mut pager.borrow_mut());
+            return result.map_err(|err| format!("couldn't write to the file to save {}: {}", location, err));
+        }
+        // a file that isn't paged is written next to the one there and moved over it once it's whole
+        let saved_path = format!("{}.saving", location);
+        let mut pager = Pager::create(&saved_path).map_err(|err| format!("couldn't open the file to save {}: {}", location, err))?;
+        db_dto.write_pages(&mut pager).map_err(|err| format!("couldn't write to the file to save {}: {}", location, err))?;
+        drop(pager);
+        fs::rename(&saved_path, location).map_err(|err| format!("couldn't replace {} with the saved file: {}", location, err))
     }
     pub fn delete_db(&self, dir: &str, file_name: &str) -> Result<(), String> {
         // TODO: it will be nice to check if the provided location actually is a db but who cares?
@@ -235,41 +468,270 @@ impl DatabaseManager {
             },
         }
     }
+    // scans the file for what doesn't match its checksums, and writes what can be read to `salvage_name` next to it
+    pub fn check_db(&self, dir: &str, file_name: &str, salvage_name: Option<&str>) -> Result<CheckReport, String> {
+        let location = &format!("{}/{}", dir, file_name);
+        let report = match salvage_name {
+            Some(salvage_name) => salvage(location, dir, salvage_name)?,
+            None => check(location)?,
+        };
+        for table in report.get_corrupt_tables() {
+            log::error!("table {} of {} is corrupt: {}", table.name, location, table.problems.join("; "));
+        }
+        Ok(report)
+    }
     pub fn get_table(&self, table_name: &str) -> Result<Table, String> {
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
         }
-        match self.database.borrow().as_ref().unwrap().get_tables_mut().get_mut(table_name) {
-            Some(table) => Ok(table.clone()),
-            None => Err(format!("there is no table with name {}", table_name))
+        // a table that wasn't loaded yet is read here
+        match self.database.borrow().as_ref().unwrap().get_table(table_name) {
+            Ok(table) => Ok(table.clone()),
+            Err(err) if self.database.borrow().as_ref().unwrap().has_table(table_name) => Err(err),
+            Err(_) => Err(format!("there is no table with name {}", table_name))
         }
     }
     pub fn get_existing_table_names(&self) -> Result<Vec<String>, String> {
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
         }
-        Ok(self.database.borrow().as_ref().unwrap().get_tables().keys().cloned().collect())
+        Ok(self.database.borrow().as_ref().unwrap().get_table_names())
     }
     pub fn db_is_opened(&self) -> bool {
         self.database.borrow().is_some()
     }
     pub fn get_table_list(&self) -> Vec<String> {
-        self.database.borrow().as_ref().unwrap().get_tables().keys().cloned().collect::<Vec<String>>()
+        self.database.borrow().as_ref().unwrap().get_table_names()
     }
-
-    pub fn join(&self, lhs_table_name: &str, rhs_table_name: &str, column: &str) -> Result<Table, String> {
+    // the plain views followed by the materialized ones
+    pub fn get_view_list(&self) -> Vec<String> {
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        let mut views = db_unwrapped.get_views().iter().map(|view| view.get_name().to_string()).collect::<Vec<String>>();
+        views.extend(db_unwrapped.get_materialized_views().iter().map(|view| view.get_name().to_string()));
+        views
+    }
+    // what the view is for the lists: V for a plain one, MV for a materialized one, MV stale for one
+    // whose sources have changed since it was refreshed
+    pub fn get_view_marker(&self, view_name: &str) -> Option<String> {
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref()?;
+        if db_unwrapped.get_view(view_name).is_ok() {
+            return Some("V".to_string());
+        }
+        db_unwrapped.get_materialized_view(view_name).ok().map(|view| match view.is_stale() {
+            true => "MV stale".to_string(),
+            false => "MV".to_string(),
+        })
+    }
+    // saves the rows of `table_name` for which `filter` is true, sorted by `order_by` (`age desc;name`)
+    // and cut down to `columns`, all of them if empty, as the view `view_name`
+    pub fn create_view(&self, view_name: &str, table_name: &str, columns: Vec<&str>, filter: Option<&str>, order_by: Option<&str>) -> Result<(), String> {
+        self.add_view(view_name, ViewSource::Table(table_name.to_string()), columns, filter, order_by)
+    }
+    // the same as `create_view`, but over the join of two tables on `column`
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_join_view(&self, view_name: &str, lhs_table_name: &str, rhs_table_name: &str, column: &str, columns: Vec<&str>, filter: Option<&str>, order_by: Option<&str>) -> Result<(), String> {
+        let source = ViewSource::Join {
+            left_table: lhs_table_name.to_string(),
+            right_table: rhs_table_name.to_string(),
+            column: column.to_string(),
+        };
+        self.add_view(view_name, source, columns, filter, order_by)
+    }
+    // the view is evaluated once to check it
+    fn add_view(&self, view_name: &str, source: ViewSource, columns: Vec<&str>, filter: Option<&str>, order_by: Option<&str>) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let view = View::new(view_name.trim().to_string(), source)
+            .with_columns(columns.iter().map(|column| column.trim().to_string()).collect())
+            .with_filter(filter.filter(|filter| !filter.trim().is_empty()))?
+            .with_order_by(order_by.map(parse_order_by).transpose()?.unwrap_or_default());
+        self.evaluate_view(&view)?;
+        let db = self.database.borrow();
+        db.as_ref().unwrap().add_view(view)
+    }
+    pub fn drop_view(&self, view_name: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        db.as_ref().unwrap().drop_view(view_name)
+    }
+    // the rows of the view as they are now, or as they were last refreshed for a materialized one
+    pub fn get_view(&self, view_name: &str) -> Result<Table, String> {
         if self.database.borrow().is_none() {
             return Err("There is no active databases in db-manager manager".to_string());
         }
-        let lhs = match self.get_table(lhs_table_name) {
-            Ok(lhs) => lhs,
-            Err(err) => return Err(err)
+        let materialized_table = self.database.borrow().as_ref().unwrap().get_materialized_views()
+            .iter()
+            .find(|view| view.get_name() == view_name)
+            .map(|view| view.get_table().clone());
+        if let Some(table) = materialized_table {
+            return Ok(table);
+        }
+        let view = self.database.borrow().as_ref().unwrap().get_view(view_name)?;
+        self.evaluate_view(&view)
+    }
+    // stores the rows of the view in a table of its own, refreshed on demand or after every change
+    // of its sources depending on `refresh`; a materialized view just gets the new mode and is refreshed
+    pub fn materialize_view(&self, view_name: &str, refresh: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let refresh = RefreshMode::parse(refresh)?;
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        if db_unwrapped.get_materialized_view(view_name).is_ok() {
+            if let Some(view) = db_unwrapped.get_materialized_views_mut().iter_mut().find(|view| view.get_name() == view_name) {
+                view.refresh = refresh;
+            }
+            return self.refresh_materialized_view(view_name);
+        }
+        let view = db_unwrapped.get_view(view_name)?;
+        let (table, positions) = self.evaluate_view_with_positions(&view)?;
+        let source_versions = self.get_source_versions(&view);
+        db_unwrapped.drop_view(view_name)?;
+        let mut materialized_view = MaterializedView::new(view, refresh, table.clone());
+        materialized_view.set_table(table, positions, source_versions);
+        db_unwrapped.add_materialized_view(materialized_view)
+    }
+    pub fn refresh_view(&self, view_name: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        if db_unwrapped.get_view(view_name).is_ok() {
+            return Err(format!("view {} isn't materialized, it's always up to date", view_name));
+        }
+        db_unwrapped.get_materialized_view(view_name)?;
+        self.refresh_materialized_view(view_name)
+    }
+    // evaluates the materialized view again; it stays stale if it can't be evaluated
+    fn refresh_materialized_view(&self, view_name: &str) -> Result<(), String> {
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        let view = match db_unwrapped.get_materialized_views().iter().find(|view| view.get_name() == view_name) {
+            Some(materialized_view) => materialized_view.get_view().clone(),
+            None => return Err(format!("There is no materialized view with name {}", view_name)),
         };
-        let rhs = match self.get_table(rhs_table_name) {
-            Ok(rhs) => rhs,
-            Err(err) => return Err(err)
+        let result = self.evaluate_view_with_positions(&view);
+        let source_versions = self.get_source_versions(&view);
+        let mut materialized_views = db_unwrapped.get_materialized_views_mut();
+        let materialized_view = match materialized_views.iter_mut().find(|view| view.get_name() == view_name) {
+            Some(materialized_view) => materialized_view,
+            None => return Err(format!("There is no materialized view with name {}", view_name)),
         };
-        
+        match result {
+            Ok((table, positions)) => {
+                materialized_view.set_table(table, positions, source_versions);
+                Ok(())
+            },
+            Err(err) => {
+                materialized_view.stale = true;
+                Err(err)
+            },
+        }
+    }
+    // catches the materialized views up with a change of their sources: the ones refreshed on demand
+    // are flagged stale and the rest replay the changes of their source, see `MaterializedView::catch_up`,
+    // or are evaluated again when they can't
+    fn refresh_views(&self, view_names: Vec<String>) {
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        for view_name in view_names {
+            let refreshed = {
+                let mut materialized_views = db_unwrapped.get_materialized_views_mut();
+                let view = match materialized_views.iter_mut().find(|view| view.get_name() == view_name) {
+                    Some(view) => view,
+                    None => continue,
+                };
+                match (view.get_refresh(), view.get_view().get_source().clone()) {
+                    (RefreshMode::OnDemand, _) => {
+                        view.stale = true;
+                        true
+                    },
+                    (RefreshMode::Incremental, ViewSource::Table(table_name)) => match db_unwrapped.get_table(&table_name) {
+                        Ok(source) => view.catch_up(&source).unwrap_or(false),
+                        Err(_) => false,
+                    },
+                    (RefreshMode::Incremental, _) => false,
+                }
+            };
+            if !refreshed {
+                if let Err(err) = self.refresh_materialized_view(&view_name) {
+                    log::error!("couldn't refresh the view {}: {}", view_name, err);
+                }
+            }
+        }
+    }
+    // the plan the view, or the table, is read by
+    pub fn explain(&self, name: &str) -> Result<PlanNode, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let view = {
+            let db = self.database.borrow();
+            let db_unwrapped = db.as_ref().unwrap();
+            if let Ok(view) = db_unwrapped.get_materialized_view(name) {
+                let rows = view.get_table().get_rows().len() as f64;
+                return Ok(PlanNode::new(Operation::Scan { table: name.to_string() }, rows, rows));
+            }
+            db_unwrapped.get_view(name).ok()
+        };
+        let view = match view {
+            Some(view) => view,
+            None => {
+                let rows = self.get_table(name)?.get_rows().len() as f64;
+                return Ok(PlanNode::new(Operation::Scan { table: name.to_string() }, rows, rows));
+            },
+        };
+        let plan = match view.get_source() {
+            ViewSource::Table(table_name) => plan_view(&view, &self.get_table(table_name)?, None)?,
+            // the join is planned, not done: the view is planned over the rows it's estimated to give
+            ViewSource::Join { left_table, right_table, column } => {
+                let lhs = self.get_table(left_table)?;
+                let rhs = self.get_table(right_table)?;
+                let join_table = self.get_join_table(&lhs, &rhs, column)?;
+                plan_view(&view, &join_table, Some(plan_join(&lhs, &rhs, column).tree))?
+            },
+        };
+        Ok(plan.tree)
+    }
+    pub fn explain_join(&self, lhs_table_name: &str, rhs_table_name: &str, column: &str) -> Result<PlanNode, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let lhs = self.get_table(lhs_table_name)?;
+        let rhs = self.get_table(rhs_table_name)?;
+        Ok(plan_join(&lhs, &rhs, column).tree)
+    }
+
+    fn evaluate_view(&self, view: &View) -> Result<Table, String> {
+        self.evaluate_view_with_positions(view).map(|(table, _)| table)
+    }
+    // the positions of the rows in the source are only known for a view over a single table
+    fn evaluate_view_with_positions(&self, view: &View) -> Result<(Table, Option<Vec<usize>>), String> {
+        match view.get_source() {
+            ViewSource::Table(table_name) => {
+                let (table, positions) = view.apply_with_positions(&self.get_table(table_name)?)?;
+                Ok((table, Some(positions)))
+            },
+            ViewSource::Join { left_table, right_table, column } => Ok((view.apply(&self.join(left_table, right_table, column)?)?, None)),
+        }
+    }
+    fn get_source_versions(&self, view: &View) -> Vec<(String, u64)> {
+        let db = self.database.borrow();
+        let db_unwrapped = db.as_ref().unwrap();
+        view.get_source().get_tables().into_iter()
+            .filter_map(|table_name| db_unwrapped.get_table_version(table_name).map(|version| (table_name.to_string(), version)))
+            .collect()
+    }
+
+    // the table the join of the tables on `column` is put into, with no rows yet
+    fn get_join_table(&self, lhs: &Table, rhs: &Table, column: &str) -> Result<Table, String> {
         let lhs_columns = lhs.scheme.get_columns();
         let rhs_columns = rhs.scheme.get_columns();
         if !lhs_columns.contains(&column.to_owned()) || !rhs_columns.contains(&column.to_owned()) {
@@ -316,13 +778,28 @@ impl DatabaseManager {
 
         let join_scheme = Scheme::new(join_types, join_columns, join_generators);
 
-        let join_table = match Table::builder()
-        .with_name("join_table".to_string())
-        .with_scheme(join_scheme)
-        .build() {
-            Ok(table) => table,
-            Err(err) => return Err(err)
-        };
+        Table::builder()
+            .with_name("join_table".to_string())
+            .with_scheme(join_scheme)
+            .build()
+    }
+
+    pub fn join(&self, lhs_table_name: &str, rhs_table_name: &str, column: &str) -> Result<Table, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let lhs = self.get_table(lhs_table_name)?;
+        let rhs = self.get_table(rhs_table_name)?;
+        
+        let join_table = self.get_join_table(&lhs, &rhs, column)?;
+        let lhs_column_index = lhs.get_scheme().get_columns().iter().position(|n| n == column).unwrap();
+        let rhs_column_index = rhs.get_scheme().get_columns().iter().position(|n| n == column).unwrap();
+
+        let lhs_range = 0..lhs.get_scheme().get_columns().len();
+        let rhs_range = 0..rhs.get_scheme().get_columns().len();
+
+        let lhs_scheme = lhs.get_scheme();
+        let rhs_scheme = rhs.get_scheme();
         
         let mut lhs_core_column_values: Vec<String> = Vec::new();
         for i in 0..lhs.get_rows().len() {
@@ -350,16 +827,40 @@ impl DatabaseManager {
                     },
                     core::types::ValueType::Email(email) => {
                         email.get_value().to_string()
+                    },
+                    core::types::ValueType::Bool(bool) => {
+                        bool.get_value().to_string()
+                    },
+                    core::types::ValueType::Decimal(decimal) => {
+                        decimal.normalize().to_string()
+                    },
+                    core::types::ValueType::IntInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::RealInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::DateInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::Enum(enumeration) => {
+                        enumeration.get_value().to_owned()
+                    },
+                    core::types::ValueType::Null(_) => {
+                        "NULL".to_owned()
                     }
                 }
             )
         }
-        let mut core_lhs_column_values_copy = lhs_core_column_values.clone();
-        let lhs_len = core_lhs_column_values_copy.len();
-        core_lhs_column_values_copy.sort();
-        core_lhs_column_values_copy.dedup();
-        if lhs_len != core_lhs_column_values_copy.len() {
-            return Err("The table column is not consist from unique values".to_owned());
+        // a declared key already guarantees there are no repeats
+        if !lhs_scheme.is_unique_column(column) {
+            let mut core_lhs_column_values_copy = lhs_core_column_values.clone();
+            let lhs_len = core_lhs_column_values_copy.len();
+            core_lhs_column_values_copy.sort();
+            core_lhs_column_values_copy.dedup();
+            if lhs_len != core_lhs_column_values_copy.len() {
+                return Err("The table column is not consist from unique values".to_owned());
+            }
         }
 
         let mut rhs_core_column_values: Vec<String> = Vec::new();
@@ -388,92 +889,46 @@ impl DatabaseManager {
                     },
                     core::types::ValueType::Email(email) => {
                         email.get_value().to_string()
-                    }
-                }
-            )
-        }
-        let mut core_rhs_column_values_copy = rhs_core_column_values.clone();
-        let rhs_len = core_rhs_column_values_copy.len();
-        core_rhs_column_values_copy.sort();
-        core_rhs_column_values_copy.dedup();
-        if rhs_len != core_rhs_column_values_copy.len() {
-            return Err("The table column is not consist from unique values".to_owned());
-        }
-
-        let mut core_column_values: Vec<String> = Vec::new();
-        for value in lhs_core_column_values {
-            core_column_values.push(value);
-        }
-        for value in rhs_core_column_values {
-            core_column_values.push(value);
-        }
-        core_column_values.sort();
-        core_column_values.dedup();
-
-        for core_column_value in core_column_values {
-            let mut row_values: Vec<String> = Vec::new();
-            row_values.push(core_column_value.clone());
-
-            let mut lhs_row_index: Option<usize> = None;
-            for index in 0..lhs.get_rows().len() {
-                let column_index_value = match lhs.get_rows().get(index).unwrap().get_values().get(lhs_column_index).unwrap().get_value() {
-                    core::types::ValueType::Int(int) => {
-                        int.get_value().to_string()
                     },
-                    core::types::ValueType::Str(str) => {
-                        str.get_value().to_owned()
+                    core::types::ValueType::Bool(bool) => {
+                        bool.get_value().to_string()
                     },
-                    core::types::ValueType::Real(real) => {
-                        real.get_value().to_string()
+                    core::types::ValueType::Decimal(decimal) => {
+                        decimal.normalize().to_string()
                     },
-                    core::types::ValueType::Pic(_picture) => {
-                        "picture".to_owned()
+                    core::types::ValueType::IntInterval(interval) => {
+                        interval.to_string()
                     },
-                    core::types::ValueType::Char(char) => {
-                        char.get_value().to_string()
+                    core::types::ValueType::RealInterval(interval) => {
+                        interval.to_string()
                     },
-                    core::types::ValueType::Date(date) => {
-                        date.get_value().to_string()
+                    core::types::ValueType::DateInterval(interval) => {
+                        interval.to_string()
                     },
-                    core::types::ValueType::Email(email) => {
-                        email.get_value().to_string()
-                    }
-                };
-                if core_column_value == column_index_value {
-                    lhs_row_index = Some(index);
-                    break;
-                }
-            }
-            let mut rhs_row_index: Option<usize> = None;
-            for index in 0..rhs.get_rows().len() {
-                let column_index_value = match rhs.get_rows().get(index).unwrap().get_values().get(rhs_column_index).unwrap().get_value() {
-                    core::types::ValueType::Int(int) => {
-                        int.get_value().to_string()
-                    },
-                    core::types::ValueType::Str(str) => {
-                        str.get_value().to_owned()
+                    core::types::ValueType::Enum(enumeration) => {
+                        enumeration.get_value().to_owned()
                     },
-                    core::types::ValueType::Real(real) => {
-                        real.get_value().to_string()
-                    },
-                    core::types::ValueType::Pic(_picture) => {
-                        "picture".to_owned()
-                    },
-                    core::types::ValueType::Char(char) => {
-                        char.get_value().to_string()
-                    },
-                    core::types::ValueType::Date(date) => {
-                        date.get_value().to_string()
-                    },
-                    core::types::ValueType::Email(email) => {
-                        email.get_value().to_string()
+                    core::types::ValueType::Null(_) => {
+                        "NULL".to_owned()
                     }
-                };
-                if core_column_value == column_index_value {
-                    rhs_row_index = Some(index);
-                    break;
                 }
+            )
+        }
+        // a declared key already guarantees there are no repeats
+        if !rhs_scheme.is_unique_column(column) {
+            let mut core_rhs_column_values_copy = rhs_core_column_values.clone();
+            let rhs_len = core_rhs_column_values_copy.len();
+            core_rhs_column_values_copy.sort();
+            core_rhs_column_values_copy.dedup();
+            if rhs_len != core_rhs_column_values_copy.len() {
+                return Err("The table column is not consist from unique values".to_owned());
             }
+        }
+
+        let joined_rows = plan_join(&lhs, &rhs, column).join_rows(&lhs, &lhs_core_column_values, &rhs, &rhs_core_column_values);
+        for (core_column_value, lhs_row_index, rhs_row_index) in joined_rows {
+            let mut row_values: Vec<String> = Vec::new();
+            row_values.push(core_column_value.clone());
 
             match lhs_row_index {
                 Some(index) => {
@@ -504,6 +959,27 @@ impl DatabaseManager {
                                 },
                                 core::types::ValueType::Email(email) => {
                                     email.get_value().to_string()
+                                },
+                                core::types::ValueType::Bool(bool) => {
+                                    bool.get_value().to_string()
+                                },
+                                core::types::ValueType::Decimal(decimal) => {
+                                    decimal.to_string()
+                                },
+                                core::types::ValueType::IntInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::RealInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::DateInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::Enum(enumeration) => {
+                                    enumeration.get_value().to_owned()
+                                },
+                                core::types::ValueType::Null(_) => {
+                                    "NULL".to_owned()
                                 }
                             }
                         )
@@ -547,6 +1023,27 @@ impl DatabaseManager {
                                 },
                                 core::types::ValueType::Email(email) => {
                                     email.get_value().to_string()
+                                },
+                                core::types::ValueType::Bool(bool) => {
+                                    bool.get_value().to_string()
+                                },
+                                core::types::ValueType::Decimal(decimal) => {
+                                    decimal.to_string()
+                                },
+                                core::types::ValueType::IntInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::RealInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::DateInterval(interval) => {
+                                    interval.to_string()
+                                },
+                                core::types::ValueType::Enum(enumeration) => {
+                                    enumeration.get_value().to_owned()
+                                },
+                                core::types::ValueType::Null(_) => {
+                                    "NULL".to_owned()
                                 }
                             }
                         )
@@ -574,18 +1071,79 @@ impl DatabaseManager {
         }
         let db = self.database.borrow();
         let db_unwrapped = db.as_ref().unwrap();
-        let res = match db_unwrapped.get_tables_mut().get_mut(table_name) {
-            Some(table) => {
-                let scheme = table.get_scheme_mut();
-                if scheme.get_columns().len() != new_columns_names.len() {
-                    return Err("wrong number of tables".to_string());
-                }
-                scheme.set_columns(new_columns_names);
-                Ok(())
-            },
-            None => Err(format!("There is no table with name {}", table_name))
+        db_unwrapped.rename_columns(table_name, new_columns_names)?;
+        self.refresh_views(db_unwrapped.get_materialized_views_depending_on(table_name));
+        Ok(())
+    }
+    pub fn rename_table(&self, table_name: &str, new_name: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        db.as_ref().unwrap().rename_table(table_name, new_name.trim())
+    }
+    pub fn rename_column(&self, table_name: &str, column: &str, new_name: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        db.as_ref().unwrap().rename_column(table_name, column.trim(), new_name.trim())?;
+        self.refresh_views(db.as_ref().unwrap().get_materialized_views_depending_on(table_name));
+        Ok(())
+    }
+    // `declaration` is written as in `create_table`, e.g. `IntValue default(0)`; the column goes
+    // to the end of the table unless `position` is given
+    pub fn add_column(&self, table_name: &str, column: &str, declaration: &str, position: Option<usize>) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let definition = ColumnDefinition::parse(column.trim(), declaration)?;
+        let value_generator = match self.supported_types.get(&definition.ty) {
+            Some(value_generator) => value_generator.clone(),
+            None => return Err(format!("No such supported data type: {}", definition.ty))
         };
-        res
+        let db = self.database.borrow();
+        db.as_ref().unwrap().add_column(table_name, definition, value_generator, position)?;
+        self.refresh_views(db.as_ref().unwrap().get_materialized_views_depending_on(table_name));
+        Ok(())
+    }
+    pub fn drop_column(&self, table_name: &str, column: &str) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let db = self.database.borrow();
+        db.as_ref().unwrap().drop_column(table_name, column.trim())?;
+        self.refresh_views(db.as_ref().unwrap().get_materialized_views_depending_on(table_name));
+        Ok(())
+    }
+    pub fn reorder_columns(&self, table_name: &str, columns: Vec<&str>) -> Result<(), String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let columns = columns.iter().map(|column| column.trim().to_string()).collect::<Vec<String>>();
+        let db = self.database.borrow();
+        db.as_ref().unwrap().reorder_columns(table_name, &columns)?;
+        self.refresh_views(db.as_ref().unwrap().get_materialized_views_depending_on(table_name));
+        Ok(())
+    }
+    // changes the type of the column to `declaration`, converting its cells; `on_failure` is abort,
+    // null or drop, and a dry run changes nothing but reports the rows that can't be converted
+    pub fn retype_column(&self, table_name: &str, column: &str, declaration: &str, on_failure: &str, dry_run: bool) -> Result<ConversionReport, String> {
+        if self.database.borrow().is_none() {
+            return Err("There is no active databases in db-manager manager".to_string());
+        }
+        let definition = ColumnDefinition::parse(column.trim(), declaration)?;
+        let value_generator = match self.supported_types.get(&definition.ty) {
+            Some(value_generator) => value_generator.clone(),
+            None => return Err(format!("No such supported data type: {}", definition.ty))
+        };
+        let on_failure = OnConversionFailure::parse(on_failure)?;
+        let db = self.database.borrow();
+        let report = db.as_ref().unwrap().retype_column(table_name, definition, value_generator, on_failure, dry_run)?;
+        if !dry_run {
+            self.refresh_views(db.as_ref().unwrap().get_materialized_views_depending_on(table_name));
+        }
+        Ok(report)
     }
 
     pub fn get_database_name(&self) -> String{
@@ -601,8 +1159,909 @@ impl Drop for DatabaseManager {
 
 #[cfg(test)]
 mod tests {
+    use core::index::IndexKind;
+    use core::planner::{Operation, PlanNode};
+    use std::rc::Rc;
+    use core::row::Row;
+    use core::table::Table;
+    use core::types::CellValue;
+    use core::types::ValueType;
+    use core::types::int_value::IntValue;
+    use core::types::string_value::StringValue;
+    use db_api::db::DatabaseDTO;
+    use db_api::format::MAGIC;
+    use db_api::pager::{Pager, PAGE_HEADER_SIZE, PAGE_SIZE};
+    use db_api::row_dto::RowDTO;
+    use db_api::scheme_dto::SchemeDTO;
+    use db_api::table::TableDTO;
+    use super::DatabaseManager;
+
+    fn open_test_db(name: &str) -> DatabaseManager {
+        let manager = DatabaseManager::new();
+        let location = std::env::temp_dir();
+        manager.create_db(name, location.to_str().unwrap()).unwrap();
+        manager
+    }
+
+    fn drop_test_db(manager: DatabaseManager, name: &str) {
+        let location = std::env::temp_dir();
+        manager.close_db(false).unwrap();
+        manager.delete_db(location.to_str().unwrap(), name).unwrap();
+    }
+
     #[test]
     fn test_creating_db_manager() {
         // DatabaseManager::new();
     }
+
+    #[test]
+    fn test_enum_column_rejects_values_outside_domain() {
+        let manager = open_test_db("test_enum_column.db");
+        manager.create_table("cars", vec!["id", "color"], vec!["IntValue", "Enum(red, green, blue)"]).unwrap();
+        assert_eq!(manager.get_table("cars").unwrap().get_scheme().get_types()[1], "EnumValue");
+        assert!(manager.add_row("cars", "1;green").is_ok());
+        assert!(manager.add_row("cars", "2;purple").is_err());
+        assert_eq!(manager.get_table("cars").unwrap().get_rows().len(), 1);
+        assert!(manager.create_table("bikes", vec!["color"], vec!["EnumValue"]).is_err());
+        drop_test_db(manager, "test_enum_column.db");
+    }
+
+    #[test]
+    fn test_decimal_join_keys_are_normalized() {
+        let manager = open_test_db("test_decimal_join.db");
+        manager.create_table("prices", vec!["price", "item"], vec!["DecimalValue(6, 2)", "StringValue"]).unwrap();
+        manager.create_table("discounts", vec!["price", "discount"], vec!["DecimalValue", "StringValue"]).unwrap();
+        manager.add_row("prices", "12.50;apple").unwrap();
+        manager.add_row("discounts", "12.5;5%").unwrap();
+        let joined = manager.join("prices", "discounts", "price").unwrap();
+        let rows = joined.get_rows()
+            .iter()
+            .map(|row| row.get_values()
+                .iter()
+                .map(|value| match value.get_value() {
+                    ValueType::Decimal(value) => value.to_string(),
+                    ValueType::Str(value) => value.get_value().to_string(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<String>>())
+            .collect::<Vec<Vec<String>>>();
+        assert_eq!(rows, [["12.5", "apple", "5%"]]);
+        drop_test_db(manager, "test_decimal_join.db");
+    }
+
+    fn cells(table: &Table) -> Vec<Vec<String>> {
+        table.get_rows()
+            .iter()
+            .map(|row| row.get_values()
+                .iter()
+                .map(|value| value.get_value().to_raw_value().unwrap_or("NULL".to_string()))
+                .collect())
+            .collect()
+    }
+
+    // what a database is made of, built by the first function and checked by the second one
+    // after the database is saved and opened again
+    type RoundTrip = (&'static str, fn(&DatabaseManager), fn(&DatabaseManager));
+
+    const ROUND_TRIPS: [RoundTrip; 13] = [
+        ("column constraints", |manager| {
+            manager.create_table(
+                "people",
+                vec!["name", "age", "nickname"],
+                vec!["StringValue(16) not_null", "IntValue range(0, 120) default(18)", "StringValue"],
+            ).unwrap();
+            manager.add_row("people", "name=Taras").unwrap();
+        }, |manager| {
+            assert_eq!(cells(&manager.get_table("people").unwrap()), [["Taras", "18", "NULL"]]);
+            assert!(manager.add_row("people", "name=Petro;age=121").is_err());
+            assert!(manager.add_row("people", "NULL;20;null").is_err());
+        }),
+        ("keys", |manager| {
+            manager.create_table_with_keys(
+                "users",
+                vec!["id", "email"],
+                vec!["IntValue", "StringValue"],
+                vec!["id"],
+                vec![vec!["email"]],
+            ).unwrap();
+            manager.add_row("users", "1;a@ukr.net").unwrap();
+        }, |manager| {
+            assert_eq!(manager.get_table("users").unwrap().get_scheme().get_primary_key(), ["id"]);
+            assert!(manager.add_row("users", "1;b@ukr.net").is_err());
+            assert!(manager.add_row("users", "2;a@ukr.net").is_err());
+        }),
+        ("foreign keys", |manager| {
+            manager.create_table_with_keys("users", vec!["id"], vec!["IntValue"], vec!["id"], Vec::default()).unwrap();
+            manager.create_table("orders", vec!["id", "user_id"], vec!["IntValue", "IntValue"]).unwrap();
+            manager.add_row("users", "1").unwrap();
+            manager.add_row("orders", "10;1").unwrap();
+            manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "cascade").unwrap();
+        }, |manager| {
+            assert_eq!(
+                manager.get_foreign_keys().unwrap().iter().map(|foreign_key| foreign_key.to_string()).collect::<Vec<String>>(),
+                ["orders(user_id) -> users(id) ON DELETE CASCADE"]
+            );
+            assert!(manager.add_row("orders", "30;3").is_err());
+            manager.delete_row("users", 0).unwrap();
+            assert!(manager.get_table("orders").unwrap().get_rows().is_empty());
+        }),
+        ("sequences", |manager| {
+            manager.create_table("users", vec!["id", "name"], vec!["IntValue auto_increment", "StringValue"]).unwrap();
+            manager.add_row("users", "10;Ivan").unwrap();
+            manager.add_row("users", ";Lesya").unwrap();
+            manager.delete_row("users", 1).unwrap();
+        }, |manager| {
+            manager.add_row("users", ";Marko").unwrap();
+            assert_eq!(cells(&manager.get_table("users").unwrap()), [["10", "Ivan"], ["12", "Marko"]]);
+        }),
+        ("quoted values", |manager| {
+            manager.create_table("notes", vec!["title", "body"], vec!["StringValue", "StringValue"]).unwrap();
+            manager.add_row("notes", r#""a; b";'  padded  '"#).unwrap();
+            manager.add_row("notes", r#""";NULL"#).unwrap();
+        }, |manager| {
+            assert_eq!(cells(&manager.get_table("notes").unwrap()), [["a; b", "  padded  "], ["", "NULL"]]);
+        }),
+        ("altered columns", |manager| {
+            manager.create_table("people", vec!["name", "email"], vec!["StringValue", "StringValue"]).unwrap();
+            manager.add_row("people", "Illia;i@ukr.net").unwrap();
+            manager.add_column("people", "age", "IntValue default(18)", None).unwrap();
+            manager.add_column("people", "id", "IntValue auto_increment", Some(0)).unwrap();
+            manager.drop_column("people", "email").unwrap();
+            manager.reorder_columns("people", vec!["id", "age", "name"]).unwrap();
+            manager.rename_column("people", "name", "first_name").unwrap();
+        }, |manager| {
+            manager.add_row("people", "first_name=Ivan").unwrap();
+            let table = manager.get_table("people").unwrap();
+            assert_eq!(table.get_columns(), ["id", "age", "first_name"]);
+            assert_eq!(cells(&table), [["1", "18", "Illia"], ["2", "18", "Ivan"]]);
+        }),
+        ("retyped column", |manager| {
+            manager.create_table("people", vec!["name", "age"], vec!["StringValue", "StringValue"]).unwrap();
+            manager.add_row("people", "Illia;21").unwrap();
+            manager.retype_column("people", "age", "IntValue default(18)", "null", false).unwrap();
+        }, |manager| {
+            manager.add_row("people", "name=Lesya").unwrap();
+            assert!(manager.add_row("people", "Marko;old").is_err());
+            let table = manager.get_table("people").unwrap();
+            assert_eq!(table.get_scheme().get_types(), ["StringValue", "IntValue"]);
+            assert_eq!(cells(&table), [["Illia", "21"], ["Lesya", "18"]]);
+        }),
+        ("renamed table", |manager| {
+            manager.create_table_with_keys("users", vec!["id"], vec!["IntValue"], vec!["id"], Vec::default()).unwrap();
+            manager.create_table("orders", vec!["id", "user_id"], vec!["IntValue", "IntValue"]).unwrap();
+            manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "restrict").unwrap();
+            manager.rename_table("users", "customers").unwrap();
+        }, |manager| {
+            assert!(manager.get_table("users").is_err());
+            assert_eq!(manager.get_table("customers").unwrap().get_name(), "customers");
+            assert_eq!(
+                manager.get_foreign_keys().unwrap().iter().map(|foreign_key| foreign_key.to_string()).collect::<Vec<String>>(),
+                ["orders(user_id) -> customers(id) ON DELETE RESTRICT"]
+            );
+        }),
+        ("computed columns", |manager| {
+            manager.create_table(
+                "orders",
+                vec!["price", "qty", "total"],
+                vec!["RealValue", "IntValue", "RealValue computed(price * qty)"],
+            ).unwrap();
+            manager.add_row("orders", "2.5;4").unwrap();
+            manager.rename_column("orders", "qty", "quantity").unwrap();
+        }, |manager| {
+            manager.add_row("orders", "3;NULL").unwrap();
+            let table = manager.get_table("orders").unwrap();
+            assert_eq!(table.get_scheme().get_column_definition(2).unwrap().get_expression(), Some("price * quantity"));
+            assert_eq!(cells(&table), [["2.5", "4", "10"], ["3", "NULL", "NULL"]]);
+        }),
+        ("views", |manager| {
+            manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+            manager.create_table("pets", vec!["pet", "name"], vec!["StringValue", "StringValue"]).unwrap();
+            manager.add_row("people", "Illia;21").unwrap();
+            manager.create_view("adults", "people", vec!["name"], Some("age >= 18"), Some("age desc")).unwrap();
+            manager.create_join_view("owners", "people", "pets", "name", Vec::default(), None, None).unwrap();
+        }, |manager| {
+            assert_eq!(manager.get_view_list(), ["adults", "owners"]);
+            manager.add_row("people", "Ivan;40").unwrap();
+            assert_eq!(cells(&manager.get_view("adults").unwrap()), [["Ivan"], ["Illia"]]);
+            assert_eq!(manager.get_view("owners").unwrap().get_columns(), ["name", "age", "pet"]);
+        }),
+        ("materialized views", |manager| {
+            manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+            manager.add_row("people", "Illia;21").unwrap();
+            manager.create_view("adults", "people", vec!["name"], Some("age >= 18"), None).unwrap();
+            manager.create_view("everyone", "people", vec!["name"], None, None).unwrap();
+            manager.materialize_view("adults", "incremental").unwrap();
+            manager.materialize_view("everyone", "on_demand").unwrap();
+            manager.add_row("people", "Ivan;40").unwrap();
+        }, |manager| {
+            assert_eq!(cells(&manager.get_view("adults").unwrap()), [["Illia"], ["Ivan"]]);
+            assert_eq!(manager.get_view_marker("everyone"), Some("MV stale".to_string()));
+            assert_eq!(cells(&manager.get_view("everyone").unwrap()), [["Illia"]]);
+        }),
+        ("indexes", |manager| {
+            manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+            manager.create_index("people", "by_age", vec!["age"], "btree").unwrap();
+            manager.create_index("people", "by_name", vec!["name"], "hash").unwrap();
+            manager.rename_column("people", "age", "years").unwrap();
+        }, |manager| {
+            let indexes = manager.get_indexes("people").unwrap()
+                .iter()
+                .map(|index| (index.name.clone(), index.kind, index.columns.clone()))
+                .collect::<Vec<(String, IndexKind, Vec<String>)>>();
+            assert_eq!(indexes, [
+                ("by_age".to_string(), IndexKind::BTree, vec!["years".to_string()]),
+                ("by_name".to_string(), IndexKind::Hash, vec!["name".to_string()]),
+            ]);
+        }),
+        ("full-text indexes", |manager| {
+            manager.create_table("notes", vec!["id", "text"], vec!["IntValue", "StringValue"]).unwrap();
+            manager.add_row("notes", "1;Feed the cat").unwrap();
+            manager.create_index("notes", "by_text", vec!["text"], "fulltext").unwrap();
+        }, |manager| {
+            assert_eq!(manager.get_indexes("notes").unwrap()[0].kind, IndexKind::FullText);
+            manager.add_row("notes", "2;Walk the dog").unwrap();
+            assert_eq!(cells(&manager.search("notes", "dog").unwrap()), [["2", "Walk the dog"]]);
+        }),
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        let location = std::env::temp_dir();
+        let dir = location.to_str().unwrap();
+        for (description, build, check) in ROUND_TRIPS {
+            let name = format!("test_round_trip_{}.db", description.replace([' ', '-'], "_"));
+            let manager = open_test_db(&name);
+            build(&manager);
+            manager.close_db(true).unwrap();
+            manager.read_db_from_directory(dir, &name).unwrap();
+            check(&manager);
+            drop_test_db(manager, &name);
+        }
+    }
+
+    #[test]
+    fn test_column_constraints_are_enforced() {
+        let name = "test_column_constraints.db";
+        let manager = open_test_db(name);
+        manager.create_table(
+            "people",
+            vec!["name", "age", "nickname"],
+            vec!["StringValue(16) not_null", "IntValue range(0, 120) default(18)", "StringValue"],
+        ).unwrap();
+        assert!(manager.add_row("people", "Illia;21;illia").is_ok());
+        assert!(manager.add_row("people", "name=Taras").is_ok());
+        assert!(manager.add_row("people", "Ivan;NULL;null").is_ok());
+        assert!(manager.add_row("people", "Petro;121;petro").is_err());
+        assert!(manager.add_row("people", "NULL;20;null").is_err());
+        assert!(manager.create_table("broken", vec!["age"], vec!["IntValue range(0, 120) default(200)"]).is_err());
+        assert_eq!(cells(&manager.get_table("people").unwrap()), [
+            ["Illia", "21", "illia"],
+            ["Taras", "18", "NULL"],
+            ["Ivan", "NULL", "NULL"],
+        ]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_keys_are_enforced() {
+        let name = "test_table_keys.db";
+        let manager = open_test_db(name);
+        manager.create_table_with_keys(
+            "users",
+            vec!["id", "email", "nickname"],
+            vec!["IntValue", "StringValue", "StringValue"],
+            vec!["id"],
+            vec![vec!["email"], vec!["nickname"]],
+        ).unwrap();
+        assert!(manager.create_table_with_keys("broken", vec!["id"], vec!["IntValue"], vec!["uuid"], Vec::default()).is_err());
+        assert!(manager.add_row("users", "1;a@ukr.net;a").is_ok());
+        assert!(manager.add_row("users", "2;b@ukr.net;NULL").is_ok());
+        assert!(manager.add_row("users", "3;c@ukr.net;NULL").is_ok());
+        assert!(manager.add_row("users", "1;d@ukr.net;d").is_err());
+        assert!(manager.add_row("users", "4;a@ukr.net;d").is_err());
+        assert!(manager.update_row("users", 2, "3;c@ukr.net;c").is_ok());
+        assert!(manager.update_row("users", 2, "2;c@ukr.net;c").is_err());
+        assert!(manager.update_row("users", 7, "7;g@ukr.net;g").is_err());
+        assert_eq!(manager.get_table("users").unwrap().get_rows().len(), 3);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_foreign_keys_are_enforced() {
+        let name = "test_foreign_keys.db";
+        let manager = open_test_db(name);
+        manager.create_table_with_keys("users", vec!["id", "name"], vec!["IntValue", "StringValue"], vec!["id"], Vec::default()).unwrap();
+        manager.create_table_with_keys("orders", vec!["id", "user_id"], vec!["IntValue", "IntValue"], vec!["id"], Vec::default()).unwrap();
+        manager.add_row("users", "1;Illia").unwrap();
+        manager.add_row("users", "2;Taras").unwrap();
+        manager.add_row("orders", "10;1").unwrap();
+        manager.add_row("orders", "20;2").unwrap();
+        assert!(manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "ignore").is_err());
+        assert!(manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["name"], "cascade").is_err());
+        manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "cascade").unwrap();
+        assert!(manager.add_row("orders", "30;3").is_err());
+        assert!(manager.update_row("orders", 1, "20;3").is_err());
+        manager.delete_row("users", 0).unwrap();
+        assert_eq!(manager.get_table("orders").unwrap().get_rows().len(), 1);
+        manager.delete_table("users").unwrap();
+        assert!(manager.get_foreign_keys().unwrap().is_empty());
+        assert_eq!(manager.get_table("orders").unwrap().get_rows().len(), 0);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_add_row_by_column_names() {
+        let name = "test_named_insert.db";
+        let manager = open_test_db(name);
+        manager.create_table(
+            "people",
+            vec!["id", "name", "email", "age"],
+            vec!["IntValue auto_increment", "StringValue not_null", "StringValue", "IntValue default(18)"],
+        ).unwrap();
+        manager.add_row("people", "name=Bob;email=b@x.io").unwrap();
+        manager.add_row("people", " age = 30 ; name = Alice ").unwrap();
+        manager.add_row("people", ";Taras;t@ukr.net;40").unwrap();
+        assert!(manager.add_row("people", "email=nobody@x.io").is_err());
+        assert!(manager.add_row("people", "name=Bob;name=Rob").is_err());
+        assert!(manager.add_row("people", "name=Bob;phone=123").is_err());
+        assert!(manager.add_row("people", ";Ivan").is_err());
+        assert!(manager.add_row("people", ";Ivan;i@ukr.net;20;extra").is_err());
+        manager.update_row("people", 1, "email=a@x.io").unwrap();
+
+        let table = manager.get_table("people").unwrap();
+        let rows = table.get_rows();
+        assert_eq!(rows.len(), 3);
+        let cells = |row: usize| rows[row].get_values()
+            .iter()
+            .map(|value| match value.get_value() {
+                ValueType::Int(value) => value.get_value().to_string(),
+                ValueType::Str(value) => value.get_value().to_string(),
+                ValueType::Null(_) => "NULL".to_string(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(cells(0), ["1", "Bob", "b@x.io", "18"]);
+        assert_eq!(cells(1), ["2", "Alice", "a@x.io", "30"]);
+        assert_eq!(cells(2), ["3", "Taras", "t@ukr.net", "40"]);
+        drop(rows);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_add_row_with_quoted_values() {
+        let name = "test_quoted_values.db";
+        let manager = open_test_db(name);
+        manager.create_table("notes", vec!["id", "title", "body"], vec!["IntValue", "StringValue", "StringValue"]).unwrap();
+        manager.add_row("notes", r#"1;"a; b";'  padded  '"#).unwrap();
+        manager.add_row("notes", r#"2;"";NULL"#).unwrap();
+        manager.add_row("notes", r#"3;"NULL";semi\;colon"#).unwrap();
+        manager.add_row("notes", r#"id=4;title="x=y";body='say "hi"'"#).unwrap();
+        assert!(manager.add_row("notes", r#"5;"unterminated;x"#).is_err());
+        assert!(manager.add_row("notes", "id=5;title;body").is_err());
+        let table = manager.get_table("notes").unwrap();
+        let cells = table.get_rows()
+            .iter()
+            .map(|row| row.get_values()[1..]
+                .iter()
+                .map(|value| match value.get_value() {
+                    ValueType::Str(value) => value.get_value().to_string(),
+                    ValueType::Null(_) => "<null>".to_string(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<String>>())
+            .collect::<Vec<Vec<String>>>();
+        assert_eq!(cells, [
+            ["a; b", "  padded  "],
+            ["", "<null>"],
+            ["NULL", "semi;colon"],
+            ["x=y", "say \"hi\""],
+        ]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_import_rows() {
+        let name = "test_import_rows.db";
+        let manager = open_test_db(name);
+        manager.create_table_with_keys("notes", vec!["id", "title"], vec!["IntValue", "StringValue"], vec!["id"], Vec::default()).unwrap();
+        manager.add_row("notes", "1;first").unwrap();
+        let rows = "2;'  padded  '\n\nid=3;title=\"a; b\"\n";
+        assert_eq!(manager.import_rows("notes", rows), Ok(2));
+        // the second line breaks the key, so the first one isn't kept either
+        assert_eq!(manager.import_rows("notes", "4;four\n1;again").unwrap_err(), "line 2: primary key (id) of table notes is violated by the row 0");
+        assert!(manager.import_rows("notes", "5;\"unterminated").is_err());
+        assert_eq!(cells(&manager.get_table("notes").unwrap()), [["1", "first"], ["2", "  padded  "], ["3", "a; b"]]);
+        // the string builder still trims what doesn't come through a row literal
+        assert_eq!(core::types::SUPPORTED_TYPES.get("StringValue").unwrap()("  trimmed ".to_string()).unwrap().get_value().to_raw_value(), Some("trimmed".to_string()));
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let name = "test_alter_table.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "email"], vec!["StringValue", "StringValue"]).unwrap();
+        manager.add_row("people", "Illia;i@ukr.net").unwrap();
+        manager.add_row("people", "Taras;t@ukr.net").unwrap();
+        assert!(manager.add_column("people", "age", "IntValue not_null", None).is_err());
+        assert!(manager.add_column("people", "age", "AgeValue", None).is_err());
+        manager.add_column("people", "age", "IntValue default(18)", None).unwrap();
+        manager.add_column("people", "id", "IntValue auto_increment", Some(0)).unwrap();
+        manager.drop_column("people", "email").unwrap();
+        manager.reorder_columns("people", vec!["id", "age", "name"]).unwrap();
+        manager.rename_column("people", "name", "first_name").unwrap();
+        manager.add_row("people", "first_name=Ivan").unwrap();
+        let table = manager.get_table("people").unwrap();
+        assert_eq!(table.get_columns(), ["id", "age", "first_name"]);
+        assert_eq!(cells(&table), [["1", "18", "Illia"], ["2", "18", "Taras"], ["3", "18", "Ivan"]]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_retype_column() {
+        let name = "test_retype_column.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "StringValue"]).unwrap();
+        manager.add_row("people", "Illia;21").unwrap();
+        manager.add_row("people", "Taras;unknown").unwrap();
+        manager.add_row("people", "Ivan;NULL").unwrap();
+        let report = manager.retype_column("people", "age", "IntValue", "abort", true).unwrap();
+        assert_eq!(report.get_failed_rows(), [1]);
+        assert!(manager.retype_column("people", "age", "IntValue", "abort", false).is_err());
+        assert!(manager.retype_column("people", "age", "IntValue", "ignore", false).is_err());
+        assert!(manager.retype_column("people", "height", "IntValue", "abort", false).is_err());
+        manager.retype_column("people", "age", "IntValue default(18)", "null", false).unwrap();
+        manager.add_row("people", "name=Lesya").unwrap();
+        assert!(manager.add_row("people", "Marko;old").is_err());
+        let table = manager.get_table("people").unwrap();
+        assert_eq!(table.get_scheme().get_types(), ["StringValue", "IntValue"]);
+        assert_eq!(cells(&table), [["Illia", "21"], ["Taras", "NULL"], ["Ivan", "NULL"], ["Lesya", "18"]]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_rename_table() {
+        let name = "test_rename_table.db";
+        let manager = open_test_db(name);
+        manager.create_table_with_keys("users", vec!["id"], vec!["IntValue"], vec!["id"], Vec::default()).unwrap();
+        manager.create_table("orders", vec!["id", "user_id"], vec!["IntValue", "IntValue"]).unwrap();
+        manager.add_row("users", "1").unwrap();
+        manager.add_row("orders", "10;1").unwrap();
+        manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "restrict").unwrap();
+        assert!(manager.rename_table("users", "orders").is_err());
+        manager.rename_table("users", "customers").unwrap();
+        assert!(manager.get_table("users").is_err());
+        assert_eq!(
+            manager.get_foreign_keys().unwrap().iter().map(|foreign_key| foreign_key.to_string()).collect::<Vec<String>>(),
+            ["orders(user_id) -> customers(id) ON DELETE RESTRICT"]
+        );
+        assert!(manager.add_row("orders", "20;2").is_err());
+        assert!(manager.delete_row("customers", 0).is_err());
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_sequences() {
+        let name = "test_sequences.db";
+        let manager = open_test_db(name);
+        manager.create_table_with_keys("users", vec!["id", "name"], vec!["IntValue auto_increment", "StringValue"], vec!["id"], Vec::default()).unwrap();
+        assert!(manager.create_table("broken", vec!["id"], vec!["RealValue auto_increment"]).is_err());
+        manager.add_row("users", ";Illia").unwrap();
+        manager.add_row("users", ";Taras").unwrap();
+        manager.add_row("users", "10;Ivan").unwrap();
+        manager.add_row("users", ";Lesya").unwrap();
+        manager.delete_row("users", 3).unwrap();
+        manager.rename("users", vec!["user_id".to_string(), "name".to_string()]).unwrap();
+        manager.add_row("users", ";Marko").unwrap();
+        let ids = cells(&manager.get_table("users").unwrap()).into_iter().map(|row| row[0].clone()).collect::<Vec<String>>();
+        assert_eq!(ids, ["1", "2", "10", "12"]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_computed_columns() {
+        let name = "test_computed_columns.db";
+        let manager = open_test_db(name);
+        manager.create_table(
+            "orders",
+            vec!["item", "price", "qty", "total"],
+            vec!["StringValue", "RealValue", "IntValue default(1)", "RealValue computed(price * qty)"],
+        ).unwrap();
+        assert!(manager.create_table("broken", vec!["total"], vec!["RealValue computed(price * qty)"]).is_err());
+        manager.add_row("orders", "pen;2.5;4").unwrap();
+        manager.add_row("orders", "item=book;price=10").unwrap();
+        assert!(manager.add_row("orders", "cup;3;1;3").is_err());
+        assert!(manager.add_row("orders", "item=cup;total=3").is_err());
+        manager.update_row("orders", 0, "qty=2").unwrap();
+        manager.rename_column("orders", "qty", "quantity").unwrap();
+        manager.add_row("orders", "cup;3;NULL").unwrap();
+        let table = manager.get_table("orders").unwrap();
+        assert_eq!(table.get_scheme().get_column_definition(3).unwrap().get_expression(), Some("price * quantity"));
+        let totals = cells(&table).into_iter().map(|row| row[3].clone()).collect::<Vec<String>>();
+        assert_eq!(totals, ["5", "10", "NULL"]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_views() {
+        let name = "test_views.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        manager.create_table("pets", vec!["pet", "name"], vec!["StringValue", "StringValue"]).unwrap();
+        manager.add_row("people", "Illia;21").unwrap();
+        manager.add_row("people", "Taras;16").unwrap();
+        manager.add_row("pets", "Rex;Illia").unwrap();
+        manager.create_view("adults", "people", vec!["name"], Some("age >= 18"), Some("age desc")).unwrap();
+        manager.create_join_view("owners", "people", "pets", "name", Vec::default(), None, None).unwrap();
+        assert!(manager.create_view("people", "pets", Vec::default(), None, None).is_err());
+        assert!(manager.create_view("broken", "people", vec!["email"], None, None).is_err());
+        assert!(manager.create_view("broken", "people", Vec::default(), Some("age >"), None).is_err());
+        assert!(manager.create_view("broken", "people", Vec::default(), None, Some("age sideways")).is_err());
+        assert!(manager.create_table("adults", vec!["id"], vec!["IntValue"]).is_err());
+        assert!(manager.delete_table("people").is_err());
+        assert!(manager.drop_column("people", "age").is_err());
+        manager.rename_column("people", "age", "years").unwrap();
+        manager.rename_table("people", "persons").unwrap();
+        manager.add_row("persons", "Ivan;40").unwrap();
+        let adults = manager.get_view("adults").unwrap();
+        assert_eq!(adults.get_columns(), ["name"]);
+        assert_eq!(cells(&adults), [["Ivan"], ["Illia"]]);
+        assert_eq!(manager.get_view("owners").unwrap().get_columns(), ["name", "years", "pet"]);
+        assert!(manager.add_row("adults", "Lesya").is_err());
+        manager.drop_view("adults").unwrap();
+        manager.drop_view("owners").unwrap();
+        assert!(manager.get_view("adults").is_err());
+        manager.delete_table("persons").unwrap();
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_materialized_views() {
+        let name = "test_materialized_views.db";
+        let manager = open_test_db(name);
+        let names = |manager: &DatabaseManager, view_name: &str| cells(&manager.get_view(view_name).unwrap())
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<String>>();
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        manager.create_table("pets", vec!["pet", "name"], vec!["StringValue", "StringValue"]).unwrap();
+        manager.add_row("people", "Illia;21").unwrap();
+        manager.add_row("people", "Taras;16").unwrap();
+        manager.add_row("pets", "Rex;Illia").unwrap();
+        manager.create_view("adults", "people", vec!["name"], Some("age >= 18"), None).unwrap();
+        manager.create_join_view("owners", "people", "pets", "name", Vec::default(), None, None).unwrap();
+        assert!(manager.materialize_view("adults", "sometimes").is_err());
+        assert!(manager.refresh_view("adults").is_err());
+        manager.materialize_view("adults", "incremental").unwrap();
+        manager.materialize_view("owners", "on_demand").unwrap();
+        assert_eq!(manager.get_view_list(), ["adults", "owners"]);
+        assert!(manager.create_table("owners", vec!["id"], vec!["IntValue"]).is_err());
+        assert!(manager.delete_table("pets").is_err());
+
+        manager.add_row("people", "Ivan;40").unwrap();
+        manager.add_row("people", "Lesya;9").unwrap();
+        manager.add_row("pets", "Tom;Ivan").unwrap();
+        assert_eq!(names(&manager, "adults"), ["Illia", "Ivan"]);
+        assert_eq!(names(&manager, "owners"), ["Illia", "Taras"]);
+        assert_eq!(manager.get_view_marker("adults"), Some("MV".to_string()));
+        assert_eq!(manager.get_view_marker("owners"), Some("MV stale".to_string()));
+        manager.refresh_view("owners").unwrap();
+        assert_eq!(manager.get_view_marker("owners"), Some("MV".to_string()));
+        assert_eq!(names(&manager, "owners"), ["Illia", "Ivan", "Lesya", "Taras"]);
+        manager.delete_row("people", 0).unwrap();
+        manager.update_row("people", 2, "Lesya;19").unwrap();
+        assert_eq!(names(&manager, "adults"), ["Ivan", "Lesya"]);
+        manager.drop_view("adults").unwrap();
+        manager.drop_view("owners").unwrap();
+        assert!(manager.get_view("owners").is_err());
+        manager.delete_table("people").unwrap();
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_materialized_views_follow_their_sources() {
+        let name = "test_materialized_views_follow_their_sources.db";
+        let location = std::env::temp_dir();
+        let manager = open_test_db(name);
+        let names = |manager: &DatabaseManager, view_name: &str| cells(&manager.get_view(view_name).unwrap())
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<String>>();
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        for row in ["Illia;21", "Taras;16", "Ivan;40", "Lesya;9"] {
+            manager.add_row("people", row).unwrap();
+        }
+        manager.create_view("adults", "people", vec!["name"], Some("age >= 18"), None).unwrap();
+        manager.create_view("everyone", "people", vec!["name"], None, None).unwrap();
+        manager.materialize_view("adults", "incremental").unwrap();
+        manager.materialize_view("everyone", "on_demand").unwrap();
+
+        // the changes are replayed, the rows keep the order of the source
+        manager.delete_row("people", 0).unwrap();
+        manager.update_row("people", 2, "Lesya;19").unwrap();
+        manager.add_row("people", "Olena;30").unwrap();
+        manager.update_row("people", 1, "Ivan;17").unwrap();
+        assert_eq!(names(&manager, "adults"), ["Lesya", "Olena"]);
+        let people_version = manager.database.borrow().as_ref().unwrap().get_table_version("people").unwrap();
+        let adults = manager.database.borrow().as_ref().unwrap().get_materialized_view("adults").unwrap();
+        assert_eq!(adults.get_source_versions(), [("people".to_string(), people_version)]);
+        assert_eq!(adults.positions, Some(vec![2, 3]));
+        manager.close_db(true).unwrap();
+
+        // a source changed behind the views is caught on reading
+        manager.read_db_from_directory(location.to_str().unwrap(), name).unwrap();
+        assert_eq!(manager.get_view_marker("everyone"), Some("MV stale".to_string()));
+        manager.refresh_view("everyone").unwrap();
+        manager.database.borrow().as_ref().unwrap().get_table_mut("people").unwrap()
+            .add_row(Row::new(vec![Rc::new(StringValue::new("Roman".to_string())) as Rc<dyn CellValue>, Rc::new(IntValue::new(50))]));
+        manager.close_db(true).unwrap();
+        manager.read_db_from_directory(location.to_str().unwrap(), name).unwrap();
+        assert_eq!(manager.get_view_marker("everyone"), Some("MV stale".to_string()));
+        assert_eq!(manager.get_view_marker("adults"), Some("MV".to_string()));
+        assert_eq!(names(&manager, "adults"), ["Lesya", "Olena", "Roman"]);
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_indexes() {
+        let name = "test_indexes.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        manager.create_table("pets", vec!["pet", "name"], vec!["StringValue", "StringValue"]).unwrap();
+        for raw_values in ["Illia;21", "Taras;16", "Ivan;40", "Lesya;21"] {
+            manager.add_row("people", raw_values).unwrap();
+        }
+        manager.add_row("pets", "Rex;Ivan").unwrap();
+        manager.create_index("people", "by_age", vec!["age"], "btree").unwrap();
+        manager.create_index("pets", "by_name", vec!["name"], "hash").unwrap();
+        assert!(manager.create_index("people", "by_age", vec!["name"], "btree").is_err());
+        assert!(manager.create_index("people", "by_email", vec!["email"], "btree").is_err());
+        assert!(manager.create_index("people", "by_name", vec!["name"], "bitmap").is_err());
+        assert!(manager.drop_column("people", "age").is_err());
+        manager.rename_column("people", "age", "years").unwrap();
+        let sizes = manager.get_indexes_memory_sizes().unwrap();
+        assert_eq!(sizes.iter().map(|(table, _)| table.as_str()).collect::<Vec<&str>>(), ["people", "pets"]);
+        assert!(sizes.iter().all(|(_, size)| *size > 0));
+        manager.delete_row("people", 0).unwrap();
+        manager.update_row("people", 0, "Taras;21").unwrap();
+        manager.add_row("people", "Oksana;30").unwrap();
+        manager.create_view("adults", "people", vec!["name"], Some("years >= 21"), Some("years")).unwrap();
+        assert_eq!(cells(&manager.get_view("adults").unwrap()), [["Taras"], ["Lesya"], ["Oksana"], ["Ivan"]]);
+        let owners = manager.join("people", "pets", "name").unwrap();
+        assert_eq!(owners.get_rows().len(), 4);
+        manager.drop_view("adults").unwrap();
+        manager.drop_index("people", "by_age").unwrap();
+        assert!(manager.drop_index("people", "by_age").is_err());
+        manager.drop_column("people", "years").unwrap();
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_search() {
+        let name = "test_search.db";
+        let manager = open_test_db(name);
+        manager.create_table("notes", vec!["id", "text"], vec!["IntValue", "StringValue"]).unwrap();
+        for raw_values in ["1;Feed the cat", "2;Buy cat food and feed the cat", "3;Catalog the books", "4;Walk the dog"] {
+            manager.add_row("notes", raw_values).unwrap();
+        }
+        assert!(manager.search("notes", "cat").is_err());
+        assert!(manager.create_index("notes", "by_id", vec!["id"], "fulltext").is_err());
+        manager.create_index("notes", "by_text", vec!["text"], "fulltext").unwrap();
+        manager.delete_row("notes", 0).unwrap();
+        manager.update_row("notes", 2, "4;Walk the cat").unwrap();
+        let ids = |query: &str| cells(&manager.search("notes", query).unwrap())
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect::<Vec<String>>();
+        assert_eq!(ids("cat"), ["2", "4", "3"]);
+        assert_eq!(ids("the F"), ["2"]);
+        assert!(ids("dog").is_empty());
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_explain_shows_the_chosen_plan() {
+        let name = "test_explain.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        manager.create_table("pets", vec!["pet", "name"], vec!["StringValue", "StringValue"]).unwrap();
+        for number in 0..100 {
+            manager.add_row("people", &format!("n{:03};{}", number, number % 50)).unwrap();
+        }
+        manager.add_row("pets", "Rex;n007").unwrap();
+        manager.create_view("of_age", "people", vec!["name"], Some("age = 21"), Some("name")).unwrap();
+        manager.create_join_view("owners", "people", "pets", "name", vec![], None, None).unwrap();
+        // the steps from the top of the plan down to the rows of the table
+        let steps = |plan: PlanNode| {
+            let mut steps = vec![plan.operation.clone()];
+            let mut node = plan;
+            while let Some(child) = node.children.into_iter().next() {
+                steps.push(child.operation.clone());
+                node = child;
+            }
+            steps
+        };
+        assert_eq!(steps(manager.explain("of_age").unwrap()), [
+            Operation::Project { columns: vec!["name".to_string()] },
+            Operation::Sort { order: "name".to_string() },
+            Operation::Filter { condition: "age = 21".to_string() },
+            Operation::Scan { table: "people".to_string() },
+        ]);
+
+        manager.create_index("people", "by_age", vec!["age"], "hash").unwrap();
+        let of_age = steps(manager.explain("of_age").unwrap());
+        assert!(of_age.contains(&Operation::IndexLookup {
+            table: "people".to_string(),
+            index: "by_age".to_string(),
+            kind: IndexKind::Hash,
+            condition: "age = 21".to_string(),
+        }));
+        assert!(!of_age.contains(&Operation::Scan { table: "people".to_string() }));
+        assert_eq!(manager.get_view("of_age").unwrap().get_rows().len(), 2);
+        let owners = manager.explain("owners").unwrap();
+        assert_eq!(owners.operation, Operation::HashJoin { column: "name".to_string(), build: "pets".to_string() });
+        manager.create_join_view("rex_owners", "people", "pets", "name", vec!["pet"], Some("pet = 'Rex'"), None).unwrap();
+        assert_eq!(steps(manager.explain("rex_owners").unwrap())[..3], [
+            Operation::Project { columns: vec!["pet".to_string()] },
+            Operation::Filter { condition: "pet = 'Rex'".to_string() },
+            Operation::HashJoin { column: "name".to_string(), build: "pets".to_string() },
+        ]);
+        assert_eq!(manager.get_view("owners").unwrap().get_rows().len(), 100);
+        assert_eq!(manager.explain("pets").unwrap().operation, Operation::Scan { table: "pets".to_string() });
+        assert!(manager.explain("nothing").is_err());
+        drop_test_db(manager, name);
+    }
+
+    #[test]
+    fn test_stats_are_exported_and_planned_with() {
+        let name = "test_stats.db";
+        let manager = open_test_db(name);
+        manager.create_table("people", vec!["name", "age"], vec!["StringValue", "IntValue"]).unwrap();
+        for number in 0..100 {
+            manager.add_row("people", &format!("n{:03};{}", number, number % 20)).unwrap();
+        }
+        manager.add_row("people", "Taras;NULL").unwrap();
+        manager.create_view("young", "people", vec![], Some("age < 5"), None).unwrap();
+        assert_eq!(manager.explain("young").unwrap().rows.round(), 34.0);
+
+        let profile = manager.get_stats("people").unwrap();
+        assert_eq!(profile.rows, 101);
+        let age = profile.get_column("age").unwrap();
+        assert_eq!((age.nulls, age.distinct, age.max.as_deref()), (1, 20, Some("19")));
+        assert_eq!(manager.explain("young").unwrap().rows.round(), 27.0);
+        assert_eq!(manager.get_stats("young").unwrap().rows, 25);
+        assert!(manager.get_stats("nothing").is_err());
+
+        let path = std::env::temp_dir().join("test_stats.csv");
+        manager.export_stats("people", path.to_str().unwrap()).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv, profile.to_csv());
+        assert!(csv.contains("\nage,IntValue,1,20,0,19,"));
+        std::fs::remove_file(path).unwrap();
+        drop_test_db(manager, name);
+    }
+    #[test]
+    fn test_old_files_are_saved_paged() {
+        let name = "test_paged.db";
+        let location = std::env::temp_dir();
+        let path = location.join(name);
+        let old = DatabaseDTO::new(
+            name.to_string(),
+            location.to_str().unwrap().to_string(),
+            vec![TableDTO::new(
+                "notes".to_string(),
+                SchemeDTO::new(vec!["StringValue".to_string()], vec!["text".to_string()]),
+                vec![RowDTO::from(Rc::new(Row::new(vec![Rc::new(StringValue::new("old".to_string())) as Rc<dyn CellValue>])))],
+            )],
+        );
+        std::fs::write(&path, old.encode()).unwrap();
+
+        let manager = DatabaseManager::new();
+        manager.read_db_from_directory(location.to_str().unwrap(), name).unwrap();
+        for number in 0..2000 {
+            manager.add_row("notes", &format!("note {}", number)).unwrap();
+        }
+        manager.close_db(true).unwrap();
+        assert!(std::fs::read(&path).unwrap().starts_with(MAGIC));
+
+        // the pages of a dropped table are taken by the next one
+        manager.read_db_from_directory(location.to_str().unwrap(), name).unwrap();
+        assert_eq!(manager.get_table("notes").unwrap().get_rows().len(), 2001);
+        let size = std::fs::metadata(&path).unwrap().len();
+        manager.delete_table("notes").unwrap();
+        manager.create_table("drafts", vec!["text"], vec!["StringValue"]).unwrap();
+        for number in 0..2000 {
+            manager.add_row("drafts", &format!("memo {}", number)).unwrap();
+        }
+        manager.close_db(true).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), size);
+
+        manager.read_db_from_directory(location.to_str().unwrap(), name).unwrap();
+        assert!(manager.get_table("notes").is_err());
+        assert_eq!(manager.get_table("drafts").unwrap().get_rows()[1999].get_values()[0].get_value().to_raw_value(), Some("memo 1999".to_string()));
+        drop_test_db(manager, name);
+    }
+    #[test]
+    fn test_corrupt_tables_are_checked_and_salvaged() {
+        let name = "test_corrupt.db";
+        let location = std::env::temp_dir();
+        let dir = location.to_str().unwrap();
+        let manager = open_test_db(name);
+        manager.create_table("notes", vec!["text"], vec!["StringValue"]).unwrap();
+        manager.create_table("tags", vec!["tag"], vec!["StringValue"]).unwrap();
+        manager.add_row("notes", "first").unwrap();
+        manager.add_row("notes", "second").unwrap();
+        manager.add_row("tags", "todo").unwrap();
+        manager.close_db(true).unwrap();
+        assert!(!manager.check_db(dir, name, None).unwrap().is_corrupt());
+
+        // the last byte of the rows of notes belongs to "second"
+        let path = location.join(name);
+        let mut pager = Pager::open(path.to_str().unwrap()).unwrap();
+        let (_, directory) = DatabaseDTO::read_catalog(&mut pager).unwrap();
+        let rows_page = directory.iter().find(|pages| pages.name == "notes").unwrap().rows_page;
+        let rows_length = pager.read_chain(rows_page).unwrap().len();
+        drop(pager);
+        let mut data = std::fs::read(&path).unwrap();
+        data[rows_page as usize * PAGE_SIZE + PAGE_HEADER_SIZE + rows_length - 1] ^= 0xFF;
+        std::fs::write(&path, data).unwrap();
+
+        let report = manager.check_db(dir, name, None).unwrap();
+        assert_eq!(report.to_string(), "notes: 1 rows, corrupt: row 1 doesn't match its checksum\ntags: 1 rows, ok");
+        // the other tables can still be read
+        manager.read_db_from_directory(dir, name).unwrap();
+        assert_eq!(manager.get_table("tags").unwrap().get_rows().len(), 1);
+        assert!(manager.get_table("notes").unwrap_err().contains("record 1 doesn't match its checksum"));
+        manager.close_db(false).unwrap();
+
+        let salvaged = "test_corrupt_salvaged.db";
+        manager.check_db(dir, name, Some(salvaged)).unwrap();
+        manager.read_db_from_directory(dir, salvaged).unwrap();
+        assert_eq!(manager.get_table("notes").unwrap().get_rows().len(), 1);
+        drop_test_db(manager, salvaged);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_only_the_tables_needed_are_loaded() {
+        let name = "test_only_needed_tables.db";
+        let location = std::env::temp_dir();
+        let dir = location.to_str().unwrap();
+        let manager = open_test_db(name);
+        manager.create_table_with_keys("users", vec!["id"], vec!["IntValue"], vec!["id"], Vec::default()).unwrap();
+        manager.create_table("orders", vec!["id", "user_id"], vec!["IntValue", "IntValue"]).unwrap();
+        manager.create_table("notes", vec!["text"], vec!["StringValue"]).unwrap();
+        manager.add_foreign_key("orders", vec!["user_id"], "users", vec!["id"], "cascade").unwrap();
+        manager.add_row("users", "1").unwrap();
+        manager.add_row("users", "2").unwrap();
+        manager.add_row("orders", "10;1").unwrap();
+        manager.close_db(true).unwrap();
+
+        manager.read_db_from_directory(dir, name).unwrap();
+        manager.add_row("notes", "first").unwrap();
+        manager.add_row("users", "3").unwrap();
+        let unloaded = manager.database.borrow().as_ref().unwrap().unloaded_tables.borrow().clone();
+        assert_eq!(unloaded, ["orders"]);
+        manager.close_db(true).unwrap();
+
+        // the rows of orders no longer match their checksum
+        let path = location.join(name);
+        let mut pager = Pager::open(path.to_str().unwrap()).unwrap();
+        let (_, directory) = DatabaseDTO::read_catalog(&mut pager).unwrap();
+        let rows_page = directory.iter().find(|pages| pages.name == "orders").unwrap().rows_page;
+        drop(pager);
+        let mut data = std::fs::read(&path).unwrap();
+        data[rows_page as usize * PAGE_SIZE + PAGE_HEADER_SIZE + 9] ^= 0xFF;
+        std::fs::write(&path, data).unwrap();
+
+        // what needs orders fails, the rest goes on
+        manager.read_db_from_directory(dir, name).unwrap();
+        assert!(manager.delete_row("users", 0).unwrap_err().contains("doesn't match its checksum"));
+        assert!(manager.update_row("users", 0, "5").unwrap_err().contains("doesn't match its checksum"));
+        assert!(manager.delete_table("users").is_err());
+        manager.add_row("users", "4").unwrap();
+        manager.add_row("notes", "second").unwrap();
+        assert_eq!(manager.get_table("users").unwrap().get_rows().len(), 4);
+        drop_test_db(manager, name);
+    }
 }
\ No newline at end of file
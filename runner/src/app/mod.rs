@@ -2,6 +2,8 @@ use std::ops::Deref;
 
 use db_manager::db_manager::DatabaseManager;
 use core::{self, table::Table};
+use core::index::format_memory_size;
+use core::stats::TableProfile;
 
 pub enum Action {
     Tick,
@@ -24,6 +26,8 @@ impl Default for DatabaseState {
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum ClosedDatabaseAppState {
     ActiveHood(String),
+    // the report of `check`
+    ActiveInfo(String),
     #[default]
     None
 }
@@ -31,9 +35,17 @@ pub enum ClosedDatabaseAppState {
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum OpenedDatabaseAppState {
     ActiveHood(String),
+    // a message that isn't an error, e.g. the list of foreign keys
+    ActiveInfo(String),
     ActiveMenu,
     ActiveTable,
     ActiveJoinResult,
+    // the buffer holds the query to look for in the displayed table
+    ActiveSearch,
+    // the plan of a query drawn as a tree
+    ActivePlan(String),
+    // the profile the last `stats` took
+    ActiveStats,
     #[default]
     None
 }
@@ -50,7 +62,13 @@ pub struct App {
     selected_row: usize,
     selected_column: usize,
 
-    join_result: Option<Table>
+    join_result: Option<Table>,
+
+    // rows of the displayed table the last search found, best first, and which of them is selected
+    search_matches: Vec<usize>,
+    search_match: usize,
+
+    stats: Option<TableProfile>,
 }
 
 impl App {
@@ -93,6 +111,18 @@ impl App {
             },
         }
     }
+    pub fn check_database(&mut self, database_dir_path: String, database_name: String, salvage_name: Option<String>) {
+        let result = self.database_manager.check_db(&database_dir_path, &database_name, salvage_name.as_deref());
+        match result {
+            Ok(report) => {
+                self.database_state = DatabaseState::Closed(ClosedDatabaseAppState::ActiveInfo(report.to_string()));
+                self.clear_buffer();
+            },
+            Err(e) => {
+                self.opening_database_error(e);
+            },
+        }
+    }
     pub fn close_database(&mut self, need_to_save: bool) {
         let result = self.database_manager.close_db(need_to_save);
         match result {
@@ -116,14 +146,22 @@ impl App {
         }
     }
 
-    pub fn create_table(&mut self, table_name: String, columns: String, data_types: String) {
+    pub fn create_table(&mut self, table_name: String, columns: String, data_types: String, primary_key: Option<String>, unique: Vec<String>) {
         let column_names = columns.split_terminator(';').collect::<Vec<&str>>();
         let column_data = data_types.split_terminator(';').collect::<Vec<&str>>();
-
-        let result = self.database_manager.create_table(
+        let primary_key = primary_key.as_deref()
+            .map(|primary_key| primary_key.split_terminator(';').map(|column| column.trim()).collect::<Vec<&str>>())
+            .unwrap_or_default();
+        let unique = unique.iter()
+            .map(|columns| columns.split_terminator(';').map(|column| column.trim()).collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+
+        let result = self.database_manager.create_table_with_keys(
             table_name.deref(),
             column_names, 
-            column_data
+            column_data,
+            primary_key,
+            unique
         );
         match result {
             Ok(_) => {
@@ -155,6 +193,10 @@ impl App {
         self.database_state = DatabaseState::Closed(ClosedDatabaseAppState::ActiveHood(error));
         self.clear_buffer();
     }
+    pub fn opened_database_info(&mut self, info: String) {
+        self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveInfo(info));
+        self.clear_buffer();
+    }
     pub fn opened_database_error(&mut self, error: String) {
         self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveHood(error));
         self.reset_column();
@@ -173,6 +215,9 @@ impl App {
     pub fn activete_opened_database_active_join_result(&mut self) {
         self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveJoinResult)
     }
+    pub fn activete_opened_database_search(&mut self) {
+        self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveSearch)
+    }
 
     pub fn release_buffer(&mut self) -> String {
         let result = self.buffer.clone();
@@ -192,8 +237,21 @@ impl App {
         self.buffer.pop();
     }
 
+    // the tables followed by the views
     pub fn get_table_list(&self) -> Vec<String> {
-        self.database_manager.get_table_list()
+        let mut table_list = self.database_manager.get_table_list();
+        table_list.extend(self.database_manager.get_view_list());
+        table_list
+    }
+    pub fn get_view_list(&self) -> Vec<String> {
+        self.database_manager.get_view_list()
+    }
+    // the markers of the views in `get_table_list`, `V`, `MV` or `MV stale`, and none for the tables
+    pub fn get_table_markers(&self) -> Vec<Option<String>> {
+        self.get_table_list()
+            .iter()
+            .map(|name| self.database_manager.get_view_marker(name))
+            .collect()
     }
     pub fn get_table_count(&self) -> usize {
         self.get_table_list().len()
@@ -221,13 +279,14 @@ impl App {
     }
 
     pub fn show_table(&mut self) {
-        self.displayed_table = self.selected_table
+        self.displayed_table = self.selected_table;
+        self.search_matches.clear();
     }
 
     //Selected cell row
     pub fn selsect_next_row(&mut self) {
         if let Some(res) = self.selected_row.checked_add(1) {
-            if res < self.get_current_table().unwrap().get_rows().len() {
+            if res < self.get_current_table().map(|table| table.get_rows().len()).unwrap_or_default() {
                 self.selected_row = res;
             }
         }
@@ -247,7 +306,7 @@ impl App {
     //Selected cell column
     pub fn selsect_next_column(&mut self) {
         if let Some(res) = self.selected_column.checked_add(1) {
-            if res < self.get_current_table().unwrap().get_columns().len() {
+            if res < self.get_current_table().map(|table| table.get_columns().len()).unwrap_or_default() {
                 self.selected_column = res;
             }
         }
@@ -269,9 +328,15 @@ impl App {
     /////////////////////////
 
 
+    // a view is evaluated anew every time and a table is read from the file when first shown, so
+    // either may fail to open
     pub fn get_current_table(&self) -> Result<core::table::Table, String> {
         if self.get_table_count() > 0 {
-            Ok(self.database_manager.get_table(&self.get_table_list()[self.displayed_table]).unwrap())
+            let name = &self.get_table_list()[self.displayed_table];
+            if self.get_view_list().contains(name) {
+                return self.database_manager.get_view(name);
+            }
+            self.database_manager.get_table(name)
         } else {
             Err("Whoops, no tables in this database :(".to_owned())
         }
@@ -288,6 +353,148 @@ impl App {
             },
         }
     }
+    // adds the rows of the file, one row literal per line, all of them or none
+    pub fn import_rows(&mut self, table_name: String, file_path: String) {
+        let text = match std::fs::read_to_string(&file_path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.opened_database_error(format!("Can't read {}: {}", file_path, e));
+                return;
+            },
+        };
+        match self.database_manager.import_rows(&table_name, &text) {
+            Ok(added) => self.opened_database_info(format!("{} rows are imported into {}", added, table_name)),
+            Err(e) => self.opened_database_error(e),
+        }
+    }
+    pub fn update_row(&mut self, table_name: String, raw_index_value: String, raw_values: String) {
+        let index = match raw_index_value.parse::<u64>() {
+            Ok(index) => index,
+            Err(e) => {
+                self.opened_database_error(e.to_string());
+                return;
+            },
+        };
+
+        let result = self.database_manager.update_row(&table_name, index, &raw_values);
+        match result {
+            Ok(_) => {
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::None)
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    pub fn add_column(&mut self, table_name: String, column: String, declaration: String, raw_position: Option<String>) {
+        let position = match raw_position.map(|raw_position| raw_position.parse::<usize>()).transpose() {
+            Ok(position) => position,
+            Err(e) => {
+                self.opened_database_error(e.to_string());
+                return;
+            },
+        };
+        let result = self.database_manager.add_column(&table_name, &column, &declaration, position);
+        self.finish_alter(result);
+    }
+    pub fn drop_column(&mut self, table_name: String, column: String) {
+        let result = self.database_manager.drop_column(&table_name, &column);
+        self.finish_alter(result);
+    }
+    pub fn reorder_columns(&mut self, table_name: String, columns: String) {
+        let result = self.database_manager.reorder_columns(&table_name, columns.split_terminator(';').collect());
+        self.finish_alter(result);
+    }
+    // the report is shown when it's a dry run or some rows failed to convert
+    pub fn retype_column(&mut self, table_name: String, column: String, declaration: String, on_failure: Option<String>, dry_run: bool) {
+        let on_failure = on_failure.unwrap_or("abort".to_owned());
+        match self.database_manager.retype_column(&table_name, &column, &declaration, &on_failure, dry_run) {
+            Ok(report) if dry_run || !report.is_clean() => {
+                self.opened_database_info(report.to_string());
+            },
+            result => self.finish_alter(result.map(|_| ())),
+        }
+    }
+    pub fn rename_column(&mut self, table_name: String, column: String, new_name: String) {
+        let result = self.database_manager.rename_column(&table_name, &column, &new_name);
+        self.finish_alter(result);
+    }
+    fn finish_alter(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(_) => {
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::None)
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    pub fn add_foreign_key(&mut self, table_name: String, columns: String, referenced_table_name: String, referenced_columns: String, on_delete: String) {
+        let result = self.database_manager.add_foreign_key(
+            &table_name,
+            columns.split_terminator(';').collect(),
+            &referenced_table_name,
+            referenced_columns.split_terminator(';').collect(),
+            &on_delete
+        );
+        match result {
+            Ok(_) => {
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::None)
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    pub fn show_foreign_keys(&mut self) {
+        match self.database_manager.get_foreign_keys() {
+            Ok(foreign_keys) if foreign_keys.is_empty() => {
+                self.opened_database_info("There are no foreign keys".to_owned());
+            },
+            Ok(foreign_keys) => {
+                let foreign_keys = foreign_keys.iter()
+                    .map(|foreign_key| foreign_key.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                self.opened_database_info(foreign_keys);
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    pub fn create_index(&mut self, table_name: String, index_name: String, columns: String, kind: String) {
+        let result = self.database_manager.create_index(&table_name, &index_name, columns.split_terminator(';').collect(), &kind);
+        self.finish_alter(result);
+    }
+    pub fn drop_index(&mut self, table_name: String, index_name: String) {
+        let result = self.database_manager.drop_index(&table_name, &index_name);
+        self.finish_alter(result);
+    }
+    // the indexes of the table, or how much memory the indexes of every table take
+    pub fn show_indexes(&mut self, table_name: Option<String>) {
+        let result = match table_name {
+            Some(table_name) => self.database_manager.get_indexes(&table_name).map(|indexes| match indexes.is_empty() {
+                true => format!("Table {} has no indexes", table_name),
+                false => indexes.iter().map(|index| index.to_string()).collect::<Vec<String>>().join("\n"),
+            }),
+            None => self.database_manager.get_indexes_memory_sizes().map(|sizes| match sizes.is_empty() {
+                true => "There are no indexes".to_owned(),
+                false => sizes.iter()
+                    .map(|(table_name, size)| format!("{}: {}", table_name, format_memory_size(*size)))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            }),
+        };
+        match result {
+            Ok(info) => {
+                self.opened_database_info(info);
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
     pub fn delete_row(&mut self, table_name: String, raw_index_value: String) {
         let parsing_result = raw_index_value.parse::<u64>();
         match &parsing_result {
@@ -343,6 +550,150 @@ impl App {
         Ok(self.join_result.clone().unwrap())
     }
 
+    // shows the profile of the table or view, or writes it to `export_path` as CSV
+    pub fn show_stats(&mut self, name: String, export_path: Option<String>) {
+        if let Some(path) = export_path {
+            match self.database_manager.export_stats(&name, &path) {
+                Ok(()) => self.opened_database_info(format!("Statistics of {} are written to {}", name, path)),
+                Err(e) => self.opened_database_error(e),
+            }
+            return;
+        }
+        match self.database_manager.get_stats(&name) {
+            Ok(profile) => {
+                self.stats = Some(profile);
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveStats);
+                self.clear_buffer();
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    pub fn get_stats(&self) -> Option<TableProfile> {
+        self.stats.clone()
+    }
+
+    // `query` is a view or a table, or the two tables and the column of a join
+    pub fn explain(&mut self, query: Vec<String>) {
+        let result = match query.as_slice() {
+            [name] => self.database_manager.explain(name),
+            [lhs_table_name, rhs_table_name, column] => self.database_manager.explain_join(lhs_table_name, rhs_table_name, column),
+            _ => Err("a plan is made for a view, a table or a join of two tables".to_owned()),
+        };
+        match result {
+            Ok(plan) => {
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActivePlan(plan.to_string()));
+                self.clear_buffer();
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+
+    // shown like a join result, the best matches first
+    pub fn search(&mut self, table_name: String, query: String) {
+        let result = self.database_manager.search(&table_name, &query);
+        match result {
+            Ok(result) => {
+                self.join_result = Some(result);
+                self.reset_row();
+                self.reset_column();
+                self.database_state = DatabaseState::Opened(OpenedDatabaseAppState::ActiveJoinResult)
+            },
+            Err(e) => {
+                self.opened_database_error(e);
+            },
+        }
+    }
+    // selects the row of the displayed table matching the query best, by its full-text indexes or,
+    // for a table without them, by the cells containing the query regardless of case
+    pub fn search_displayed_table(&mut self, query: String) {
+        let table = match self.get_current_table() {
+            Ok(table) => table,
+            Err(e) => {
+                self.opened_database_error(e);
+                return;
+            },
+        };
+        self.search_matches = match table.search(&query) {
+            Ok(found) => found.into_iter().map(|(position, _)| position).collect(),
+            Err(_) => {
+                let query = query.to_lowercase();
+                table.get_rows()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, row)| row.get_values()
+                        .iter()
+                        .filter_map(|cell| cell.get_value().to_raw_value())
+                        .any(|cell| cell.to_lowercase().contains(&query)))
+                    .map(|(position, _)| position)
+                    .collect()
+            },
+        };
+        self.search_match = 0;
+        self.clear_buffer();
+        match self.search_matches.first() {
+            Some(&position) => {
+                self.selected_row = position;
+                self.activete_opened_database_active_table();
+            },
+            None => self.opened_database_info(format!("Nothing in {} matches {}", table.get_name(), query)),
+        }
+    }
+    // selects the next row the last search found, going around after the last one
+    pub fn select_next_match(&mut self) {
+        let rows_count = self.get_current_table().map(|table| table.get_rows().len()).unwrap_or_default();
+        // the rows may have changed since the search
+        self.search_matches.retain(|&position| position < rows_count);
+        if !self.search_matches.is_empty() {
+            self.search_match = (self.search_match + 1) % self.search_matches.len();
+            self.selected_row = self.search_matches[self.search_match];
+        }
+    }
+
+    // `source` is either a table or the two tables and the column to join them on; the view is
+    // materialized right away if `materialize` gives its refresh mode
+    pub fn create_view(&mut self, name: String, source: Vec<String>, columns: Option<String>, filter: Option<String>, order: Option<String>, materialize: Option<String>) {
+        let columns = columns.as_deref().unwrap_or_default().split_terminator(';').collect();
+        let result = match source.as_slice() {
+            [table_name] => self.database_manager.create_view(&name, table_name, columns, filter.as_deref(), order.as_deref()),
+            [lhs_table_name, rhs_table_name, column] => self.database_manager.create_join_view(
+                &name,
+                lhs_table_name,
+                rhs_table_name,
+                column,
+                columns,
+                filter.as_deref(),
+                order.as_deref()
+            ),
+            _ => Err("a view is made of a table or a join of two tables".to_owned()),
+        };
+        let result = match (result, materialize) {
+            (Ok(()), Some(refresh)) => self.database_manager.materialize_view(&name, &refresh).inspect_err(|_| {
+                let _ = self.database_manager.drop_view(&name);
+            }),
+            (result, _) => result,
+        };
+        self.finish_alter(result);
+    }
+    pub fn drop_view(&mut self, name: String) {
+        let result = self.database_manager.drop_view(&name);
+        self.finish_alter(result);
+    }
+    pub fn materialize_view(&mut self, name: String, refresh: String) {
+        let result = self.database_manager.materialize_view(&name, &refresh);
+        self.finish_alter(result);
+    }
+    pub fn refresh_view(&mut self, name: String) {
+        let result = self.database_manager.refresh_view(&name);
+        self.finish_alter(result);
+    }
+    pub fn rename_table(&mut self, table_name: String, new_name: String) {
+        let result = self.database_manager.rename_table(&table_name, &new_name);
+        self.finish_alter(result);
+    }
     pub fn rename_row(&mut self, table_name: String, columns: String) {
         let column_names = columns.split_terminator(';').collect::<Vec<&str>>().iter().map(|s| s.to_owned().to_owned()).collect();
         let result = self.database_manager.rename(&table_name, column_names);
@@ -1,4 +1,4 @@
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, ArgGroup, Command};
 
 pub fn get_parser() -> Command {
     Command::new("database")
@@ -37,6 +37,16 @@ pub fn get_parser() -> Command {
                             .conflicts_with("database")
                             .required_unless_present("database")
                             .action(ArgAction::Set),
+                        Arg::new("primary_key")
+                            .short('k')
+                            .conflicts_with("database")
+                            .required(false)
+                            .action(ArgAction::Set),
+                        Arg::new("unique")
+                            .short('u')
+                            .conflicts_with("database")
+                            .required(false)
+                            .action(ArgAction::Append),
                     ]),
 
                 Command::new("delete")
@@ -76,6 +86,21 @@ pub fn get_parser() -> Command {
                             .action(ArgAction::Set),
                     ]),
 
+                Command::new("check")
+                    .args([
+                        Arg::new("database_path")
+                            .short('p')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("database_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("salvage_name")
+                            .short('s')
+                            .action(ArgAction::Set),
+                    ]),
+
                 Command::new("close")
                     .args([
                         Arg::new("save")
@@ -96,6 +121,34 @@ pub fn get_parser() -> Command {
                             .action(ArgAction::Set)
                     ]),
                 
+                Command::new("import")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("file_path")
+                            .short('f')
+                            .required(true)
+                            .action(ArgAction::Set)
+                    ]),
+
+                Command::new("update")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("row_index")
+                            .short('i')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("row_value")
+                            .short('r')
+                            .required(true)
+                            .action(ArgAction::Set)
+                    ]),
+
                 Command::new("remove")
                     .args([
                         Arg::new("table_name")
@@ -116,6 +169,170 @@ pub fn get_parser() -> Command {
                             .action(ArgAction::Set),
                         Arg::new("table_column_names")
                             .short('c')
+                            .conflicts_with("new_table_name")
+                            .required_unless_present("new_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("new_table_name")
+                            .short('t')
+                            .action(ArgAction::Set),
+                    ]),
+
+                Command::new("alter")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("add_column")
+                            .short('a')
+                            .requires("column_type")
+                            .action(ArgAction::Set),
+                        Arg::new("column_type")
+                            .short('v')
+                            .action(ArgAction::Set),
+                        Arg::new("position")
+                            .short('p')
+                            .requires("add_column")
+                            .action(ArgAction::Set),
+                        Arg::new("drop_column")
+                            .short('d')
+                            .action(ArgAction::Set),
+                        Arg::new("order")
+                            .short('o')
+                            .action(ArgAction::Set),
+                        Arg::new("retype_column")
+                            .short('m')
+                            .requires("column_type")
+                            .action(ArgAction::Set),
+                        Arg::new("on_failure")
+                            .short('f')
+                            .requires("retype_column")
+                            .action(ArgAction::Set),
+                        Arg::new("dry_run")
+                            .short('y')
+                            .requires("retype_column")
+                            .action(ArgAction::SetTrue),
+                        Arg::new("rename_column")
+                            .short('r')
+                            .requires("new_name")
+                            .action(ArgAction::Set),
+                        Arg::new("new_name")
+                            .short('t')
+                            .requires("rename_column")
+                            .action(ArgAction::Set),
+                    ])
+                    .group(
+                        ArgGroup::new("operation")
+                            .args(["add_column", "drop_column", "order", "retype_column", "rename_column"])
+                            .required(true)
+                    ),
+
+                Command::new("reference")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("table_column_names")
+                            .short('c')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("referenced_table_name")
+                            .short('r')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("referenced_column_names")
+                            .short('k')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("on_delete")
+                            .short('o')
+                            .default_value("restrict")
+                            .action(ArgAction::Set),
+                    ]),
+
+                Command::new("references"),
+
+                Command::new("index")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("index_name")
+                            .short('i')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("table_column_names")
+                            .short('c')
+                            .conflicts_with("drop")
+                            .action(ArgAction::Set),
+                        Arg::new("kind")
+                            .short('k')
+                            .requires("table_column_names")
+                            .default_value("btree")
+                            .action(ArgAction::Set),
+                        Arg::new("drop")
+                            .short('d')
+                            .action(ArgAction::SetTrue),
+                    ])
+                    .group(
+                        ArgGroup::new("operation")
+                            .args(["table_column_names", "drop"])
+                            .required(true)
+                    ),
+
+                Command::new("indexes")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .action(ArgAction::Set),
+                    ]),
+
+                Command::new("stats")
+                    .args([
+                        Arg::new("name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("export_path")
+                            .short('e')
+                            .action(ArgAction::Set),
+                    ]),
+
+                Command::new("explain")
+                    .args([
+                        Arg::new("name")
+                            .short('n')
+                            .conflicts_with("left_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("left_table_name")
+                            .short('l')
+                            .requires_all(["right_table_name", "column_name"])
+                            .action(ArgAction::Set),
+                        Arg::new("right_table_name")
+                            .short('r')
+                            .requires("left_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("column_name")
+                            .short('c')
+                            .requires("left_table_name")
+                            .action(ArgAction::Set),
+                    ])
+                    .group(
+                        ArgGroup::new("query")
+                            .args(["name", "left_table_name"])
+                            .required(true)
+                    ),
+
+                Command::new("search")
+                    .args([
+                        Arg::new("table_name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("query")
+                            .short('q')
                             .required(true)
                             .action(ArgAction::Set),
                     ]),
@@ -136,6 +353,60 @@ pub fn get_parser() -> Command {
                             .required(true)
                             .action(ArgAction::Set),
                     ]),
+
+                Command::new("view")
+                    .args([
+                        Arg::new("name")
+                            .short('n')
+                            .required(true)
+                            .action(ArgAction::Set),
+                        Arg::new("table_name")
+                            .short('s')
+                            .conflicts_with("left_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("left_table_name")
+                            .short('l')
+                            .requires_all(["right_table_name", "join_column"])
+                            .action(ArgAction::Set),
+                        Arg::new("right_table_name")
+                            .short('r')
+                            .requires("left_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("join_column")
+                            .short('j')
+                            .requires("left_table_name")
+                            .action(ArgAction::Set),
+                        Arg::new("table_column_names")
+                            .short('c')
+                            .conflicts_with("drop")
+                            .action(ArgAction::Set),
+                        Arg::new("filter")
+                            .short('w')
+                            .conflicts_with("drop")
+                            .action(ArgAction::Set),
+                        Arg::new("order")
+                            .short('o')
+                            .conflicts_with("drop")
+                            .action(ArgAction::Set),
+                        Arg::new("materialize")
+                            .short('m')
+                            .conflicts_with("drop")
+                            .action(ArgAction::Set),
+                        Arg::new("refresh")
+                            .short('f')
+                            .conflicts_with_all(["table_name", "left_table_name", "materialize", "drop"])
+                            .action(ArgAction::SetTrue),
+                        Arg::new("drop")
+                            .short('d')
+                            .conflicts_with_all(["table_name", "left_table_name"])
+                            .action(ArgAction::SetTrue),
+                    ])
+                    .group(
+                        ArgGroup::new("source")
+                            .args(["table_name", "left_table_name", "materialize", "refresh", "drop"])
+                            .multiple(true)
+                            .required(true)
+                    ),
             ])
 }
 
@@ -165,5 +436,143 @@ mod tests {
         assert!(command.try_get_matches_from_mut(&args).is_ok());
         let args = vec!["database", "close", "-s", "\"\""];
         assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "create", "-t", "-n", "users", "-c", "id;email", "-v", "IntValue;StringValue", "-k", "id", "-u", "email", "-u", "id;email"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("create", arg)) => {
+                assert_eq!(arg.get_one::<String>("primary_key").unwrap(), "id");
+                assert_eq!(arg.get_many::<String>("unique").unwrap().collect::<Vec<&String>>(), ["email", "id;email"]);
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "create", "-d", "-n", "\"\"", "-p", "\"\"", "-k", "id"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "alter", "-n", "users", "-a", "age", "-v", "IntValue default(18)", "-p", "1"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("alter", arg)) => {
+                assert_eq!(arg.get_one::<String>("add_column").unwrap(), "age");
+                assert_eq!(arg.get_one::<String>("position").unwrap(), "1");
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "alter", "-n", "users"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "alter", "-n", "users", "-a", "age"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "alter", "-n", "users", "-d", "age", "-o", "id;name"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "rename", "-n", "users", "-t", "customers"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("rename", arg)) => {
+                assert_eq!(arg.get_one::<String>("new_table_name").unwrap(), "customers");
+                assert!(arg.get_one::<String>("table_column_names").is_none());
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "rename", "-n", "users", "-t", "customers", "-c", "id"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "rename", "-n", "users"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "alter", "-n", "users", "-m", "age", "-v", "IntValue", "-f", "null", "-y"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("alter", arg)) => {
+                assert_eq!(arg.get_one::<String>("on_failure").unwrap(), "null");
+                assert!(arg.get_flag("dry_run"));
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "alter", "-n", "users", "-d", "age", "-y"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "alter", "-n", "users", "-r", "name", "-t", "first_name"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "update", "-n", "users", "-i", "0", "-r", "1;a@ukr.net"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "import", "-n", "users", "-f", "users.rows"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("import", arg)) => assert_eq!(arg.get_one::<String>("file_path").unwrap(), "users.rows"),
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "import", "-n", "users"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "reference", "-n", "orders", "-c", "user_id", "-r", "users", "-k", "id"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("reference", arg)) => {
+                assert_eq!(arg.get_one::<String>("on_delete").unwrap(), "restrict")
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "references"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "view", "-n", "adults", "-s", "users", "-w", "age >= 18", "-o", "age desc;name"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("view", arg)) => {
+                assert_eq!(arg.get_one::<String>("filter").unwrap(), "age >= 18");
+                assert!(!arg.get_flag("drop"));
+            },
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "view", "-n", "orders_of_users", "-l", "users", "-r", "orders", "-j", "user_id"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "view", "-n", "orders_of_users", "-l", "users", "-r", "orders"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "view", "-n", "adults", "-s", "users", "-l", "users", "-r", "orders", "-j", "user_id"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "view", "-n", "adults", "-d", "-w", "age > 1"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "view", "-n", "adults"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "view", "-n", "adults", "-s", "users", "-m", "incremental"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "view", "-n", "adults", "-m", "on_demand"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "view", "-n", "adults", "-f"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "view", "-n", "adults", "-f", "-m", "incremental"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "view", "-n", "adults", "-d", "-s", "users"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "index", "-n", "users", "-i", "by_email", "-c", "email"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "index", "-n", "users", "-i", "by_email", "-d"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "index", "-n", "users", "-i", "by_email"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "indexes", "-n", "users"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "index", "-n", "users", "-i", "by_email", "-c", "email", "-k", "hash"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("index", arg)) => assert_eq!(arg.get_one::<String>("kind").unwrap(), "hash"),
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "indexes"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "search", "-n", "notes", "-q", "feed the cat"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("search", arg)) => assert_eq!(arg.get_one::<String>("query").unwrap(), "feed the cat"),
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "search", "-n", "notes"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "explain", "-n", "adults"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "explain", "-l", "users", "-r", "orders", "-c", "user_id"];
+        assert!(command.try_get_matches_from_mut(&args).is_ok());
+        let args = vec!["database", "explain", "-l", "users", "-r", "orders"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "explain"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "stats", "-n", "users", "-e", "users.csv"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("stats", arg)) => assert_eq!(arg.get_one::<String>("export_path").unwrap(), "users.csv"),
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "stats"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
+        let args = vec!["database", "check", "-p", "/tmp", "-n", "shop.db", "-s", "shop_salvaged.db"];
+        match command.try_get_matches_from_mut(args).unwrap().subcommand() {
+            Some(("check", arg)) => assert_eq!(arg.get_one::<String>("salvage_name").unwrap(), "shop_salvaged.db"),
+            _ => unreachable!(),
+        }
+        let args = vec!["database", "check", "-p", "/tmp"];
+        assert!(command.try_get_matches_from_mut(&args).is_err());
     }   
 }
\ No newline at end of file
@@ -26,6 +26,9 @@ use ratatui::widgets::Row;
 use ratatui::widgets::Table;
 use ratatui::widgets::Wrap;
 
+use core::stats::{Histogram, TableProfile};
+use core::types::enum_value::EnumValue;
+
 use crate::app::App;
 use crate::app::ClosedDatabaseAppState;
 use crate::app::DatabaseState;
@@ -87,9 +90,106 @@ fn render_screen_hood(f: &mut Frame, layout: Rect, color: Color, text: String) {
     )
 }
 
-fn render_active_menu(f: &mut Frame, layout: Rect, color: Color, db_name: String, table_names: Vec<String>, index: usize) {
+fn render_search_prompt(f: &mut Frame, layout: Rect, color: Color, text: String) {
+    f.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(
+                        Line::from(vec![
+                            Span::styled(" Search ", Style::default().fg(color)),
+                            Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
+                            Span::styled("Enter", Style::default().fg(Color::White)),
+                            Span::styled(" to jump to the best match, ", Style::default().fg(Color::DarkGray)),
+                            Span::styled("n", Style::default().fg(Color::White)),
+                            Span::styled(" for the next one, ", Style::default().fg(Color::DarkGray)),
+                            Span::styled("Esc", Style::default().fg(Color::White)),
+                            Span::styled(" to leave) ", Style::default().fg(Color::DarkGray)),
+                        ])
+                    )
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick),
+            )
+            .style(Style::default().fg(color))
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center),
+            layout,
+    )
+}
+
+// a line per column of the profiled table
+fn render_stats(f: &mut Frame, layout: Rect, color: Color, profile: TableProfile) {
+    let header = ["column", "type", "nulls", "distinct", "min", "max", "mean", "stddev", "most frequent", "lengths", "picture sizes"];
+    let number = |value: Option<f64>| value.map(|value| format!("{:.2}", value)).unwrap_or_default();
+    let histogram = |value: &Option<Histogram>| value.as_ref().map(|value| value.to_string()).unwrap_or_default();
+    let lines = profile.columns.iter()
+        .map(|column| vec![
+            column.name.clone(),
+            column.ty.clone(),
+            column.nulls.to_string(),
+            column.distinct.to_string(),
+            column.min.clone().unwrap_or_default(),
+            column.max.clone().unwrap_or_default(),
+            number(column.mean),
+            number(column.stddev),
+            column.get_most_frequent(),
+            histogram(&column.lengths),
+            histogram(&column.picture_sizes),
+        ])
+        .collect::<Vec<Vec<String>>>();
+    let widths = header.iter()
+        .enumerate()
+        .map(|(position, title)| {
+            let width = lines.iter().map(|line| line[position].chars().count()).max().unwrap_or_default();
+            Constraint::Length(width.max(title.len()) as u16)
+        })
+        .collect::<Vec<Constraint>>();
+
+    let table =
+        Table::new(lines.into_iter().map(Row::new).collect::<Vec<Row>>())
+            .header(Row::new(header.to_vec()).style(Style::default().fg(color)))
+            .block(
+                Block::default()
+                    .title(format!(" Statistics of {}: {} rows ", profile.table, profile.rows))
+                    .title_alignment(Alignment::Left)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick)
+                    .border_style(Style::default().fg(color)),
+            )
+            .widths(&widths)
+            .column_spacing(3);
+
+    f.render_widget(table, layout);
+}
+
+// the lines keep their indentation, which draws the tree
+fn render_plan(f: &mut Frame, layout: Rect, color: Color, plan: String) {
+    f.render_widget(
+        Paragraph::new(plan)
+            .block(
+                Block::default()
+                    .title(Span::styled(" Plan ", Style::default().fg(color)))
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick)
+                    .border_style(Style::default().fg(color)),
+            )
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false })
+            .alignment(Alignment::Left),
+            layout,
+    )
+}
+
+fn render_active_menu(f: &mut Frame, layout: Rect, color: Color, db_name: String, table_names: Vec<String>, markers: Vec<Option<String>>, index: usize) {
     let mut lines: Vec<Line> = Vec::new();
-    for (i, table_name) in table_names.iter().enumerate() {
+    for (i, (table_name, marker)) in table_names.iter().zip(markers).enumerate() {
+        // views are marked like `adults [V]` or `adults [MV stale]`
+        let table_name = match marker {
+            Some(marker) => format!("{} [{}]", table_name, marker),
+            None => table_name.clone(),
+        };
         if i == index {
             lines.push(Line::from(Span::styled(table_name, Style::default().fg(Color::Cyan).bold())))
         } else {
@@ -141,10 +241,16 @@ fn render_active_table(f: &mut Frame, layout: Rect, color: Color, table_result:
     let mut header_content: Vec<String> = Vec::new();
     let mut widths = Vec::new();
     for column_header in table.get_columns() {
-        widths.push(Constraint::Length(table.get_max_column_len(&column_header) as u16));
-        header_content.push(column_header);
+        // key columns are marked like `id [PK]`
+        let header = match table.get_scheme().get_key_marker(&column_header) {
+            Some(marker) => format!("{} [{}]", column_header, marker),
+            None => column_header.clone(),
+        };
+        widths.push(Constraint::Length(table.get_max_column_len(&column_header).max(header.len()) as u16));
+        header_content.push(header);
     }
     let table_header = Row::new(header_content);
+    let table_title = get_table_title(&table, selected_column);
 
     let mut table_content = Vec::new();
     
@@ -176,6 +282,27 @@ fn render_active_table(f: &mut Frame, layout: Rect, color: Color, table_result:
                     },
                     core::types::ValueType::Email(email) => {
                         email.get_value().to_string()
+                    },
+                    core::types::ValueType::Bool(bool) => {
+                        bool.get_value().to_string()
+                    },
+                    core::types::ValueType::Decimal(decimal) => {
+                        decimal.to_string()
+                    },
+                    core::types::ValueType::IntInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::RealInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::DateInterval(interval) => {
+                        interval.to_string()
+                    },
+                    core::types::ValueType::Enum(enumeration) => {
+                        enumeration.get_value().to_owned()
+                    },
+                    core::types::ValueType::Null(_) => {
+                        "NULL".to_owned()
                     }
                 };
             
@@ -198,7 +325,7 @@ fn render_active_table(f: &mut Frame, layout: Rect, color: Color, table_result:
             .header(table_header)
             .block(
                 Block::default()
-                    .title(format!(" {} ", table.get_name()))
+                    .title(table_title)
                     .title_alignment(Alignment::Left)
                     .borders(Borders::ALL)
                     .border_type(BorderType::Thick)
@@ -210,6 +337,31 @@ fn render_active_table(f: &mut Frame, layout: Rect, color: Color, table_result:
     f.render_widget(table, layout);
 }
 
+// enum columns get a hint with their allowed values while selected, columns with constraints
+// list them
+fn get_table_title(table: &core::table::Table, selected_column: usize) -> String {
+    let scheme = table.get_scheme();
+    match scheme.get_types().get(selected_column) {
+        Some(ty) if *ty == EnumValue::get_type_name() => format!(
+            " {} | {}: {} ",
+            table.get_name(),
+            scheme.get_columns()[selected_column],
+            scheme.get_column_parameters(selected_column).join(" | "),
+        ),
+        Some(_) if !scheme.get_column_constraints(selected_column).is_empty() => format!(
+            " {} | {}: {} ",
+            table.get_name(),
+            scheme.get_columns()[selected_column],
+            scheme.get_column_constraints(selected_column)
+                .iter()
+                .map(|constraint| constraint.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+        ),
+        _ => format!(" {} ", table.get_name()),
+    }
+}
+
 fn render_default_screen(f: &mut Frame, app: &mut App) {
     if let DatabaseState::Closed(state) = app.get_database_state() {
         let layout = 
@@ -239,6 +391,10 @@ fn render_default_screen(f: &mut Frame, app: &mut App) {
                     render_screen_hood(f, err_layout[1], Color::Red, e);
                 }
             },
+            ClosedDatabaseAppState::ActiveInfo(report) => {
+                render_default_screen_body(f, err_layout[0], Color::White);
+                render_screen_hood(f, err_layout[1], Color::Green, report);
+            },
             ClosedDatabaseAppState::None => {
                 render_default_screen_body(f, layout[0], Color::White);
                 render_screen_hood(f, layout[1], Color::White, "".to_owned());
@@ -277,7 +433,7 @@ fn render_main_screen(f: &mut Frame, app: &mut App) {
 
         match state {
             crate::app::OpenedDatabaseAppState::ActiveHood(e) => {
-                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_selected_table_index());
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
                 if e.is_empty() {
                     render_screen_hood(f, inner_layout[0], Color::Cyan, app.get_buffer());
                     render_active_table(f, inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
@@ -286,23 +442,46 @@ fn render_main_screen(f: &mut Frame, app: &mut App) {
                     render_active_table(f, err_inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
                 }
             },
+            crate::app::OpenedDatabaseAppState::ActiveInfo(info) => {
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
+                render_screen_hood(f, err_inner_layout[0], Color::Green, info);
+                render_active_table(f, err_inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
+            },
             crate::app::OpenedDatabaseAppState::ActiveMenu => {
-                render_active_menu(f, layout[0], Color::Cyan, app.get_database_name(), app.get_table_list(), app.get_selected_table_index());
+                render_active_menu(f, layout[0], Color::Cyan, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
                 render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
                 render_active_table(f, inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
             },
             crate::app::OpenedDatabaseAppState::ActiveTable => {
-                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_selected_table_index());
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
                 render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
                 render_active_table(f, inner_layout[1], Color::Cyan, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
             },
+            crate::app::OpenedDatabaseAppState::ActiveStats => {
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
+                render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
+                match app.get_stats() {
+                    Some(profile) => render_stats(f, inner_layout[1], Color::Green, profile),
+                    None => render_active_table(f, inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index()),
+                }
+            },
+            crate::app::OpenedDatabaseAppState::ActivePlan(plan) => {
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
+                render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
+                render_plan(f, inner_layout[1], Color::Green, plan);
+            },
+            crate::app::OpenedDatabaseAppState::ActiveSearch => {
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
+                render_search_prompt(f, inner_layout[0], Color::Cyan, app.get_buffer());
+                render_active_table(f, inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
+            },
             crate::app::OpenedDatabaseAppState::ActiveJoinResult => {
-                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_selected_table_index());
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
                 render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
                 render_active_table(f, inner_layout[1], Color::Cyan, app.get_join_result_table(), app.get_selected_row_index(), app.get_selected_column_index());
             },
             crate::app::OpenedDatabaseAppState::None => {
-                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_selected_table_index());
+                render_active_menu(f, layout[0], Color::White, app.get_database_name(), app.get_table_list(), app.get_table_markers(), app.get_selected_table_index());
                 render_screen_hood(f, inner_layout[0], Color::White, "".to_owned());
                 render_active_table(f, inner_layout[1], Color::White, app.get_current_table(), app.get_selected_row_index(), app.get_selected_column_index());
             },
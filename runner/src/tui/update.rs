@@ -15,10 +15,8 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
     }
 
     match key_event.code {
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit()
-            }
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit()
         },
         KeyCode::Char('w') => {
             if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveTable) = app.get_database_state() {
@@ -60,15 +58,26 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
             }
         },
         KeyCode::Char('/') => {
-            if let DatabaseState::Closed(ClosedDatabaseAppState::None) = app.get_database_state() {
+            if let DatabaseState::Closed(ClosedDatabaseAppState::None | ClosedDatabaseAppState::ActiveInfo(_)) = app.get_database_state() {
                 app.activete_closed_database_hood();
                 app.clear_buffer();
             }
-            if let DatabaseState::Opened(OpenedDatabaseAppState::None) = app.get_database_state() {
+            if let DatabaseState::Opened(OpenedDatabaseAppState::None | OpenedDatabaseAppState::ActiveInfo(_) | OpenedDatabaseAppState::ActivePlan(_) | OpenedDatabaseAppState::ActiveStats) = app.get_database_state() {
                 app.activete_opened_database_hood();
                 app.clear_buffer();
             }
         },
+        KeyCode::Char('?') => {
+            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveTable) = app.get_database_state() {
+                app.activete_opened_database_search();
+                app.clear_buffer();
+            }
+        },
+        KeyCode::Char('n') => {
+            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveTable) = app.get_database_state() {
+                app.select_next_match()
+            }
+        },
         KeyCode::Char('[') => {
             if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveMenu) = app.get_database_state() {
                 app.selsect_priv_table()
@@ -80,14 +89,18 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
             }
         }
         KeyCode::Esc => {
-            if let DatabaseState::Closed(ClosedDatabaseAppState::ActiveHood(_)) = app.get_database_state() {
+            if let DatabaseState::Closed(ClosedDatabaseAppState::ActiveHood(_) | ClosedDatabaseAppState::ActiveInfo(_)) = app.get_database_state() {
                 app.deactivete_closed_database_hood();
                 app.clear_buffer();
             }
-            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveHood(_)) = app.get_database_state() {
+            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveHood(_) | OpenedDatabaseAppState::ActiveInfo(_) | OpenedDatabaseAppState::ActivePlan(_) | OpenedDatabaseAppState::ActiveStats) = app.get_database_state() {
                 app.deactivete_opened_database_hood();
                 app.clear_buffer();
             }
+            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveSearch) = app.get_database_state() {
+                app.activete_opened_database_active_table();
+                app.clear_buffer();
+            }
         }
         KeyCode::Enter => {
             if let DatabaseState::Closed(ClosedDatabaseAppState::ActiveHood(_)) = app.get_database_state() {
@@ -132,6 +145,13 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                             args.get_one::<String>("database_name").unwrap().to_owned()
                         );
                     },
+                    Some(("check", args)) => {
+                        app.check_database(
+                            args.get_one::<String>("database_path").unwrap().to_owned(),
+                            args.get_one::<String>("database_name").unwrap().to_owned(),
+                            args.get_one::<String>("salvage_name").cloned()
+                        );
+                    },
                     _ => {
                         app.opening_database_error("Unsupported comand for this hood".to_owned());
                     },
@@ -169,7 +189,9 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                             app.create_table(
                                 args.get_one::<String>("name").unwrap().to_owned(),
                                 args.get_one::<String>("table_column_names").unwrap().to_owned(),
-                                args.get_one::<String>("table_types").unwrap().to_owned()
+                                args.get_one::<String>("table_types").unwrap().to_owned(),
+                                args.get_one::<String>("primary_key").cloned(),
+                                args.get_many::<String>("unique").unwrap_or_default().cloned().collect()
                             );
                         }
                         if args.get_flag("database") {
@@ -182,6 +204,19 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                             args.get_one::<String>("row_value").unwrap().to_owned(),
                         );
                     }
+                    Some(("import", args)) => {
+                        app.import_rows(
+                            args.get_one::<String>("table_name").unwrap().to_owned(),
+                            args.get_one::<String>("file_path").unwrap().to_owned(),
+                        );
+                    }
+                    Some(("update", args)) => {
+                        app.update_row(
+                            args.get_one::<String>("table_name").unwrap().to_owned(),
+                            args.get_one::<String>("row_index").unwrap().to_owned(),
+                            args.get_one::<String>("row_value").unwrap().to_owned(),
+                        );
+                    }
                     Some(("delete", args)) => {
                         if args.get_flag("table") {
                             app.delete_table(
@@ -202,9 +237,91 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                         )
                     },
                     Some(("rename", args)) => {
-                        app.rename_row(
+                        if let Some(new_table_name) = args.get_one::<String>("new_table_name") {
+                            app.rename_table(
+                                args.get_one::<String>("table_name").unwrap().to_owned(),
+                                new_table_name.to_owned()
+                            )
+                        } else {
+                            app.rename_row(
+                                args.get_one::<String>("table_name").unwrap().to_owned(),
+                                args.get_one::<String>("table_column_names").unwrap().to_owned()
+                            )
+                        }
+                    },
+                    Some(("alter", args)) => {
+                        let table_name = args.get_one::<String>("table_name").unwrap().to_owned();
+                        if let Some(column) = args.get_one::<String>("add_column") {
+                            app.add_column(
+                                table_name,
+                                column.to_owned(),
+                                args.get_one::<String>("column_type").unwrap().to_owned(),
+                                args.get_one::<String>("position").cloned()
+                            )
+                        } else if let Some(column) = args.get_one::<String>("drop_column") {
+                            app.drop_column(table_name, column.to_owned())
+                        } else if let Some(columns) = args.get_one::<String>("order") {
+                            app.reorder_columns(table_name, columns.to_owned())
+                        } else if let Some(column) = args.get_one::<String>("retype_column") {
+                            app.retype_column(
+                                table_name,
+                                column.to_owned(),
+                                args.get_one::<String>("column_type").unwrap().to_owned(),
+                                args.get_one::<String>("on_failure").cloned(),
+                                args.get_flag("dry_run")
+                            )
+                        } else if let Some(column) = args.get_one::<String>("rename_column") {
+                            app.rename_column(
+                                table_name,
+                                column.to_owned(),
+                                args.get_one::<String>("new_name").unwrap().to_owned()
+                            )
+                        }
+                    },
+                    Some(("reference", args)) => {
+                        app.add_foreign_key(
                             args.get_one::<String>("table_name").unwrap().to_owned(),
-                            args.get_one::<String>("table_column_names").unwrap().to_owned()
+                            args.get_one::<String>("table_column_names").unwrap().to_owned(),
+                            args.get_one::<String>("referenced_table_name").unwrap().to_owned(),
+                            args.get_one::<String>("referenced_column_names").unwrap().to_owned(),
+                            args.get_one::<String>("on_delete").unwrap().to_owned()
+                        )
+                    },
+                    Some(("references", _)) => {
+                        app.show_foreign_keys()
+                    },
+                    Some(("index", args)) => {
+                        let table_name = args.get_one::<String>("table_name").unwrap().to_owned();
+                        let index_name = args.get_one::<String>("index_name").unwrap().to_owned();
+                        match args.get_one::<String>("table_column_names") {
+                            Some(columns) => app.create_index(
+                                table_name,
+                                index_name,
+                                columns.to_owned(),
+                                args.get_one::<String>("kind").unwrap().to_owned()
+                            ),
+                            None => app.drop_index(table_name, index_name),
+                        }
+                    },
+                    Some(("indexes", args)) => {
+                        app.show_indexes(args.get_one::<String>("table_name").cloned())
+                    },
+                    Some(("stats", args)) => {
+                        app.show_stats(
+                            args.get_one::<String>("name").unwrap().to_owned(),
+                            args.get_one::<String>("export_path").cloned()
+                        )
+                    },
+                    Some(("explain", args)) => {
+                        let query = ["name", "left_table_name", "right_table_name", "column_name"].iter()
+                            .filter_map(|id| args.get_one::<String>(id).cloned())
+                            .collect();
+                        app.explain(query)
+                    },
+                    Some(("search", args)) => {
+                        app.search(
+                            args.get_one::<String>("table_name").unwrap().to_owned(),
+                            args.get_one::<String>("query").unwrap().to_owned()
                         )
                     },
                     Some(("join", args)) => {
@@ -214,6 +331,34 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                             args.get_one::<String>("column_name").unwrap().to_owned()
                         )
                     },
+                    Some(("view", args)) => {
+                        let name = args.get_one::<String>("name").unwrap().to_owned();
+                        let materialize = args.get_one::<String>("materialize").cloned();
+                        if args.get_flag("drop") {
+                            app.drop_view(name)
+                        } else if args.get_flag("refresh") {
+                            app.refresh_view(name)
+                        } else if !args.contains_id("table_name") && !args.contains_id("left_table_name") {
+                            app.materialize_view(name, materialize.unwrap())
+                        } else {
+                            let source = match args.get_one::<String>("table_name") {
+                                Some(table_name) => vec![table_name.to_owned()],
+                                None => vec![
+                                    args.get_one::<String>("left_table_name").unwrap().to_owned(),
+                                    args.get_one::<String>("right_table_name").unwrap().to_owned(),
+                                    args.get_one::<String>("join_column").unwrap().to_owned(),
+                                ],
+                            };
+                            app.create_view(
+                                name,
+                                source,
+                                args.get_one::<String>("table_column_names").cloned(),
+                                args.get_one::<String>("filter").cloned(),
+                                args.get_one::<String>("order").cloned(),
+                                materialize
+                            )
+                        }
+                    },
                     _ => {
                         app.opened_database_error("Unsupported comand for this hood".to_owned());
                     },
@@ -226,6 +371,10 @@ pub fn update(app: &mut App, key_event: KeyEvent) {
                 app.show_table();
                 app.activete_opened_database_active_table();
             }
+
+            if let DatabaseState::Opened(OpenedDatabaseAppState::ActiveSearch) = app.get_database_state() {
+                app.search_displayed_table(app.get_buffer());
+            }
         },
         KeyCode::Backspace => {
             app.remove_last_char_from_the_buffer();